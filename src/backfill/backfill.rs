@@ -0,0 +1,182 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use chrono::{Duration, NaiveDateTime};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use crate::error::error::UsgsError;
+use crate::sinks::Sink;
+use crate::windows::windows::{time_windows, ChunkBy};
+use crate::{local_time_as_utc, ConfigureQuery, UsgsClient, UsgsQuery};
+
+/// Progress saved after every completed window.
+#[derive(Serialize, Deserialize, Debug)]
+struct Checkpoint {
+	completed_until: NaiveDateTime,
+}
+
+/// Outcome of a [`Backfill::run`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackfillReport {
+	/// Time the run started from, later than the configured start when resumed.
+	pub resumed_from: NaiveDateTime,
+
+	/// End of the last completed window.
+	pub completed_until: NaiveDateTime,
+
+	/// Number of windows fetched by this run.
+	pub windows: usize,
+
+	/// Number of events sent to the sink by this run.
+	pub events: usize,
+}
+
+/// Downloads a historical time range in windows and feeds the events into
+/// a [`Sink`], resuming after crashes from a checkpoint file.
+///
+/// Windows are fetched one after another (see [`ChunkBy`]); after each one
+/// has been delivered, its end is written to the checkpoint, so a restarted
+/// run continues with the first incomplete window. Delivery is at least
+/// once: a crash between sending and checkpointing, or an event exactly on
+/// a window boundary, delivers events twice.
+///
+/// ## Example
+/// ```rust,no_run
+/// use chrono::NaiveDate;
+/// use usgs_earthquake_api::{Backfill, ChunkBy, UsgsClient};
+/// use usgs_earthquake_api::sinks::Sink;
+/// # struct Stdout;
+/// # impl Sink for Stdout {
+/// #     async fn send(&self, event: &usgs_earthquake_api::EarthquakeFeatures) -> Result<(), usgs_earthquake_api::UsgsError> {
+/// #         println!("{}", event);
+/// #         Ok(())
+/// #     }
+/// # }
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let start = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+///     let backfill = Backfill::new(UsgsClient::new(), start)
+///         .chunk_by(ChunkBy::Months(1))
+///         .configure(|query| query.min_magnitude(4.5))
+///         .checkpoint("backfill.checkpoint.json");
+///     let report = backfill.run(&Stdout).await.unwrap();
+///     println!("{} events up to {}", report.events, report.completed_until);
+/// }
+/// ```
+pub struct Backfill {
+	client: UsgsClient,
+	configure: Arc<ConfigureQuery>,
+	start: NaiveDateTime,
+	end: Option<NaiveDateTime>,
+	chunk_by: ChunkBy,
+	checkpoint: Option<PathBuf>,
+	split_on_timeout: Option<Duration>,
+}
+
+impl Backfill {
+	/// Creates a backfill of worldwide events from `start` (UTC) until now,
+	/// in windows of one month.
+	pub fn new(client: UsgsClient, start: NaiveDateTime) -> Self {
+		Self {
+			client,
+			configure: Arc::new(|query| query),
+			start,
+			end: None,
+			chunk_by: ChunkBy::Months(1),
+			checkpoint: None,
+			split_on_timeout: None,
+		}
+	}
+
+	/// Stops at `end` (UTC) instead of the time the run starts.
+	pub fn end(mut self, end: NaiveDateTime) -> Self {
+		self.end = Some(end);
+		self
+	}
+
+	/// Sets the size of the fetched windows; dense ranges need smaller
+	/// windows to stay below the service's result limit.
+	pub fn chunk_by(mut self, chunk_by: ChunkBy) -> Self {
+		self.chunk_by = chunk_by;
+		self
+	}
+
+	/// Narrows the fetched queries, e.g. by magnitude, region or catalog.
+	///
+	/// The time range is always the current window.
+	pub fn configure(mut self, configure: impl for<'a> Fn(UsgsQuery<'a>) -> UsgsQuery<'a> + Send + Sync + 'static) -> Self {
+		self.configure = Arc::new(configure);
+		self
+	}
+
+	/// Persists progress to `path` and resumes from it. Without a
+	/// checkpoint every run starts over.
+	pub fn checkpoint(mut self, path: impl Into<PathBuf>) -> Self {
+		self.checkpoint = Some(path.into());
+		self
+	}
+
+	/// Splits windows timing out server-side, see [`UsgsQuery::split_on_timeout`].
+	pub fn split_on_timeout(mut self, min_window: Duration) -> Self {
+		self.split_on_timeout = Some(min_window);
+		self
+	}
+
+	/// End of the last completed window according to the checkpoint.
+	pub async fn progress(&self) -> Result<Option<NaiveDateTime>, UsgsError> {
+		let Some(path) = &self.checkpoint else {
+			return Ok(None);
+		};
+		match tokio::fs::read(path).await {
+			Ok(contents) => Ok(Some(serde_json::from_slice::<Checkpoint>(&contents)?.completed_until)),
+			Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+			Err(err) => Err(err.into()),
+		}
+	}
+
+	async fn save_progress(&self, completed_until: NaiveDateTime) -> Result<(), UsgsError> {
+		let Some(path) = &self.checkpoint else {
+			return Ok(());
+		};
+		if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+			tokio::fs::create_dir_all(parent).await?;
+		}
+		// Write to a temporary file first so a crash never leaves a partial checkpoint.
+		let temporary = path.with_extension(format!("tmp{}", std::process::id()));
+		tokio::fs::write(&temporary, serde_json::to_vec(&Checkpoint { completed_until })?).await?;
+		tokio::fs::rename(&temporary, path).await?;
+		Ok(())
+	}
+
+	/// Fetches the remaining windows and sends their events to `sink`,
+	/// stopping at the first failure. Progress up to the failed window is
+	/// kept, so the next run continues there.
+	pub async fn run<S: Sink + Sync>(&self, sink: &S) -> Result<BackfillReport, UsgsError> {
+		let end = self.end.unwrap_or_else(local_time_as_utc);
+		let resumed_from = self.progress().await?.map_or(self.start, |done| done.max(self.start));
+		let mut report = BackfillReport {
+			resumed_from,
+			completed_until: resumed_from,
+			windows: 0,
+			events: 0,
+		};
+
+		for (window_start, window_end) in time_windows(resumed_from, end, self.chunk_by) {
+			let mut query = (self.configure)(self.client.query().worldwide());
+			query.start_time = Some(window_start);
+			query.end_time = window_end;
+			if let Some(min_window) = self.split_on_timeout {
+				query = query.split_on_timeout(min_window);
+			}
+			let response = query.fetch().await?;
+			debug!("backfilled {} events from {} to {}", response.features.len(), window_start, window_end);
+
+			sink.send_all(&response.features).await?;
+			self.save_progress(window_end).await?;
+			report.completed_until = window_end;
+			report.windows += 1;
+			report.events += response.features.len();
+		}
+		Ok(report)
+	}
+}
@@ -0,0 +1 @@
+pub mod backfill;
\ No newline at end of file
@@ -0,0 +1,324 @@
+use std::collections::HashMap;
+#[cfg(feature = "country-filter")]
+use std::path::PathBuf;
+use std::sync::Arc;
+#[cfg(feature = "country-filter")]
+use std::sync::OnceLock;
+use std::time::Duration;
+use reqwest::Client;
+use crate::config::config::QueryConfig;
+#[cfg(feature = "country-filter")]
+use crate::country::country::boundaries_from_path;
+use crate::error::error::UsgsError;
+use crate::fdsn::fdsn::FdsnProfile;
+use crate::models::schema::ParseMode;
+use crate::rate_limit::rate_limit::RateLimiter;
+#[cfg(feature = "replay")]
+use crate::replay::replay::HttpMode;
+use crate::retry::retry::{ExponentialBackoff, RetryBudget, RetryPolicy};
+use crate::UsgsClient;
+
+const DEFAULT_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+/// Builder for [`UsgsClient`].
+///
+/// Created with [`UsgsClient::builder`].
+///
+/// ## Example
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use usgs_earthquake_api::UsgsClient;
+///
+/// let client = UsgsClient::builder()
+///     .timeout(Duration::from_secs(30))
+///     .user_agent("my-app/1.0")
+///     .max_retries(3)
+///     .rate_limit(5, Duration::from_secs(1))
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug)]
+pub struct UsgsClientBuilder {
+	profile: FdsnProfile,
+	base_url: Option<String>,
+	timeout: Option<Duration>,
+	connect_timeout: Option<Duration>,
+	read_timeout: Option<Duration>,
+	deadline: Option<Duration>,
+	user_agent: String,
+	pool_idle_timeout: Option<Option<Duration>>,
+	pool_max_idle_per_host: Option<usize>,
+	http2_keep_alive_interval: Option<Duration>,
+	http2_keep_alive_timeout: Option<Duration>,
+	http2_keep_alive_while_idle: bool,
+	max_retries: u32,
+	retry_backoff: Duration,
+	retry_policy: Option<Arc<dyn RetryPolicy>>,
+	retry_budget: Option<RetryBudget>,
+	rate_limit: Option<(u32, Duration)>,
+	query_profiles: HashMap<String, QueryConfig>,
+	parse_mode: ParseMode,
+	#[cfg(feature = "compression")]
+	compression: bool,
+	#[cfg(feature = "replay")]
+	http_mode: HttpMode,
+	#[cfg(feature = "country-filter")]
+	country_code: String,
+	#[cfg(feature = "country-filter")]
+	country_boundaries_path: Option<PathBuf>,
+}
+
+impl UsgsClientBuilder {
+	pub(crate) fn new() -> Self {
+		Self {
+			profile: FdsnProfile::usgs(),
+			base_url: None,
+			timeout: None,
+			connect_timeout: None,
+			read_timeout: None,
+			deadline: None,
+			user_agent: DEFAULT_USER_AGENT.to_string(),
+			pool_idle_timeout: None,
+			pool_max_idle_per_host: None,
+			http2_keep_alive_interval: None,
+			http2_keep_alive_timeout: None,
+			http2_keep_alive_while_idle: false,
+			max_retries: 0,
+			retry_backoff: Duration::from_millis(500),
+			retry_policy: None,
+			retry_budget: None,
+			rate_limit: None,
+			query_profiles: HashMap::new(),
+			parse_mode: ParseMode::Lenient,
+			#[cfg(feature = "compression")]
+			compression: true,
+			#[cfg(feature = "replay")]
+			http_mode: HttpMode::Live,
+			#[cfg(feature = "country-filter")]
+			country_code: "US".to_string(),
+			#[cfg(feature = "country-filter")]
+			country_boundaries_path: None,
+		}
+	}
+
+	/// Sets the compatibility profile of the targeted FDSN service.
+	///
+	/// The profile's endpoint is used unless [`UsgsClientBuilder::base_url`] is set.
+	pub fn profile(mut self, profile: FdsnProfile) -> Self {
+		self.profile = profile;
+		self
+	}
+
+	/// Overrides the query endpoint.
+	pub fn base_url(mut self, base_url: &str) -> Self {
+		self.base_url = Some(base_url.to_string());
+		self
+	}
+
+	/// Sets the total timeout of each HTTP request attempt.
+	pub fn timeout(mut self, timeout: Duration) -> Self {
+		self.timeout = Some(timeout);
+		self
+	}
+
+	/// Sets the timeout for establishing a connection.
+	pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+		self.connect_timeout = Some(timeout);
+		self
+	}
+
+	/// Sets the maximum time to wait for data while reading a response.
+	///
+	/// Unlike [`UsgsClientBuilder::timeout`] this resets whenever data
+	/// arrives, so large but steadily streaming responses are not cut off.
+	pub fn read_timeout(mut self, timeout: Duration) -> Self {
+		self.read_timeout = Some(timeout);
+		self
+	}
+
+	/// Sets the overall deadline of each fetch, covering all attempts,
+	/// retry backoff and reading the body.
+	///
+	/// Can be overridden per query with [`UsgsQuery::deadline`](crate::UsgsQuery::deadline).
+	pub fn deadline(mut self, deadline: Duration) -> Self {
+		self.deadline = Some(deadline);
+		self
+	}
+
+	/// Sets the `User-Agent` header sent with every request.
+	pub fn user_agent(mut self, user_agent: &str) -> Self {
+		self.user_agent = user_agent.to_string();
+		self
+	}
+
+	/// Sets how long idle pooled connections are kept alive (`None` keeps them forever).
+	pub fn pool_idle_timeout(mut self, timeout: Option<Duration>) -> Self {
+		self.pool_idle_timeout = Some(timeout);
+		self
+	}
+
+	/// Sets the maximum number of idle connections kept per host.
+	pub fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+		self.pool_max_idle_per_host = Some(max);
+		self
+	}
+
+	/// Sends HTTP/2 keep-alive pings at the given interval.
+	pub fn http2_keep_alive_interval(mut self, interval: Duration) -> Self {
+		self.http2_keep_alive_interval = Some(interval);
+		self
+	}
+
+	/// Closes the connection if a keep-alive ping is not acknowledged within `timeout`.
+	pub fn http2_keep_alive_timeout(mut self, timeout: Duration) -> Self {
+		self.http2_keep_alive_timeout = Some(timeout);
+		self
+	}
+
+	/// Keeps sending HTTP/2 keep-alive pings while the connection is idle.
+	pub fn http2_keep_alive_while_idle(mut self, enabled: bool) -> Self {
+		self.http2_keep_alive_while_idle = enabled;
+		self
+	}
+
+	/// Retries failed requests (connection errors, timeouts, 429 and 5xx) up to `retries` times.
+	pub fn max_retries(mut self, retries: u32) -> Self {
+		self.max_retries = retries;
+		self
+	}
+
+	/// Sets the initial delay between retries; it doubles after every attempt
+	/// and is jittered so concurrent retries spread out.
+	pub fn retry_backoff(mut self, backoff: Duration) -> Self {
+		self.retry_backoff = backoff;
+		self
+	}
+
+	/// Installs a custom [`RetryPolicy`], replacing the default exponential
+	/// backoff configured by [`UsgsClientBuilder::max_retries`] and
+	/// [`UsgsClientBuilder::retry_backoff`].
+	pub fn retry_policy(mut self, policy: impl RetryPolicy + 'static) -> Self {
+		self.retry_policy = Some(Arc::new(policy));
+		self
+	}
+
+	/// Limits the retries of all queries sent through the client (and its
+	/// clones) with a shared [`RetryBudget`].
+	pub fn retry_budget(mut self, budget: RetryBudget) -> Self {
+		self.retry_budget = Some(budget);
+		self
+	}
+
+	/// Sends at most `max_requests` requests every `per`.
+	pub fn rate_limit(mut self, max_requests: u32, per: Duration) -> Self {
+		self.rate_limit = Some((max_requests, per));
+		self
+	}
+
+	/// Registers a named, reusable query template, started with
+	/// [`UsgsClient::query_profile`].
+	pub fn query_profile(mut self, name: &str, config: QueryConfig) -> Self {
+		self.query_profiles.insert(name.to_string(), config);
+		self
+	}
+
+	/// Sets how strictly GeoJSON responses are checked (lenient by default).
+	///
+	/// [`ParseMode::Strict`] fails on unknown fields with
+	/// [`UsgsError::Schema`](crate::UsgsError::Schema) and skips the lenient
+	/// numeric coercions, so oddly typed values fail with
+	/// [`UsgsError::Parse`](crate::UsgsError::Parse).
+	pub fn parse_mode(mut self, mode: ParseMode) -> Self {
+		self.parse_mode = mode;
+		self
+	}
+
+	/// Enables or disables gzip/brotli response compression (enabled by default).
+	#[cfg(feature = "compression")]
+	pub fn compression(mut self, enabled: bool) -> Self {
+		self.compression = enabled;
+		self
+	}
+
+	/// Records responses to, or replays them from, a fixture directory.
+	#[cfg(feature = "replay")]
+	pub fn http_mode(mut self, mode: HttpMode) -> Self {
+		self.http_mode = mode;
+		self
+	}
+
+	/// Sets the country filter applied to new queries (e.g., `"TR"`, `"US"`).
+	///
+	/// An empty string disables country filtering by default.
+	#[cfg(feature = "country-filter")]
+	pub fn country_code(mut self, country_code: &str) -> Self {
+		self.country_code = country_code.to_string();
+		self
+	}
+
+	/// Loads the country boundaries from a file instead of the embedded
+	/// dataset, e.g. one of the `*.ser` files of the `country-boundaries`
+	/// crate at another resolution.
+	///
+	/// The file is read by [`UsgsClientBuilder::build`]. Combine with the
+	/// `boundaries-60x30` feature to also keep the embedded dataset small.
+	#[cfg(feature = "country-filter")]
+	pub fn country_boundaries_file(mut self, path: impl Into<PathBuf>) -> Self {
+		self.country_boundaries_path = Some(path.into());
+		self
+	}
+
+	/// Builds the [`UsgsClient`].
+	pub fn build(self) -> Result<UsgsClient, UsgsError> {
+		let mut client = Client::builder().user_agent(self.user_agent);
+		if let Some(timeout) = self.timeout {
+			client = client.timeout(timeout);
+		}
+		if let Some(timeout) = self.connect_timeout {
+			client = client.connect_timeout(timeout);
+		}
+		if let Some(timeout) = self.read_timeout {
+			client = client.read_timeout(timeout);
+		}
+		if let Some(timeout) = self.pool_idle_timeout {
+			client = client.pool_idle_timeout(timeout);
+		}
+		if let Some(max) = self.pool_max_idle_per_host {
+			client = client.pool_max_idle_per_host(max);
+		}
+		if let Some(interval) = self.http2_keep_alive_interval {
+			client = client.http2_keep_alive_interval(interval);
+		}
+		if let Some(timeout) = self.http2_keep_alive_timeout {
+			client = client.http2_keep_alive_timeout(timeout);
+		}
+		client = client.http2_keep_alive_while_idle(self.http2_keep_alive_while_idle);
+		#[cfg(feature = "country-filter")]
+		let country_boundaries = match &self.country_boundaries_path {
+			Some(path) => Arc::new(OnceLock::from(boundaries_from_path(path)?)),
+			None => Arc::default(),
+		};
+
+		Ok(UsgsClient {
+			base_url: self.base_url.unwrap_or_else(|| self.profile.base_url.clone()),
+			client: client.build()?,
+			profile: self.profile,
+			retry_policy: self.retry_policy.unwrap_or_else(|| Arc::new(ExponentialBackoff::new(self.max_retries, self.retry_backoff))),
+			retry_budget: self.retry_budget.map(Arc::new),
+			rate_limiter: self.rate_limit.map(|(max_requests, per)| Arc::new(RateLimiter::new(max_requests, per))),
+			query_profiles: Arc::new(self.query_profiles),
+			parse_mode: self.parse_mode,
+			deadline: self.deadline,
+			#[cfg(feature = "compression")]
+			compression: self.compression,
+			#[cfg(feature = "replay")]
+			http_mode: self.http_mode,
+			#[cfg(feature = "country-filter")]
+			country_code: self.country_code,
+			#[cfg(feature = "country-filter")]
+			country_boundaries,
+			#[cfg(feature = "country-filter")]
+			country_extents: Arc::default(),
+		})
+	}
+}
\ No newline at end of file
@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use crate::error::error::UsgsError;
+use crate::models::models::EarthquakeFeatures;
+
+/// 64-bit FNV-1a hash, stable across platforms, processes and releases.
+pub(crate) fn stable_hash(bytes: &[u8]) -> u64 {
+	bytes.iter().fold(0xcbf29ce484222325, |hash, byte| (hash ^ *byte as u64).wrapping_mul(0x100000001b3))
+}
+
+/// Key-value storage for cached response bodies.
+///
+/// Implementations are shared between clients, possibly across processes,
+/// so entries expire on their own after the given time to live.
+pub trait CacheBackend: Send + Sync {
+	/// Returns the value stored under `key`, if present and not expired.
+	fn get(&self, key: &str) -> impl Future<Output = Result<Option<Vec<u8>>, UsgsError>> + Send;
+
+	/// Stores `value` under `key` for `ttl`.
+	fn put(&self, key: &str, value: Vec<u8>, ttl: Duration) -> impl Future<Output = Result<(), UsgsError>> + Send;
+}
+
+/// Remembers which events have already been handled, so alerts are sent
+/// once even when several instances poll the same query.
+pub trait SeenEventStore: Send + Sync {
+	/// Records `id` as seen, returning `true` if it had not been seen before.
+	///
+	/// The check and the update are a single atomic step, so of several
+	/// concurrent callers exactly one gets `true`.
+	fn mark_seen(&self, id: &str) -> impl Future<Output = Result<bool, UsgsError>> + Send;
+
+	/// Marks all events as seen and returns those that had not been seen before.
+	fn filter_unseen<'a>(&self, events: &'a [EarthquakeFeatures]) -> impl Future<Output = Result<Vec<&'a EarthquakeFeatures>, UsgsError>> + Send {
+		async move {
+			let mut unseen = Vec::new();
+			for event in events {
+				if self.mark_seen(&event.id).await? {
+					unseen.push(event);
+				}
+			}
+			Ok(unseen)
+		}
+	}
+}
+
+/// In-process [`CacheBackend`] keeping entries in a hash map.
+///
+/// Expired entries are dropped when they are read and whenever a new entry
+/// is stored.
+#[derive(Debug, Default)]
+pub struct MemoryCache {
+	entries: Mutex<HashMap<String, (Instant, Vec<u8>)>>,
+}
+
+impl MemoryCache {
+	/// Creates an empty cache.
+	pub fn new() -> Self {
+		Self::default()
+	}
+}
+
+impl CacheBackend for MemoryCache {
+	async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, UsgsError> {
+		let mut entries = self.entries.lock().expect("cache lock poisoned");
+		match entries.get(key) {
+			Some((expires, value)) if *expires > Instant::now() => Ok(Some(value.clone())),
+			Some(_) => {
+				entries.remove(key);
+				Ok(None)
+			}
+			None => Ok(None),
+		}
+	}
+
+	async fn put(&self, key: &str, value: Vec<u8>, ttl: Duration) -> Result<(), UsgsError> {
+		let now = Instant::now();
+		let mut entries = self.entries.lock().expect("cache lock poisoned");
+		entries.retain(|_, (expires, _)| *expires > now);
+		entries.insert(key.to_string(), (now + ttl, value));
+		Ok(())
+	}
+}
+
+/// In-process [`SeenEventStore`], remembering event ids for a limited time.
+#[derive(Debug)]
+pub struct MemorySeenStore {
+	seen: Mutex<HashMap<String, Instant>>,
+	ttl: Duration,
+}
+
+impl MemorySeenStore {
+	/// Creates a store remembering event ids for 7 days.
+	pub fn new() -> Self {
+		Self::with_ttl(Duration::from_secs(7 * 24 * 60 * 60))
+	}
+
+	/// Creates a store remembering event ids for `ttl`.
+	pub fn with_ttl(ttl: Duration) -> Self {
+		Self {
+			seen: Mutex::new(HashMap::new()),
+			ttl,
+		}
+	}
+}
+
+impl Default for MemorySeenStore {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl SeenEventStore for MemorySeenStore {
+	async fn mark_seen(&self, id: &str) -> Result<bool, UsgsError> {
+		let now = Instant::now();
+		let mut seen = self.seen.lock().expect("seen store lock poisoned");
+		seen.retain(|_, expires| *expires > now);
+		Ok(seen.insert(id.to_string(), now + self.ttl).is_none())
+	}
+}
+
+/// [`CacheBackend`] storing each entry as a file in a directory, so cached
+/// responses survive restarts.
+///
+/// Files are named after a hash of the key and start with the expiry time.
+/// Expired files are removed when they are read.
+///
+/// With the `cache-zstd` feature entries are stored zstd-compressed, which
+/// shrinks GeoJSON responses several times over. Compressed and plain
+/// entries are told apart when read, so existing cache directories stay
+/// usable when the feature or [`DiskCache::compression_level`] changes.
+#[derive(Debug, Clone)]
+pub struct DiskCache {
+	directory: PathBuf,
+	#[cfg(feature = "cache-zstd")]
+	compression_level: Option<i32>,
+}
+
+/// Magic number at the start of every zstd frame.
+#[cfg(feature = "cache-zstd")]
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+impl DiskCache {
+	/// Creates a cache in `directory`, which is created on first write.
+	pub fn new(directory: impl Into<PathBuf>) -> Self {
+		Self {
+			directory: directory.into(),
+			#[cfg(feature = "cache-zstd")]
+			compression_level: Some(zstd::DEFAULT_COMPRESSION_LEVEL),
+		}
+	}
+
+	/// Sets the zstd level new entries are compressed with (1 to 22,
+	/// default 3), or stores them uncompressed with `None`.
+	#[cfg(feature = "cache-zstd")]
+	pub fn compression_level(mut self, level: Option<i32>) -> Self {
+		self.compression_level = level;
+		self
+	}
+
+	fn path(&self, key: &str) -> PathBuf {
+		self.directory.join(format!("{:016x}.cache", stable_hash(key.as_bytes())))
+	}
+}
+
+fn unix_millis() -> u64 {
+	SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
+
+impl CacheBackend for DiskCache {
+	async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, UsgsError> {
+		let path = self.path(key);
+		let mut contents = match tokio::fs::read(&path).await {
+			Ok(contents) => contents,
+			Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+			Err(err) => return Err(err.into()),
+		};
+		let expires = contents.get(..8).map(|bytes| u64::from_le_bytes(bytes.try_into().expect("8 bytes")));
+		if expires.is_none_or(|expires| expires <= unix_millis()) {
+			let _ = tokio::fs::remove_file(&path).await;
+			return Ok(None);
+		}
+		let value = contents.split_off(8);
+		#[cfg(feature = "cache-zstd")]
+		if value.starts_with(&ZSTD_MAGIC) {
+			return Ok(Some(zstd::decode_all(value.as_slice()).map_err(UsgsError::Decompression)?));
+		}
+		Ok(Some(value))
+	}
+
+	async fn put(&self, key: &str, value: Vec<u8>, ttl: Duration) -> Result<(), UsgsError> {
+		tokio::fs::create_dir_all(&self.directory).await?;
+		let expires = unix_millis() + ttl.as_millis() as u64;
+		#[cfg(feature = "cache-zstd")]
+		let value = match self.compression_level {
+			Some(level) => zstd::encode_all(value.as_slice(), level)?,
+			None => value,
+		};
+		let mut contents = Vec::with_capacity(value.len() + 8);
+		contents.extend_from_slice(&expires.to_le_bytes());
+		contents.extend_from_slice(&value);
+
+		// Write to a temporary file first so readers never see partial entries.
+		let path = self.path(key);
+		let temporary = path.with_extension(format!("tmp{}", std::process::id()));
+		tokio::fs::write(&temporary, contents).await?;
+		tokio::fs::rename(&temporary, &path).await?;
+		Ok(())
+	}
+}
\ No newline at end of file
@@ -0,0 +1,3 @@
+pub mod cache;
+#[cfg(feature = "redis")]
+pub mod redis_store;
\ No newline at end of file
@@ -0,0 +1,107 @@
+use std::time::Duration;
+use redis::aio::ConnectionManager;
+use crate::error::error::UsgsError;
+use super::cache::{CacheBackend, SeenEventStore};
+
+fn redis_error(err: redis::RedisError) -> UsgsError {
+	UsgsError::Cache(err.to_string())
+}
+
+/// Redis implementation of [`CacheBackend`] and [`SeenEventStore`], letting
+/// horizontally scaled services share cached responses and the set of
+/// already alerted events.
+///
+/// Cache entries are stored under `<prefix>cache:<key>` and seen events under
+/// `<prefix>seen:<id>`, both with a Redis expiry. The connection reconnects
+/// automatically and can be cloned cheaply.
+///
+/// ## Example
+/// ```rust,no_run
+/// use usgs_earthquake_api::{RedisStore, SeenEventStore, UsgsClient};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let store = RedisStore::connect("redis://127.0.0.1/").await.unwrap();
+///     let response = UsgsClient::new().query().min_magnitude(5.0).fetch().await.unwrap();
+///     for event in store.filter_unseen(&response.features).await.unwrap() {
+///         println!("new event {}", event.id);
+///     }
+/// }
+/// ```
+#[derive(Clone)]
+pub struct RedisStore {
+	connection: ConnectionManager,
+	prefix: String,
+	seen_ttl: Duration,
+}
+
+impl std::fmt::Debug for RedisStore {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("RedisStore")
+			.field("prefix", &self.prefix)
+			.field("seen_ttl", &self.seen_ttl)
+			.finish_non_exhaustive()
+	}
+}
+
+impl RedisStore {
+	/// Connects to the Redis server at `url` (e.g. `redis://127.0.0.1/`).
+	pub async fn connect(url: &str) -> Result<Self, UsgsError> {
+		let client = redis::Client::open(url).map_err(redis_error)?;
+		let connection = ConnectionManager::new(client).await.map_err(redis_error)?;
+		Ok(Self {
+			connection,
+			prefix: "usgs:".to_string(),
+			seen_ttl: Duration::from_secs(7 * 24 * 60 * 60),
+		})
+	}
+
+	/// Sets the key prefix (default `usgs:`), to share a server between services.
+	pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+		self.prefix = prefix.into();
+		self
+	}
+
+	/// Sets how long seen event ids are remembered (default 7 days).
+	pub fn seen_ttl(mut self, ttl: Duration) -> Self {
+		self.seen_ttl = ttl;
+		self
+	}
+}
+
+impl CacheBackend for RedisStore {
+	async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, UsgsError> {
+		redis::cmd("GET")
+			.arg(format!("{}cache:{}", self.prefix, key))
+			.query_async(&mut self.connection.clone())
+			.await
+			.map_err(redis_error)
+	}
+
+	async fn put(&self, key: &str, value: Vec<u8>, ttl: Duration) -> Result<(), UsgsError> {
+		redis::cmd("SET")
+			.arg(format!("{}cache:{}", self.prefix, key))
+			.arg(value)
+			.arg("PX")
+			.arg(ttl.as_millis().max(1) as u64)
+			.query_async::<()>(&mut self.connection.clone())
+			.await
+			.map_err(redis_error)
+	}
+}
+
+impl SeenEventStore for RedisStore {
+	async fn mark_seen(&self, id: &str) -> Result<bool, UsgsError> {
+		// `SET .. NX` answers `OK` only for the first caller.
+		let reply: Option<String> = redis::cmd("SET")
+			.arg(format!("{}seen:{}", self.prefix, id))
+			.arg(1)
+			.arg("NX")
+			.arg("PX")
+			.arg(self.seen_ttl.as_millis().max(1) as u64)
+			.query_async(&mut self.connection.clone())
+			.await
+			.map_err(redis_error)?;
+		Ok(reply.is_some())
+	}
+}
\ No newline at end of file
@@ -0,0 +1,130 @@
+use std::sync::OnceLock;
+use crate::error::error::UsgsError;
+use crate::models::models::{haversine_km, EarthquakeFeatures};
+
+/// Bundled `name,country,latitude,longitude` list of capitals and cities in
+/// seismically active regions.
+const BUNDLED_CITIES: &str = include_str!("../../data/cities.csv");
+
+/// A populated place.
+#[derive(Debug, Clone, PartialEq)]
+pub struct City {
+	/// City name.
+	pub name: String,
+
+	/// ISO 3166-1 alpha-2 country code.
+	pub country: String,
+
+	/// Latitude in degrees.
+	pub latitude: f64,
+
+	/// Longitude in degrees.
+	pub longitude: f64,
+}
+
+/// The city closest to an epicenter, see [`EarthquakeFeatures::nearest_city`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct NearestCity<'a> {
+	/// The closest city.
+	pub city: &'a City,
+
+	/// Great-circle distance from the epicenter in kilometers.
+	pub distance_km: f64,
+}
+
+/// Set of populated places for reverse geocoding epicenters.
+///
+/// [`Cities::bundled`] covers a few hundred capitals and cities near active
+/// faults; load a GeoNames dump (e.g. `cities15000.txt`) with
+/// [`Cities::from_geonames`] for finer coverage.
+#[derive(Debug, Clone, Default)]
+pub struct Cities {
+	/// The places.
+	pub cities: Vec<City>,
+}
+
+impl Cities {
+	/// The dataset bundled with the crate, parsed once.
+	pub fn bundled() -> &'static Cities {
+		static BUNDLED: OnceLock<Cities> = OnceLock::new();
+		BUNDLED.get_or_init(|| Cities::from_csv(BUNDLED_CITIES).expect("Failed to parse bundled cities"))
+	}
+
+	/// Parses a `name,country,latitude,longitude` CSV with a header row.
+	pub fn from_csv(csv: &str) -> Result<Self, UsgsError> {
+		let cities = csv.lines()
+			.skip(1)
+			.filter(|line| !line.trim().is_empty())
+			.map(|line| {
+				let invalid = || UsgsError::Cities(format!("invalid row `{}`", line));
+				let columns: Vec<&str> = line.split(',').collect();
+				let [name, country, latitude, longitude] = columns.as_slice() else {
+					return Err(invalid());
+				};
+				Ok(City {
+					name: name.trim().to_string(),
+					country: country.trim().to_string(),
+					latitude: latitude.trim().parse().map_err(|_| invalid())?,
+					longitude: longitude.trim().parse().map_err(|_| invalid())?,
+				})
+			})
+			.collect::<Result<_, _>>()?;
+		Ok(Self { cities })
+	}
+
+	/// Parses a tab-separated GeoNames dump such as `cities15000.txt`.
+	pub fn from_geonames(dump: &str) -> Result<Self, UsgsError> {
+		let cities = dump.lines()
+			.filter(|line| !line.trim().is_empty())
+			.map(|line| {
+				let invalid = || UsgsError::Cities(format!("invalid GeoNames row `{}`", line));
+				let columns: Vec<&str> = line.split('\t').collect();
+				if columns.len() < 9 {
+					return Err(invalid());
+				}
+				Ok(City {
+					name: columns[1].to_string(),
+					country: columns[8].to_string(),
+					latitude: columns[4].parse().map_err(|_| invalid())?,
+					longitude: columns[5].parse().map_err(|_| invalid())?,
+				})
+			})
+			.collect::<Result<_, _>>()?;
+		Ok(Self { cities })
+	}
+
+	/// The city closest to a point.
+	pub fn nearest(&self, latitude: f64, longitude: f64) -> Option<NearestCity<'_>> {
+		self.cities.iter()
+			.map(|city| NearestCity {
+				city,
+				distance_km: haversine_km((latitude, longitude), (city.latitude, city.longitude)),
+			})
+			.min_by(|a, b| a.distance_km.total_cmp(&b.distance_km))
+	}
+}
+
+impl EarthquakeFeatures {
+	/// The bundled city closest to the epicenter, for a readable location
+	/// when `place` is empty or unhelpful.
+	///
+	/// ## Example
+	/// ```rust
+	/// use usgs_earthquake_api::EarthquakeFeatures;
+	///
+	/// let event: EarthquakeFeatures = serde_json::from_str(r#"{
+	///     "type": "Feature", "id": "us6000jllz", "properties": {},
+	///     "geometry": { "type": "Point", "coordinates": [37.03, 37.23, 10.0] }
+	/// }"#).unwrap();
+	/// let nearest = event.nearest_city().unwrap();
+	/// assert_eq!(nearest.city.name, "Gaziantep");
+	/// ```
+	pub fn nearest_city(&self) -> Option<NearestCity<'static>> {
+		Cities::bundled().nearest(self.geometry.latitude(), self.geometry.longitude())
+	}
+
+	/// The city of `cities` closest to the epicenter.
+	pub fn nearest_city_in<'a>(&self, cities: &'a Cities) -> Option<NearestCity<'a>> {
+		cities.nearest(self.geometry.latitude(), self.geometry.longitude())
+	}
+}
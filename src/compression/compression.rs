@@ -0,0 +1,44 @@
+use reqwest::header::CONTENT_ENCODING;
+use reqwest::Response;
+use crate::error::error::UsgsError;
+
+/// Value of the `Accept-Encoding` header sent when compression is enabled.
+#[cfg(feature = "compression")]
+pub(crate) const ACCEPT_ENCODING_VALUE: &str = "gzip, br";
+
+/// Body of a response together with its size on the wire.
+pub(crate) struct Body {
+	pub(crate) text: String,
+	pub(crate) downloaded_bytes: u64,
+}
+
+/// Reads a response body, decompressing it according to `Content-Encoding`.
+pub(crate) async fn read_body(response: Response) -> Result<Body, UsgsError> {
+	let encoding = response.headers().get(CONTENT_ENCODING)
+		.and_then(|value| value.to_str().ok())
+		.map(|value| value.trim().to_ascii_lowercase());
+	let bytes = response.bytes().await.map_err(UsgsError::request)?;
+	let downloaded_bytes = bytes.len() as u64;
+
+	let decoded = match encoding.as_deref() {
+		None | Some("") | Some("identity") => bytes.to_vec(),
+		#[cfg(feature = "compression")]
+		Some("gzip") => {
+			let mut decoded = Vec::new();
+			std::io::Read::read_to_end(&mut flate2::read::GzDecoder::new(&bytes[..]), &mut decoded).map_err(UsgsError::Decompression)?;
+			decoded
+		}
+		#[cfg(feature = "compression")]
+		Some("br") => {
+			let mut decoded = Vec::new();
+			std::io::Read::read_to_end(&mut brotli_decompressor::Decompressor::new(&bytes[..], 4096), &mut decoded).map_err(UsgsError::Decompression)?;
+			decoded
+		}
+		Some(other) => return Err(UsgsError::UnsupportedEncoding(other.to_string())),
+	};
+
+	Ok(Body {
+		text: String::from_utf8_lossy(&decoded).into_owned(),
+		downloaded_bytes,
+	})
+}
\ No newline at end of file
@@ -0,0 +1 @@
+pub mod compression;
\ No newline at end of file
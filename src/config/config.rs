@@ -0,0 +1,239 @@
+use chrono::{NaiveDateTime, TimeDelta};
+use serde::{Deserialize, Serialize};
+use crate::error::error::UsgsError;
+use crate::models::ids::CatalogId;
+use crate::{local_time_as_utc, parse_url_time, AlertLevel, OrderBy, UsgsClient, UsgsQuery};
+
+/// Circular search region of a [`QueryConfig`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct CircleConfig {
+	/// Latitude of the center in degrees.
+	pub latitude: f64,
+
+	/// Longitude of the center in degrees.
+	pub longitude: f64,
+
+	/// Radius in kilometers.
+	pub radius_km: f64,
+}
+
+/// Query definition loadable from configuration files, so deployments can
+/// change their monitoring scope without recompiling.
+///
+/// Times are time window expressions (see [`parse_time_expression`]), e.g.
+/// `start = "24h"` for the past day. Every field is optional; missing
+/// fields keep the defaults of [`UsgsQuery::new`], except that `countries`
+/// replaces the client's default country filter (an empty list queries
+/// worldwide).
+///
+/// ## Example
+/// ```rust
+/// use usgs_earthquake_api::{QueryConfig, UsgsClient};
+///
+/// let config: QueryConfig = serde_json::from_str(r#"{
+///     "start": "7d",
+///     "min_magnitude": 4.0,
+///     "alert_level": "orange",
+///     "circle": { "latitude": 39.0, "longitude": 35.0, "radius_km": 800.0 }
+/// }"#).unwrap();
+///
+/// let client = UsgsClient::new();
+/// let url = config.to_query(&client).unwrap().build_url().unwrap();
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct QueryConfig {
+	/// Start of the time window.
+	pub start: Option<String>,
+
+	/// End of the time window (default now).
+	pub end: Option<String>,
+
+	/// Minimum magnitude.
+	pub min_magnitude: Option<f32>,
+
+	/// Maximum magnitude.
+	pub max_magnitude: Option<f32>,
+
+	/// PAGER alert level (`green`, `yellow`, `orange`, `red` or `all`).
+	pub alert_level: Option<String>,
+
+	/// Lowest PAGER alert level to keep, including every worse level.
+	pub min_alert_level: Option<String>,
+
+	/// Ordering (`time`, `time-asc`, `magnitude` or `magnitude-asc`).
+	pub order_by: Option<String>,
+
+	/// Catalog to limit results to.
+	pub catalog: Option<CatalogId>,
+
+	/// Circular region to limit results to.
+	pub circle: Option<CircleConfig>,
+
+	/// Minimum number of felt reports.
+	pub min_felt: Option<u32>,
+
+	/// Minimum significance index.
+	pub min_significance: Option<u32>,
+
+	/// Also return deleted events.
+	pub include_deleted: Option<bool>,
+
+	/// ISO 3166-1 alpha-2 codes of the countries to keep events of
+	/// (`country-filter` feature).
+	pub countries: Option<Vec<String>>,
+}
+
+/// Parses a time window expression into a UTC time.
+///
+/// Accepted forms:
+/// - `now`
+/// - a duration before now: `90s`, `30m`, `24h`, `7d` or `2w`, optionally
+///   written as `now-24h`
+/// - an absolute UTC time such as `2024-02-06T01:17:00`, `2024-02-06 01:17`
+///   or `2024-02-06`
+pub fn parse_time_expression(expression: &str) -> Result<NaiveDateTime, UsgsError> {
+	let expression = expression.trim();
+	let invalid = || UsgsError::Config(format!("invalid time expression `{}`", expression));
+	if expression == "now" {
+		return Ok(local_time_as_utc());
+	}
+
+	let relative = expression.strip_prefix("now-").unwrap_or(expression);
+	if let Some((amount, unit)) = relative.find(|c: char| !c.is_ascii_digit()).map(|at| relative.split_at(at)) {
+		let amount = amount.parse::<i64>().ok();
+		let duration = match (amount, unit) {
+			(Some(amount), "s") => Some(TimeDelta::try_seconds(amount)),
+			(Some(amount), "m") => Some(TimeDelta::try_minutes(amount)),
+			(Some(amount), "h") => Some(TimeDelta::try_hours(amount)),
+			(Some(amount), "d") => Some(TimeDelta::try_days(amount)),
+			(Some(amount), "w") => Some(TimeDelta::try_weeks(amount)),
+			_ => None,
+		};
+		if let Some(duration) = duration {
+			return duration
+				.and_then(|duration| local_time_as_utc().checked_sub_signed(duration))
+				.ok_or_else(invalid);
+		}
+	}
+
+	parse_url_time(expression).ok_or_else(invalid)
+}
+
+impl QueryConfig {
+	/// Parses a query definition from TOML.
+	#[cfg(feature = "toml")]
+	pub fn from_toml(text: &str) -> Result<Self, UsgsError> {
+		toml::from_str(text).map_err(|err| UsgsError::Config(err.to_string()))
+	}
+
+	/// Parses a query definition from YAML.
+	#[cfg(feature = "yaml")]
+	pub fn from_yaml(text: &str) -> Result<Self, UsgsError> {
+		serde_yaml::from_str(text).map_err(|err| UsgsError::Config(err.to_string()))
+	}
+
+	/// Builds the query on `client`.
+	pub fn to_query<'a>(&self, client: &'a UsgsClient) -> Result<UsgsQuery<'a>, UsgsError> {
+		let mut query = UsgsQuery::new(client);
+		if let Some(start) = &self.start {
+			query.start_time = Some(parse_time_expression(start)?);
+		}
+		if let Some(end) = &self.end {
+			query.end_time = parse_time_expression(end)?;
+		}
+		if let Some(min) = self.min_magnitude {
+			query = query.min_magnitude(min);
+		}
+		if let Some(max) = self.max_magnitude {
+			query = query.max_magnitude(max);
+		}
+		if let Some(level) = &self.alert_level {
+			query = query.alert_level(level.parse::<AlertLevel>()?);
+		}
+		if let Some(level) = &self.min_alert_level {
+			query = query.min_alert_level(level.parse::<AlertLevel>()?);
+		}
+		if let Some(order_by) = &self.order_by {
+			query = query.order_by(order_by.parse::<OrderBy>()?);
+		}
+		if let Some(catalog) = &self.catalog {
+			query = query.catalog(catalog);
+		}
+		if let Some(circle) = &self.circle {
+			query = query.circle(circle.latitude, circle.longitude, circle.radius_km);
+		}
+		if let Some(felt) = self.min_felt {
+			query = query.min_felt(felt);
+		}
+		if let Some(sig) = self.min_significance {
+			query = query.min_significance(sig);
+		}
+		if self.include_deleted == Some(true) {
+			query = query.include_deleted();
+		}
+		if let Some(countries) = &self.countries {
+			query = Self::countries(query, countries)?;
+		}
+		Ok(query)
+	}
+
+	#[cfg(feature = "country-filter")]
+	fn countries<'a>(query: UsgsQuery<'a>, countries: &[String]) -> Result<UsgsQuery<'a>, UsgsError> {
+		let countries: Vec<&str> = countries.iter().map(String::as_str).collect();
+		Ok(query.filter_by_country_codes(&countries))
+	}
+
+	#[cfg(not(feature = "country-filter"))]
+	fn countries<'a>(query: UsgsQuery<'a>, countries: &[String]) -> Result<UsgsQuery<'a>, UsgsError> {
+		if countries.is_empty() {
+			Ok(query)
+		} else {
+			Err(UsgsError::UnsupportedParameter("countries".to_string()))
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use chrono::{NaiveDate, Utc};
+	use super::*;
+
+	#[test]
+	fn relative_expressions_count_back_from_now() {
+		for (expression, expected) in [("24h", TimeDelta::hours(24)), ("now-7d", TimeDelta::days(7)), ("90m", TimeDelta::minutes(90)), ("2w", TimeDelta::weeks(2))] {
+			let before = Utc::now().naive_utc();
+			let time = parse_time_expression(expression).unwrap();
+			let after = Utc::now().naive_utc();
+			assert!(before - expected <= time && time <= after - expected, "{}: {}", expression, time);
+		}
+		let before = Utc::now().naive_utc();
+		let time = parse_time_expression(" now ").unwrap();
+		assert!(before <= time && time <= Utc::now().naive_utc());
+	}
+
+	#[test]
+	fn absolute_expressions_are_utc_times() {
+		let expected = NaiveDate::from_ymd_opt(2024, 2, 6).unwrap().and_hms_opt(1, 17, 0).unwrap();
+		assert_eq!(parse_time_expression("2024-02-06T01:17:00").unwrap(), expected);
+		assert_eq!(parse_time_expression("2024-02-06 01:17").unwrap(), expected);
+		assert_eq!(parse_time_expression("2024-02-06").unwrap(), expected.date().and_hms_opt(0, 0, 0).unwrap());
+	}
+
+	#[test]
+	fn invalid_and_overflowing_expressions_are_config_errors() {
+		for expression in ["", "yesterday", "24x", "now-", "h", "999999999d", "99999999999999999999s", "2024-13-01"] {
+			assert!(matches!(parse_time_expression(expression), Err(UsgsError::Config(_))), "{}", expression);
+		}
+	}
+
+	#[test]
+	fn unknown_fields_are_rejected() {
+		let config: QueryConfig = serde_json::from_str(r#"{ "start": "7d", "min_magnitude": 4.0 }"#).unwrap();
+		assert_eq!(config.min_magnitude, Some(4.0));
+
+		assert!(serde_json::from_str::<QueryConfig>(r#"{ "start": "7d", "minmagnitude": 4.0 }"#).is_err());
+		assert!(serde_json::from_str::<QueryConfig>(r#"{ "circle": { "latitude": 39.0, "longitude": 35.0, "radius": 800.0 } }"#).is_err());
+	}
+}
@@ -0,0 +1,297 @@
+use std::collections::HashMap;
+use std::path::Path;
+use country_boundaries::{BoundingBox, CountryBoundaries, LatLon};
+use crate::error::error::UsgsError;
+use crate::UsgsClient;
+use crate::models::models::{haversine_km, EarthquakeFeatures, EarthquakeResponse};
+
+/// Half diagonal of a 1° grid cell at the equator, in kilometers.
+const CELL_HALF_DIAGONAL_KM: f64 = 79.0;
+
+/// Largest `maxradiuskm` accepted by USGS, half the Earth's circumference.
+const MAX_RADIUS_KM: f64 = 20_001.6;
+
+/// Server-side pre-filter sent along with the client-side country filter,
+/// so the service drops most events outside the countries before download.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CountryPrefilter {
+	/// Bounding box of the countries as `minlatitude` ... `maxlongitude`
+	#[default]
+	BoundingBox,
+
+	/// Circle around the centroid of the countries enclosing all of them,
+	/// tighter than the bounding box for compact or diagonal countries
+	Circle,
+
+	/// Nothing; combine with [`UsgsQuery::circle`](crate::UsgsQuery::circle)
+	/// for a hand-picked circle
+	None,
+}
+
+/// Embedded boundaries dataset, the coarsest one selected by the
+/// `boundaries-*` features; the others are left out of the binary.
+#[cfg(feature = "boundaries-60x30")]
+const EMBEDDED_BOUNDARIES: &[u8] = country_boundaries::BOUNDARIES_ODBL_60X30;
+#[cfg(all(feature = "boundaries-180x90", not(feature = "boundaries-60x30")))]
+const EMBEDDED_BOUNDARIES: &[u8] = country_boundaries::BOUNDARIES_ODBL_180X90;
+#[cfg(not(any(feature = "boundaries-180x90", feature = "boundaries-60x30")))]
+const EMBEDDED_BOUNDARIES: &[u8] = country_boundaries::BOUNDARIES_ODBL_360X180;
+
+/// Parses the embedded boundaries dataset.
+pub(crate) fn embedded_boundaries() -> CountryBoundaries {
+	CountryBoundaries::from_reader(EMBEDDED_BOUNDARIES).expect("Failed to parse embedded country boundaries")
+}
+
+/// Reads a boundaries dataset in the format of the `country-boundaries`
+/// crate (`*.ser`) from a file.
+pub(crate) fn boundaries_from_path(path: &Path) -> Result<CountryBoundaries, UsgsError> {
+	let contents = std::fs::read(path)?;
+	CountryBoundaries::from_reader(contents.as_slice()).map_err(|e| UsgsError::CountryBoundaries(e.to_string()))
+}
+
+/// ISO 3166-1 alpha-2 code of the country containing a point, ignoring
+/// subdivision ids such as `US-CA`.
+pub(crate) fn country_at(boundaries: &CountryBoundaries, latitude: f64, longitude: f64) -> Option<String> {
+	let position = LatLon::new(latitude, longitude).ok()?;
+	boundaries.ids(position).into_iter()
+		.find(|id| !id.contains('-'))
+		.map(str::to_string)
+}
+
+/// Cells of the 1° grid touched by a region: its latitude range and the
+/// longitude columns (`-180 + i .. -179 + i`) it covers.
+#[derive(Debug, Clone)]
+pub(crate) struct CountryExtent {
+	south: i32,
+	north: i32,
+	columns: Vec<bool>,
+	cells: Vec<(i32, i32)>,
+}
+
+/// Extents of every country and subdivision, from one pass over the 1°
+/// raster of the boundaries dataset.
+pub(crate) fn country_extents(boundaries: &CountryBoundaries) -> HashMap<String, CountryExtent> {
+	let mut extents: HashMap<String, CountryExtent> = HashMap::new();
+	for south in -90..90 {
+		for west in -180..180 {
+			// Inset so that only this cell is visited, not its neighbours.
+			let (south_edge, west_edge) = (f64::from(south), f64::from(west));
+			let Ok(cell) = BoundingBox::new(south_edge + 0.01, west_edge + 0.01, south_edge + 0.99, west_edge + 0.99) else {
+				continue;
+			};
+			for id in boundaries.intersecting_ids(cell) {
+				let extent = extents.entry(id.to_string()).or_insert_with(|| CountryExtent {
+					south,
+					north: south + 1,
+					columns: vec![false; 360],
+					cells: Vec::new(),
+				});
+				extent.south = extent.south.min(south);
+				extent.north = extent.north.max(south + 1);
+				extent.columns[(west + 180) as usize] = true;
+				extent.cells.push((south, west));
+			}
+		}
+	}
+	extents
+}
+
+/// Smallest `(min_lat, max_lat, min_lon, max_lon)` box covering all `codes`,
+/// or `None` if a code is unknown.
+///
+/// Boxes crossing the antimeridian (e.g. for `RU` or `FJ`) keep `min_lon`
+/// in `[-180, 180)` and continue `max_lon` past 180, as accepted by USGS.
+pub(crate) fn bounding_box(extents: &HashMap<String, CountryExtent>, codes: &[String]) -> Option<(f64, f64, f64, f64)> {
+	let (mut south, mut north) = (90, -90);
+	let mut columns = [false; 360];
+	for code in codes {
+		let extent = extents.get(code)?;
+		south = south.min(extent.south);
+		north = north.max(extent.north);
+		for (column, covered) in columns.iter_mut().zip(&extent.columns) {
+			*column |= covered;
+		}
+	}
+	if south >= north {
+		return None;
+	}
+
+	// The box spans everything but the widest run of empty columns, which
+	// may wrap around the antimeridian.
+	let (mut gap_start, mut gap_length) = (0, 0);
+	let mut run = 0;
+	for index in 0..720 {
+		if columns[index % 360] {
+			run = 0;
+		} else {
+			run += 1;
+			if run > gap_length && run < 360 {
+				gap_start = index + 1 - run;
+				gap_length = run;
+			}
+		}
+	}
+	let (west, east) = match gap_length {
+		0 => (0, 359),
+		_ => ((gap_start + gap_length) % 360, (gap_start + 359) % 360),
+	};
+	let east = if east < west { east + 360 } else { east };
+	Some((f64::from(south), f64::from(north), west as f64 - 180.0, east as f64 - 179.0))
+}
+
+/// Circle `(latitude, longitude, radius_km)` around the centroid of the
+/// grid cells of `codes` that encloses all of them, or `None` if a code is
+/// unknown or the cells spread around the globe.
+pub(crate) fn enclosing_circle(extents: &HashMap<String, CountryExtent>, codes: &[String]) -> Option<(f64, f64, f64)> {
+	let mut centers = Vec::new();
+	for code in codes {
+		let extent = extents.get(code)?;
+		centers.extend(extent.cells.iter().map(|&(south, west)| (f64::from(south) + 0.5, f64::from(west) + 0.5)));
+	}
+
+	// Mean of the cell centers on the unit sphere, so that countries
+	// crossing the antimeridian are not averaged across the globe.
+	let (mut x, mut y, mut z) = (0.0, 0.0, 0.0);
+	for &(latitude, longitude) in &centers {
+		let (latitude, longitude) = (latitude.to_radians(), longitude.to_radians());
+		x += latitude.cos() * longitude.cos();
+		y += latitude.cos() * longitude.sin();
+		z += latitude.sin();
+	}
+	let length = (x * x + y * y + z * z).sqrt();
+	if centers.is_empty() || length < 1e-6 * centers.len() as f64 {
+		return None;
+	}
+	let centroid = ((z / length).asin().to_degrees(), y.atan2(x).to_degrees());
+	let radius_km = centers.iter()
+		.map(|&center| haversine_km(centroid, center))
+		.fold(0.0, f64::max) + CELL_HALF_DIAGONAL_KM;
+	Some((centroid.0, centroid.1, radius_km.ceil().min(MAX_RADIUS_KM)))
+}
+
+impl EarthquakeResponse {
+	/// Groups events by the ISO 3166-1 alpha-2 code of their epicenter country.
+	///
+	/// Reuses the countries resolved by the client-side country filter and
+	/// only looks up the remaining events in the embedded boundaries. Events
+	/// outside every country (e.g. offshore) are skipped.
+	pub fn group_by_country(&self) -> HashMap<String, Vec<&EarthquakeFeatures>> {
+		let mut boundaries = None;
+		self.group_countries(|latitude, longitude| country_at(boundaries.get_or_insert_with(embedded_boundaries), latitude, longitude))
+	}
+
+	/// Like [`EarthquakeResponse::group_by_country`], but looks up events in
+	/// the boundaries of `client`, so grouping agrees with the country filter
+	/// of clients loading their boundaries from a file (see
+	/// [`UsgsClientBuilder::country_boundaries_file`](crate::UsgsClientBuilder::country_boundaries_file)).
+	pub fn group_by_country_with(&self, client: &UsgsClient) -> HashMap<String, Vec<&EarthquakeFeatures>> {
+		self.group_countries(|latitude, longitude| country_at(client.country_boundaries(), latitude, longitude))
+	}
+
+	fn group_countries(&self, mut locate: impl FnMut(f64, f64) -> Option<String>) -> HashMap<String, Vec<&EarthquakeFeatures>> {
+		let mut groups: HashMap<String, Vec<&EarthquakeFeatures>> = HashMap::new();
+		for feature in &self.features {
+			let country = feature.country.clone()
+				.or_else(|| locate(feature.geometry.latitude(), feature.geometry.longitude()));
+			if let Some(country) = country {
+				groups.entry(country).or_default().push(feature);
+			}
+		}
+		groups
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json::json;
+	use crate::UsgsClient;
+	use crate::tests::tests::{feature, response, QUERY_URL};
+	use super::*;
+
+	#[test]
+	fn group_by_country_reuses_and_resolves_countries() {
+		let mut response = response(QUERY_URL, vec![
+			feature("ankara", [32.85, 39.93, 10.0], json!({ "mag": 5.0 })),
+			feature("tokyo", [139.69, 35.69, 30.0], json!({ "mag": 4.0 })),
+			feature("pacific", [-140.0, 0.0, 10.0], json!({ "mag": 6.0 })),
+		]);
+		response.features[1].country = Some("XX".to_string());
+
+		let groups = response.group_by_country_with(&UsgsClient::new());
+		let mut countries: Vec<(&str, usize)> = groups.iter().map(|(country, events)| (country.as_str(), events.len())).collect();
+		countries.sort();
+		assert_eq!(countries, [("TR", 1), ("XX", 1)]);
+	}
+
+	fn codes(codes: &[&str]) -> Vec<String> {
+		codes.iter().map(|code| code.to_string()).collect()
+	}
+
+	fn box_contains((south, north, west, east): (f64, f64, f64, f64), (latitude, longitude): (f64, f64)) -> bool {
+		let longitude = if longitude < west { longitude + 360.0 } else { longitude };
+		(south..=north).contains(&latitude) && (west..=east).contains(&longitude)
+	}
+
+	#[test]
+	fn bounding_box_covers_a_country() {
+		let client = UsgsClient::new();
+		let turkey = bounding_box(client.country_extents(), &codes(&["TR"])).unwrap();
+		for city in [(39.93, 32.85), (41.01, 28.98), (36.2, 36.16), (39.9, 44.0)] {
+			assert!(box_contains(turkey, city), "{:?} {:?}", turkey, city);
+		}
+		assert!(turkey.3 < 180.0 && turkey.3 - turkey.2 < 30.0, "{:?}", turkey);
+
+		let both = bounding_box(client.country_extents(), &codes(&["TR", "JP"])).unwrap();
+		assert!(box_contains(both, (39.93, 32.85)) && box_contains(both, (35.69, 139.69)), "{:?}", both);
+	}
+
+	#[test]
+	fn bounding_box_continues_past_the_antimeridian() {
+		let client = UsgsClient::new();
+		let fiji = bounding_box(client.country_extents(), &codes(&["FJ"])).unwrap();
+		assert!((-180.0..180.0).contains(&fiji.2) && fiji.3 > 180.0, "{:?}", fiji);
+		assert!(fiji.3 - fiji.2 < 20.0, "{:?}", fiji);
+		for island in [(-18.14, 178.44), (-16.43, 179.38), (-18.05, -178.6)] {
+			assert!(box_contains(fiji, island), "{:?} {:?}", fiji, island);
+		}
+
+		let russia = bounding_box(client.country_extents(), &codes(&["RU"])).unwrap();
+		assert!(russia.2 < 21.0 && russia.3 > 180.0, "{:?}", russia);
+		for city in [(54.71, 20.51), (55.75, 37.62), (64.73, 177.5), (66.0, -170.0)] {
+			assert!(box_contains(russia, city), "{:?} {:?}", russia, city);
+		}
+	}
+
+	#[test]
+	fn bounding_box_rejects_unknown_codes() {
+		let client = UsgsClient::new();
+		assert_eq!(bounding_box(client.country_extents(), &codes(&["XX"])), None);
+		assert_eq!(bounding_box(client.country_extents(), &codes(&["TR", "XX"])), None);
+		assert_eq!(bounding_box(client.country_extents(), &[]), None);
+	}
+
+	#[test]
+	fn enclosing_circle_contains_every_cell() {
+		let client = UsgsClient::new();
+		for code in ["TR", "JP", "FJ", "RU"] {
+			let (latitude, longitude, radius_km) = enclosing_circle(client.country_extents(), &codes(&[code])).unwrap();
+			for &(south, west) in &client.country_extents()[code].cells {
+				for corner in [(south, west), (south, west + 1), (south + 1, west), (south + 1, west + 1)] {
+					let corner = (f64::from(corner.0), f64::from(corner.1));
+					assert!(haversine_km((latitude, longitude), corner) <= radius_km, "{} {:?}", code, corner);
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn enclosing_circle_is_centered_across_the_antimeridian() {
+		let client = UsgsClient::new();
+		let (latitude, longitude, radius_km) = enclosing_circle(client.country_extents(), &codes(&["FJ"])).unwrap();
+		assert!((-20.0..-15.0).contains(&latitude) && longitude.abs() > 175.0, "{} {}", latitude, longitude);
+		assert!(radius_km < 2000.0, "{}", radius_km);
+
+		assert_eq!(enclosing_circle(client.country_extents(), &codes(&["XX"])), None);
+		assert_eq!(enclosing_circle(client.country_extents(), &[]), None);
+	}
+}
@@ -0,0 +1,128 @@
+use std::collections::{BTreeMap, HashMap};
+use chrono::NaiveDate;
+use serde::Serialize;
+use crate::country::country::{country_at, embedded_boundaries};
+use crate::models::models::{EarthquakeFeatures, EarthquakeResponse};
+
+/// Summary of one UTC day of a [`daily_digest`].
+#[derive(Serialize, Debug, Clone)]
+pub struct DailyDigest<'a> {
+	/// UTC date summarized.
+	pub date: NaiveDate,
+
+	/// Number of events of the day.
+	pub events: usize,
+
+	/// Summaries per country, most active first.
+	pub countries: Vec<CountryDigest<'a>>,
+}
+
+/// Events of one day in one country.
+#[derive(Serialize, Debug, Clone)]
+pub struct CountryDigest<'a> {
+	/// ISO 3166-1 alpha-2 code of the epicenter country, `None` for events
+	/// outside every country (e.g. offshore).
+	pub country: Option<String>,
+
+	/// Number of events.
+	pub events: usize,
+
+	/// Number of events per whole magnitude, e.g. key `4` counts M4.0 to
+	/// M4.9. Events without a magnitude are left out.
+	pub by_magnitude: BTreeMap<i32, usize>,
+
+	/// Event with the largest magnitude.
+	pub strongest: Option<&'a EarthquakeFeatures>,
+
+	/// Number of events with "Did You Feel It?" reports.
+	pub felt_events: usize,
+
+	/// Total number of "Did You Feel It?" reports.
+	pub felt_reports: u32,
+}
+
+impl<'a> CountryDigest<'a> {
+	fn new(country: Option<String>) -> Self {
+		Self {
+			country,
+			events: 0,
+			by_magnitude: BTreeMap::new(),
+			strongest: None,
+			felt_events: 0,
+			felt_reports: 0,
+		}
+	}
+
+	fn add(&mut self, event: &'a EarthquakeFeatures) {
+		self.events += 1;
+		if let Some(magnitude) = event.properties.magnitude {
+			*self.by_magnitude.entry(magnitude.floor() as i32).or_default() += 1;
+			if self.strongest.and_then(|strongest| strongest.properties.magnitude).is_none_or(|strongest| magnitude > strongest) {
+				self.strongest = Some(event);
+			}
+		}
+		if let Some(felt) = event.properties.felt.filter(|felt| *felt > 0) {
+			self.felt_events += 1;
+			self.felt_reports += felt;
+		}
+	}
+}
+
+/// Summarizes the events of `response` per UTC day and epicenter country,
+/// e.g. for newsletters or status pages.
+///
+/// Days are in chronological order and only days with events are listed.
+/// Countries resolved by the client-side country filter are reused; the
+/// others are looked up in the embedded boundaries. Events without an
+/// origin time are skipped.
+///
+/// ## Example
+/// ```rust,no_run
+/// use usgs_earthquake_api::UsgsClient;
+/// use usgs_earthquake_api::digest::daily_digest;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let response = UsgsClient::new().query()
+///         .filter_by_country_code("")
+///         .start_time(2025, 1, 1, 0, 0)
+///         .min_magnitude(2.5)
+///         .fetch().await.unwrap();
+///     for day in daily_digest(&response) {
+///         println!("{}: {} events", day.date, day.events);
+///         for country in &day.countries {
+///             let name = country.country.as_deref().unwrap_or("offshore");
+///             println!("  {}: {} events, {:?}", name, country.events, country.by_magnitude);
+///         }
+///     }
+/// }
+/// ```
+pub fn daily_digest(response: &EarthquakeResponse) -> Vec<DailyDigest<'_>> {
+	let mut boundaries = None;
+	let mut days: BTreeMap<NaiveDate, HashMap<Option<String>, CountryDigest<'_>>> = BTreeMap::new();
+	for event in &response.features {
+		let Some(origin) = event.origin_time() else {
+			continue;
+		};
+		let country = event.country.clone().or_else(|| {
+			let boundaries = boundaries.get_or_insert_with(embedded_boundaries);
+			country_at(boundaries, event.geometry.latitude(), event.geometry.longitude())
+		});
+		days.entry(origin.date_naive()).or_default()
+			.entry(country.clone())
+			.or_insert_with(|| CountryDigest::new(country))
+			.add(event);
+	}
+
+	days.into_iter()
+		.map(|(date, countries)| {
+			let mut countries: Vec<CountryDigest<'_>> = countries.into_values().collect();
+			countries.sort_by(|a, b| b.events.cmp(&a.events).then_with(|| a.country.cmp(&b.country)));
+			DailyDigest {
+				date,
+				events: countries.iter().map(|country| country.events).sum(),
+				countries,
+			}
+		})
+		.collect()
+}
@@ -0,0 +1,3 @@
+mod digest;
+
+pub use digest::*;
\ No newline at end of file
@@ -1,4 +1,6 @@
+use reqwest::StatusCode;
 use thiserror::Error;
+use crate::models::ids::EventId;
 
 // TODO: Errors can be better
 #[derive(Debug, Error)]
@@ -16,9 +18,175 @@ pub enum UsgsError {
 	#[error("Request error: {0}")]
 	Request(#[from] reqwest::Error),
 
+	#[error("Timed out {0}")]
+	Timeout(TimeoutPhase),
+
+	#[error("Failed to decompress response: {0}")]
+	Decompression(std::io::Error),
+
+	#[error("I/O error: {0}")]
+	Io(#[from] std::io::Error),
+
+	#[error("Unsupported content encoding `{0}`")]
+	UnsupportedEncoding(String),
+
+	#[error("Failed to parse response: {0}")]
+	Json(#[from] serde_json::Error),
+
+	#[error("Failed to parse response at `{path}` (line {line}, column {column}): {message} near `{snippet}`")]
+	Parse { path: String, line: usize, column: usize, message: String, snippet: String },
+
 	#[error("Minimum magnitude cannot be smaller than 0")]
 	MinimumMagnitude,
 
 	#[error("Maximum magnitude cannot be greater than 10")]
 	MaximumMagnitude,
+
+	#[error("Latitude must be between -90 and 90 and longitude between -180 and 180")]
+	InvalidCoordinates,
+
+	#[error("Radius cannot be negative")]
+	InvalidRadius,
+
+	#[error("Invalid query URL: {0}")]
+	InvalidUrl(String),
+
+	#[error("Invalid plate boundary dataset: {0}")]
+	PlateBoundaries(String),
+
+	#[error("Invalid country boundaries dataset: {0}")]
+	CountryBoundaries(String),
+
+	#[error("Invalid cities dataset: {0}")]
+	Cities(String),
+
+	#[error("Failed to draw plot: {0}")]
+	Plot(String),
+
+	#[error("Failed to write shapefile: {0}")]
+	Shapefile(String),
+
+	#[error("Parameter `{0}` is not supported by this service")]
+	UnsupportedParameter(String),
+
+	#[error("Malformed FDSN text response: {0}")]
+	TextFormat(String),
+
+	#[error("Unexpected response schema: {0}")]
+	Schema(String),
+
+	#[error("Malformed service description: {0}")]
+	Capabilities(String),
+
+	#[error("Malformed Atom/RSS feed: {0}")]
+	Feed(String),
+
+	#[error("MQTT publish failed: {0}")]
+	Mqtt(String),
+
+	#[error("HTTP fixture error: {0}")]
+	Fixture(String),
+
+	#[error("Cache backend error: {0}")]
+	Cache(String),
+
+	#[error("Failed to write archive object: {0}")]
+	Archive(String),
+
+	#[error("Invalid query configuration: {0}")]
+	Config(String),
+
+	#[error("No event with id `{0}`")]
+	EventNotFound(EventId),
+
+	#[error("Event `{0}` has no origin time")]
+	MissingOriginTime(EventId),
+
+	#[error("No query profile named `{0}`")]
+	UnknownQueryProfile(String),
+
+	#[error("API answered with status {0}")]
+	ApiStatus(StatusCode),
+
+	#[error("API request failed with {status}: {message}")]
+	Api { status: StatusCode, message: String },
+}
+
+/// Phase of a request in which a timeout elapsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeoutPhase {
+	/// Establishing the connection
+	Connect,
+
+	/// Waiting for response data, or the total per-request timeout
+	Read,
+
+	/// Overall deadline of a fetch, including retries and backoff
+	Deadline,
+}
+
+impl std::fmt::Display for TimeoutPhase {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let phase = match self {
+			TimeoutPhase::Connect => "while connecting",
+			TimeoutPhase::Read => "while reading the response",
+			TimeoutPhase::Deadline => "after exceeding the fetch deadline",
+		};
+		write!(f, "{}", phase)
+	}
+}
+
+impl UsgsError {
+	/// Classifies an HTTP client error, reporting timeouts with their phase.
+	pub(crate) fn request(err: reqwest::Error) -> Self {
+		match (err.is_timeout(), err.is_connect()) {
+			(true, true) => UsgsError::Timeout(TimeoutPhase::Connect),
+			(true, false) => UsgsError::Timeout(TimeoutPhase::Read),
+			_ => UsgsError::Request(err),
+		}
+	}
+
+	/// Reads the JSON error document sent by USGS with `jsonerror=true`,
+	/// `{"metadata": {"status": 400, "error": "..."}}`.
+	pub(crate) fn json_api(body: &str) -> Option<Self> {
+		let document: serde_json::Value = serde_json::from_str(body).ok()?;
+		let metadata = document.get("metadata")?;
+		let message = metadata.get("error")?.as_str()?.trim().to_string();
+		let status = metadata.get("status")
+			.and_then(serde_json::Value::as_u64)
+			.and_then(|status| StatusCode::from_u16(u16::try_from(status).ok()?).ok())
+			.unwrap_or(StatusCode::BAD_REQUEST);
+		Some(UsgsError::Api { status, message })
+	}
+
+	/// Builds an [`UsgsError::Api`] from an unsuccessful response, extracting
+	/// the explanation from the JSON error document or the FDSN text error
+	/// document (`Error 400: Bad Request`, the message, then usage details).
+	pub(crate) fn api(status: StatusCode, body: &str) -> Self {
+		if let Some(UsgsError::Api { message, .. }) = Self::json_api(body) {
+			return UsgsError::Api { status, message };
+		}
+		let message: Vec<&str> = body.split("\n\n")
+			.map(str::trim)
+			.filter(|paragraph| !paragraph.is_empty())
+			.skip_while(|paragraph| paragraph.starts_with("Error "))
+			.take_while(|paragraph| !paragraph.starts_with("Usage details") && !paragraph.starts_with("Request:"))
+			.collect();
+		let message = match message.join(" ") {
+			message if message.is_empty() => status.canonical_reason().unwrap_or("unknown error").to_string(),
+			message => message.replace('\n', " "),
+		};
+		UsgsError::Api { status, message }
+	}
+
+	/// Returns `true` for failures worth retrying later: timeouts, connection
+	/// errors, rate limiting and server errors.
+	pub fn is_retryable(&self) -> bool {
+		match self {
+			UsgsError::Request(err) => err.is_timeout() || err.is_connect(),
+			UsgsError::Timeout(phase) => *phase != TimeoutPhase::Deadline,
+			UsgsError::Api { status, .. } | UsgsError::ApiStatus(status) => status.is_server_error() || *status == StatusCode::TOO_MANY_REQUESTS,
+			_ => false,
+		}
+	}
 }
\ No newline at end of file
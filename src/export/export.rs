@@ -0,0 +1,191 @@
+use std::io::Write;
+use std::path::Path;
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde_json::{json, Value};
+use crate::error::error::UsgsError;
+use crate::models::models::{EarthquakeFeatures, EarthquakeResponse};
+
+const LEAFLET_VERSION: &str = "1.9.4";
+
+/// Escapes text for inclusion in HTML content.
+pub(crate) fn escape_html(text: &str) -> String {
+	text.replace('&', "&amp;")
+		.replace('<', "&lt;")
+		.replace('>', "&gt;")
+		.replace('"', "&quot;")
+}
+
+/// Renders the events as a standalone HTML page with a Leaflet map.
+///
+/// The events are embedded as a GeoJSON `FeatureCollection`; the Leaflet
+/// library and OpenStreetMap tiles are loaded from public CDNs when the page
+/// is opened.
+pub fn leaflet_html_string(response: &EarthquakeResponse) -> Result<String, UsgsError> {
+	// `<\/` keeps a `</script>` inside a string value from closing the tag.
+	let geojson = serde_json::to_string(response)?.replace("</", "<\\/");
+	let title = escape_html(&response.metadata.title);
+
+	Ok(format!(r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{title}</title>
+<link rel="stylesheet" href="https://unpkg.com/leaflet@{LEAFLET_VERSION}/dist/leaflet.css">
+<script src="https://unpkg.com/leaflet@{LEAFLET_VERSION}/dist/leaflet.js"></script>
+<style>
+html, body, #map {{ height: 100%; margin: 0; }}
+</style>
+</head>
+<body>
+<div id="map"></div>
+<script>
+const events = {geojson};
+
+function color(depth) {{
+	return depth < 70 ? "#d73027" : depth < 300 ? "#fecc3e" : "#3168b2";
+}}
+
+function escape(text) {{
+	const element = document.createElement("span");
+	element.textContent = text == null ? "" : String(text);
+	return element.innerHTML;
+}}
+
+const map = L.map("map").setView([0, 0], 2);
+L.tileLayer("https://{{s}}.tile.openstreetmap.org/{{z}}/{{x}}/{{y}}.png", {{
+	maxZoom: 18,
+	attribution: "&copy; OpenStreetMap contributors"
+}}).addTo(map);
+
+const layer = L.geoJSON(events, {{
+	pointToLayer: (feature, latlng) => L.circleMarker(latlng, {{
+		radius: Math.max(3, (feature.properties.mag || 0) * 2),
+		color: "#333",
+		weight: 1,
+		fillColor: color(feature.geometry.coordinates[2]),
+		fillOpacity: 0.75
+	}}),
+	onEachFeature: (feature, marker) => {{
+		const p = feature.properties;
+		const time = p.time == null ? "" : new Date(p.time).toISOString();
+		const link = p.url ? `<br><a href="${{escape(p.url)}}" target="_blank">Event page</a>` : "";
+		marker.bindPopup(`<b>${{escape(p.title || feature.id)}}</b><br>${{escape(time)}}<br>Depth: ${{escape(feature.geometry.coordinates[2])}} km${{link}}`);
+	}}
+}}).addTo(map);
+
+if (events.features.length > 0) {{
+	map.fitBounds(layer.getBounds(), {{ padding: [20, 20], maxZoom: 8 }});
+}}
+</script>
+</body>
+</html>
+"##))
+}
+
+/// Writes the events to `path` as a standalone HTML page with a Leaflet map,
+/// handy for sharing a quick situational snapshot by email.
+///
+/// See [`leaflet_html_string`] for the page contents.
+pub fn leaflet_html(response: &EarthquakeResponse, path: impl AsRef<Path>) -> Result<(), UsgsError> {
+	std::fs::write(path, leaflet_html_string(response)?)?;
+	Ok(())
+}
+
+/// Writes the events as CSV with a WKT geometry column, ready for
+/// `COPY events (id, time, mag, geom) FROM STDIN WITH (FORMAT csv, HEADER)`
+/// into PostGIS or similar spatial databases.
+///
+/// Columns: `id`, `time` (ISO 8601, UTC), `mag` and `geom`
+/// ([`EarthquakeGeometry::to_wkt`](crate::EarthquakeGeometry::to_wkt)).
+/// Missing values are written as empty fields.
+pub fn write_wkt_csv(response: &EarthquakeResponse, mut writer: impl Write) -> Result<(), UsgsError> {
+	writeln!(writer, "id,time,mag,geom")?;
+	for feature in &response.features {
+		let time = feature.properties.time.and_then(iso_time).unwrap_or_default();
+		let magnitude = feature.properties.magnitude.map(|m| m.to_string()).unwrap_or_default();
+		writeln!(writer, "\"{}\",{},{},\"{}\"", feature.id.replace('"', "\"\""), time, magnitude, feature.geometry.to_wkt())?;
+	}
+	Ok(())
+}
+
+fn iso_time(millis: u64) -> Option<String> {
+	DateTime::<Utc>::from_timestamp_millis(millis as i64).map(|time| time.to_rfc3339_opts(SecondsFormat::Millis, true))
+}
+
+/// Point color by depth: shallow red, intermediate yellow, deep blue.
+fn depth_rgba(depth_km: f64) -> [u8; 4] {
+	match depth_km {
+		depth if depth < 70.0 => [215, 48, 39, 220],
+		depth if depth < 300.0 => [254, 204, 62, 220],
+		_ => [49, 104, 178, 220],
+	}
+}
+
+fn czml_packet(feature: &EarthquakeFeatures, start: &str, end: &str) -> Value {
+	let properties = &feature.properties;
+	let magnitude = properties.magnitude.unwrap_or_default();
+	let geometry = &feature.geometry;
+	let description = format!("<p>{}</p><p>Depth: {:.1} km</p>{}",
+		escape_html(properties.place.as_deref().unwrap_or_default()),
+		geometry.depth_km(),
+		properties.url.as_deref().map(|url| format!(r#"<p><a href="{0}" target="_blank">{0}</a></p>"#, escape_html(url))).unwrap_or_default());
+
+	json!({
+		"id": feature.id,
+		"name": properties.title.as_deref().unwrap_or(&feature.id),
+		"description": description,
+		"availability": format!("{start}/{end}"),
+		"position": {
+			"cartographicDegrees": [geometry.longitude(), geometry.latitude(), -geometry.depth_km() * 1000.0],
+		},
+		"point": {
+			"pixelSize": (magnitude * 3.0).max(4.0),
+			"color": { "rgba": depth_rgba(geometry.depth_km()) },
+			"outlineColor": { "rgba": [0, 0, 0, 255] },
+			"outlineWidth": 1,
+		},
+	})
+}
+
+impl EarthquakeResponse {
+	/// Converts the events to a CZML document for CesiumJS.
+	///
+	/// The document clock spans the first to the last event, and every event
+	/// packet becomes available at its origin time, so the events appear in
+	/// order when the timeline is animated. Points are placed at the
+	/// hypocenter (negative height), sized by magnitude and colored by depth.
+	/// Events without a time are skipped.
+	pub fn to_czml(&self) -> Value {
+		let mut events: Vec<(&EarthquakeFeatures, String)> = self.features.iter()
+			.filter_map(|f| Some((f, iso_time(f.properties.time?)?)))
+			.collect();
+		events.sort_by_key(|(f, _)| f.properties.time);
+
+		let start = events.first().map(|(_, time)| time.clone());
+		// End a minute after the last event so it is shown before the clock stops.
+		let end = events.last().map(|(f, _)| f.properties.time.unwrap_or_default())
+			.and_then(|last| iso_time(last + 60_000));
+
+		let mut document = json!({
+			"id": "document",
+			"name": self.metadata.title,
+			"version": "1.0",
+		});
+		if let (Some(start), Some(end)) = (&start, &end) {
+			document["clock"] = json!({
+				"interval": format!("{start}/{end}"),
+				"currentTime": start,
+				"multiplier": 3600,
+				"range": "LOOP_STOP",
+				"step": "SYSTEM_CLOCK_MULTIPLIER",
+			});
+		}
+
+		let mut packets = vec![document];
+		if let Some(end) = &end {
+			packets.extend(events.iter().map(|(feature, time)| czml_packet(feature, time, end)));
+		}
+		Value::Array(packets)
+	}
+}
\ No newline at end of file
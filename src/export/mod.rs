@@ -0,0 +1,7 @@
+mod export;
+#[cfg(feature = "shapefile")]
+mod shp;
+
+pub use export::*;
+#[cfg(feature = "shapefile")]
+pub use shp::*;
\ No newline at end of file
@@ -0,0 +1,89 @@
+use std::path::Path;
+use chrono::{DateTime, SecondsFormat};
+use shapefile::dbase::{FieldName, FieldValue, Record, TableWriterBuilder};
+use shapefile::{Point, Writer};
+use crate::error::error::UsgsError;
+use crate::models::models::{EarthquakeFeatures, EarthquakeResponse};
+
+/// WGS 84 geographic coordinate system, written to the `.prj` file.
+const WGS84_PRJ: &str = r#"GEOGCS["GCS_WGS_1984",DATUM["D_WGS_1984",SPHEROID["WGS_1984",6378137.0,298.257223563]],PRIMEM["Greenwich",0.0],UNIT["Degree",0.0174532925199433]]"#;
+
+/// Maximum length of a dBASE character field.
+const MAX_CHARACTER_LENGTH: u8 = 254;
+
+fn shapefile_error(err: impl std::fmt::Display) -> UsgsError {
+	UsgsError::Shapefile(err.to_string())
+}
+
+fn field(name: &str) -> FieldName {
+	FieldName::try_from(name).expect("field names are at most 10 characters")
+}
+
+/// Truncates to at most `max` bytes on a character boundary.
+fn character(value: Option<&str>, max: u8) -> FieldValue {
+	FieldValue::Character(value.map(|value| {
+		let mut end = value.len().min(max as usize);
+		while !value.is_char_boundary(end) {
+			end -= 1;
+		}
+		value[..end].to_string()
+	}))
+}
+
+fn record(feature: &EarthquakeFeatures) -> Record {
+	let properties = &feature.properties;
+	let time = properties.time
+		.and_then(|time| DateTime::from_timestamp_millis(time as i64))
+		.map(|time| time.to_rfc3339_opts(SecondsFormat::Millis, true));
+
+	let mut record = Record::default();
+	record.insert("id".to_string(), character(Some(&feature.id), 40));
+	record.insert("time".to_string(), character(time.as_deref(), 24));
+	record.insert("mag".to_string(), FieldValue::Numeric(properties.magnitude));
+	record.insert("mag_type".to_string(), character(properties.magnitude_type.as_deref(), 10));
+	record.insert("depth_km".to_string(), FieldValue::Numeric(Some(feature.geometry.depth_km())));
+	record.insert("place".to_string(), character(properties.place.as_deref(), MAX_CHARACTER_LENGTH));
+	record.insert("alert".to_string(), character(properties.alert_level.as_deref(), 6));
+	record.insert("sig".to_string(), FieldValue::Numeric(properties.sig.map(f64::from)));
+	record.insert("felt".to_string(), FieldValue::Numeric(properties.felt.map(f64::from)));
+	record.insert("tsunami".to_string(), FieldValue::Logical(properties.tsunami.map(|t| t != 0)));
+	record.insert("status".to_string(), character(properties.status.as_deref(), 20));
+	record.insert("event_type".to_string(), character(properties.event_type.as_deref(), 30));
+	record.insert("url".to_string(), character(properties.url.as_deref(), MAX_CHARACTER_LENGTH));
+	record
+}
+
+/// Writes the events as an ESRI point shapefile (`.shp`, `.shx`, `.dbf` and
+/// a WGS 84 `.prj`) next to `path`.
+///
+/// Attribute columns: `id`, `time` (ISO 8601, UTC), `mag`, `mag_type`,
+/// `depth_km`, `place`, `alert`, `sig`, `felt`, `tsunami`, `status`,
+/// `event_type` and `url`. Text longer than a dBASE field allows is
+/// truncated.
+pub fn write_shapefile(response: &EarthquakeResponse, path: impl AsRef<Path>) -> Result<(), UsgsError> {
+	let path = path.as_ref().with_extension("shp");
+	let table = TableWriterBuilder::new()
+		.add_character_field(field("id"), 40)
+		.add_character_field(field("time"), 24)
+		.add_numeric_field(field("mag"), 6, 2)
+		.add_character_field(field("mag_type"), 10)
+		.add_numeric_field(field("depth_km"), 10, 3)
+		.add_character_field(field("place"), MAX_CHARACTER_LENGTH)
+		.add_character_field(field("alert"), 6)
+		.add_numeric_field(field("sig"), 6, 0)
+		.add_numeric_field(field("felt"), 10, 0)
+		.add_logical_field(field("tsunami"))
+		.add_character_field(field("status"), 20)
+		.add_character_field(field("event_type"), 30)
+		.add_character_field(field("url"), MAX_CHARACTER_LENGTH);
+
+	let mut writer = Writer::from_path(&path, table).map_err(shapefile_error)?;
+	for feature in &response.features {
+		let point = Point::new(feature.geometry.longitude(), feature.geometry.latitude());
+		writer.write_shape_and_record(&point, &record(feature)).map_err(shapefile_error)?;
+	}
+	drop(writer);
+
+	std::fs::write(path.with_extension("prj"), WGS84_PRJ)?;
+	Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,170 @@
+use serde::Deserialize;
+use crate::error::error::UsgsError;
+use crate::models::ids::CatalogId;
+use super::fdsn::{FdsnProfile, ResponseFormat, STANDARD_PARAMETERS};
+
+/// Query parameter declared in a service's `application.wadl`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceParameter {
+	/// Parameter name (e.g. `"minmagnitude"`).
+	pub name: String,
+
+	/// Declared XML schema type (e.g. `"xs:double"`), if any.
+	pub param_type: Option<String>,
+
+	/// Allowed values, for enumerated parameters such as `format`.
+	pub options: Vec<String>,
+}
+
+/// What an FDSN event service supports, as described by its
+/// `application.wadl` and catalog/contributor resources.
+///
+/// Obtained with [`UsgsClient::capabilities`](crate::UsgsClient::capabilities).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Capabilities {
+	/// Query parameters accepted by the `query` resource.
+	pub parameters: Vec<ServiceParameter>,
+
+	/// Values accepted by the `format` parameter.
+	pub formats: Vec<String>,
+
+	/// Catalogs events can be limited to.
+	pub catalogs: Vec<CatalogId>,
+
+	/// Contributors events can be limited to.
+	pub contributors: Vec<String>,
+
+	/// Event types (USGS application document only).
+	pub event_types: Vec<String>,
+
+	/// Magnitude types (USGS application document only).
+	pub magnitude_types: Vec<String>,
+
+	/// Product types (USGS application document only).
+	pub product_types: Vec<String>,
+}
+
+/// The USGS `application.json` document.
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+struct ApplicationDocument {
+	catalogs: Vec<CatalogId>,
+	contributors: Vec<String>,
+	eventtypes: Vec<String>,
+	magnitudetypes: Vec<String>,
+	producttypes: Vec<String>,
+}
+
+/// Value of attribute `name` in the opening tag `tag`.
+fn attribute(tag: &str, name: &str) -> Option<String> {
+	let pattern = format!(" {}=", name);
+	let start = tag.find(&pattern)? + pattern.len();
+	let quote = tag[start..].chars().next().filter(|c| *c == '"' || *c == '\'')?;
+	let value = &tag[start + 1..];
+	Some(value[..value.find(quote)?].to_string())
+}
+
+/// Text of every `<element>...</element>` in `xml`, ignoring namespace prefixes.
+fn element_texts(xml: &str, element: &str) -> Vec<String> {
+	let mut texts = Vec::new();
+	let mut rest = xml;
+	while let Some(open) = rest.find('<') {
+		rest = &rest[open + 1..];
+		let Some(close) = rest.find('>') else { break };
+		let tag = &rest[..close];
+		let local = tag.rsplit(':').next().unwrap_or(tag);
+		rest = &rest[close + 1..];
+		if local == element {
+			let end = rest.find("</").unwrap_or(rest.len());
+			texts.push(rest[..end].trim().to_string());
+			rest = &rest[end..];
+		}
+	}
+	texts
+}
+
+impl Capabilities {
+	/// Reads the query parameters and formats from an `application.wadl` document.
+	pub fn from_wadl(wadl: &str) -> Result<Self, UsgsError> {
+		let mut parameters: Vec<ServiceParameter> = Vec::new();
+		let mut rest = wadl;
+		while let Some(open) = rest.find('<') {
+			rest = &rest[open + 1..];
+			let Some(close) = rest.find('>') else { break };
+			let tag = format!(" {}", &rest[..close]);
+			rest = &rest[close + 1..];
+			let local = tag.split_whitespace().next().unwrap_or_default();
+			let local = local.rsplit(':').next().unwrap_or(local);
+			match local {
+				"param" => {
+					let Some(name) = attribute(&tag, "name") else { continue };
+					if parameters.iter().any(|p| p.name == name) {
+						continue;
+					}
+					parameters.push(ServiceParameter {
+						name,
+						param_type: attribute(&tag, "type"),
+						options: Vec::new(),
+					});
+				}
+				// Options belong to the last declared parameter.
+				"option" => {
+					if let (Some(value), Some(parameter)) = (attribute(&tag, "value"), parameters.last_mut()) {
+						parameter.options.push(value);
+					}
+				}
+				_ => {}
+			}
+		}
+		if parameters.is_empty() {
+			return Err(UsgsError::Capabilities("no parameters declared".to_string()));
+		}
+
+		let formats = parameters.iter()
+			.find(|p| p.name == "format")
+			.map(|p| p.options.clone())
+			.unwrap_or_default();
+		Ok(Self {
+			parameters,
+			formats,
+			..Default::default()
+		})
+	}
+
+	/// Adds catalogs, contributors and type lists from a USGS
+	/// `application.json` document.
+	pub fn merge_application_json(&mut self, json: &str) -> Result<(), UsgsError> {
+		let document: ApplicationDocument = serde_json::from_str(json)?;
+		self.catalogs = document.catalogs;
+		self.contributors = document.contributors;
+		self.event_types = document.eventtypes;
+		self.magnitude_types = document.magnitudetypes;
+		self.product_types = document.producttypes;
+		Ok(())
+	}
+
+	/// Reads the names of a `catalogs` (`<Catalog>`) or `contributors`
+	/// (`<Contributor>`) resource.
+	pub(crate) fn parse_name_list(xml: &str, element: &str) -> Vec<String> {
+		element_texts(xml, element).into_iter().filter(|name| !name.is_empty()).collect()
+	}
+
+	/// Returns `true` if the service declares the query parameter.
+	pub fn supports(&self, parameter: &str) -> bool {
+		self.parameters.iter().any(|p| p.name == parameter)
+	}
+
+	/// Profile matching these capabilities: GeoJSON if offered (text
+	/// otherwise) and every declared non-standard parameter as an extension.
+	pub fn profile(&self, name: &str, base_url: &str) -> FdsnProfile {
+		let mut profile = FdsnProfile::standard(name, base_url);
+		if self.formats.iter().any(|format| format == "geojson") {
+			profile.format = ResponseFormat::GeoJson;
+		}
+		profile.extensions = self.parameters.iter()
+			.map(|p| p.name.clone())
+			.filter(|name| !STANDARD_PARAMETERS.contains(&name.as_str()))
+			.collect();
+		profile
+	}
+}
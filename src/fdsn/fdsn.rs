@@ -0,0 +1,236 @@
+use chrono::{NaiveDateTime, Utc};
+use reqwest::{StatusCode, Url};
+use crate::error::error::UsgsError;
+use crate::models::ids::{EventId, NetworkCode};
+use crate::models::models::{EarthquakeFeatures, EarthquakeGeometry, EarthquakeMetadata, EarthquakeProperties, EarthquakeResponse};
+
+/// Query parameters defined by the FDSN event 1.x specification.
+///
+/// Every compliant service is expected to understand these.
+pub(crate) const STANDARD_PARAMETERS: &[&str] = &[
+	"starttime", "endtime", "minlatitude", "maxlatitude", "minlongitude", "maxlongitude",
+	"latitude", "longitude", "minradius", "maxradius", "mindepth", "maxdepth",
+	"minmagnitude", "maxmagnitude", "magnitudetype", "includeallorigins", "includeallmagnitudes",
+	"includearrivals", "eventid", "limit", "offset", "orderby", "catalog", "contributor",
+	"updatedafter", "format", "nodata",
+];
+
+/// Response format requested from an FDSN event service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseFormat {
+	/// USGS GeoJSON (`format=geojson`).
+	GeoJson,
+
+	/// Pipe-separated FDSN text format (`format=text`).
+	Text,
+}
+
+/// Compatibility profile of an FDSN event 1.x service.
+///
+/// Describes which response format to request and which non-standard
+/// parameters the service accepts, so the same query can run against
+/// USGS, EMSC, IRIS or GeoNet.
+#[derive(Debug, Clone)]
+pub struct FdsnProfile {
+	/// Human-readable name of the service.
+	pub name: String,
+
+	/// Default query endpoint of the service.
+	pub base_url: String,
+
+	/// Format requested from the service.
+	pub format: ResponseFormat,
+
+	/// Non-standard parameters the service accepts (e.g. `"alertlevel"`).
+	pub extensions: Vec<String>,
+
+	/// Endpoint serving scenario (hypothetical) events, if the service has one.
+	pub scenario_base_url: Option<String>,
+}
+
+impl FdsnProfile {
+	/// Creates a profile for a service that only implements the FDSN standard.
+	pub fn standard(name: &str, base_url: &str) -> Self {
+		Self {
+			name: name.to_string(),
+			base_url: base_url.to_string(),
+			format: ResponseFormat::Text,
+			extensions: Vec::new(),
+			scenario_base_url: None,
+		}
+	}
+
+	/// Profile of the USGS ComCat event service.
+	pub fn usgs() -> Self {
+		Self {
+			name: "USGS".to_string(),
+			base_url: "https://earthquake.usgs.gov/fdsnws/event/1/query".to_string(),
+			format: ResponseFormat::GeoJson,
+			extensions: ["alertlevel", "includedeleted", "includesuperseded", "jsonerror", "maxradiuskm", "minfelt", "minsig"].iter().map(|p| p.to_string()).collect(),
+			scenario_base_url: Some("https://earthquake.usgs.gov/fdsnws/scenario/1/query".to_string()),
+		}
+	}
+
+	/// Profile of the EMSC seismic portal.
+	pub fn emsc() -> Self {
+		Self::standard("EMSC", "https://www.seismicportal.eu/fdsnws/event/1/query")
+	}
+
+	/// Profile of the IRIS (EarthScope) event service.
+	pub fn iris() -> Self {
+		Self::standard("IRIS", "https://service.iris.edu/fdsnws/event/1/query")
+	}
+
+	/// Profile of the GeoNet (New Zealand) event service.
+	pub fn geonet() -> Self {
+		Self::standard("GeoNet", "https://service.geonet.org.nz/fdsnws/event/1/query")
+	}
+
+	/// Returns `true` if the service accepts the given query parameter.
+	pub fn supports(&self, parameter: &str) -> bool {
+		STANDARD_PARAMETERS.contains(&parameter) || self.extensions.iter().any(|p| p == parameter)
+	}
+
+	/// Value of the `format` query parameter for this profile.
+	pub fn format_parameter(&self) -> &'static str {
+		match self.format {
+			ResponseFormat::GeoJson => "geojson",
+			ResponseFormat::Text => "text",
+		}
+	}
+}
+
+fn parse_text_time(value: &str) -> Result<u64, UsgsError> {
+	let value = value.trim().trim_end_matches('Z');
+	let time = NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f")
+		.map_err(|_| UsgsError::TextFormat(format!("invalid time `{}`", value)))?;
+	Ok(time.and_utc().timestamp_millis().max(0) as u64)
+}
+
+fn parse_text_number(value: &str, column: &str) -> Result<f32, UsgsError> {
+	value.trim().parse()
+		.map_err(|_| UsgsError::TextFormat(format!("invalid {} `{}`", column, value)))
+}
+
+fn optional_text(value: Option<&&str>) -> Option<String> {
+	value.map(|v| v.trim()).filter(|v| !v.is_empty()).map(|v| v.to_string())
+}
+
+/// Parses an FDSN text-format body into an [`EarthquakeResponse`].
+///
+/// Columns: `EventID|Time|Latitude|Longitude|Depth/km|Author|Catalog|Contributor|ContributorID|MagType|Magnitude|MagAuthor|EventLocationName[|EventType]`.
+pub(crate) fn parse_text_response(body: &str, url: &str, profile: &FdsnProfile) -> Result<EarthquakeResponse, UsgsError> {
+	let mut features = Vec::new();
+
+	for line in body.lines() {
+		let line = line.trim();
+		if line.is_empty() || line.starts_with('#') {
+			continue;
+		}
+
+		let columns: Vec<&str> = line.split('|').collect();
+		if columns.len() < 13 {
+			return Err(UsgsError::TextFormat(format!("expected at least 13 columns, found {}", columns.len())));
+		}
+
+		let latitude = parse_text_number(columns[2], "latitude")?;
+		let longitude = parse_text_number(columns[3], "longitude")?;
+		let depth = parse_text_number(columns[4], "depth").unwrap_or(0.0);
+		let magnitude = columns[10].trim().parse::<f64>().ok();
+		let place = optional_text(columns.get(12));
+
+		let properties = EarthquakeProperties {
+			magnitude,
+			time: Some(parse_text_time(columns[1])?),
+			net: optional_text(columns.get(7)).map(NetworkCode::from),
+			code: optional_text(columns.get(8)),
+			sources: optional_text(columns.get(6)),
+			magnitude_type: optional_text(columns.get(9)),
+			event_type: optional_text(columns.get(13)),
+			title: magnitude.map(|m| format!("M {:.1} - {}", m, place.as_deref().unwrap_or(""))),
+			place,
+			..Default::default()
+		};
+
+		features.push(EarthquakeFeatures {
+			feature_type: "Feature".to_string(),
+			properties,
+			geometry: EarthquakeGeometry {
+				geometry_type: "Point".to_string(),
+				coordinates: vec![longitude, latitude, depth],
+			},
+			id: EventId::from(columns[0].trim()),
+			country: None,
+		});
+	}
+
+	let mut response = EarthquakeResponse {
+		data_type: "FeatureCollection".to_string(),
+		metadata: EarthquakeMetadata {
+			generated_timestamp: Utc::now(),
+			url: Url::parse(url).map_err(|e| UsgsError::InvalidUrl(e.to_string()))?,
+			title: format!("{} Earthquakes", profile.name),
+			status: StatusCode::OK,
+			api_version: "1".to_string(),
+			count: features.len() as u32,
+			#[cfg(feature = "extra-fields")]
+			extra: Default::default(),
+		},
+		features,
+		bbox: None,
+	};
+	response.recompute_bbox();
+	Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const URL: &str = "https://www.seismicportal.eu/fdsnws/event/1/query?format=text&limit=2";
+
+	#[test]
+	fn parses_text_with_empty_columns() {
+		let body = "#EventID|Time|Latitude|Longitude|Depth/km|Author|Catalog|Contributor|ContributorID|MagType|Magnitude|MagAuthor|EventLocationName|EventType\n\
+			20240610_0000123|2024-06-10T12:31:40.2Z|38.1234|26.5432|10.0|EMSC|EMSC-RTS|EMSC|1654321|ml|3.4|EMSC|DODECANESE ISLANDS, GREECE|ke\n\
+			\n\
+			gfz2024lmno|2024-06-10T13:00:00|-15.5|-173.1||GFZ|GFZ|||||GFZ|TONGA ISLANDS\n";
+		let response = parse_text_response(body, URL, &FdsnProfile::emsc()).unwrap();
+		assert_eq!(response.metadata.count, 2);
+
+		let full = &response.features[0];
+		assert_eq!(full.id, "20240610_0000123");
+		assert_eq!(full.properties.time, Some(1718022700200));
+		assert_eq!(full.properties.magnitude, Some(3.4));
+		assert_eq!(full.properties.magnitude_type.as_deref(), Some("ml"));
+		assert_eq!(full.properties.net.as_ref().map(|net| net.as_str()), Some("EMSC"));
+		assert_eq!(full.properties.code.as_deref(), Some("1654321"));
+		assert_eq!(full.properties.event_type.as_deref(), Some("ke"));
+		assert_eq!(full.properties.title.as_deref(), Some("M 3.4 - DODECANESE ISLANDS, GREECE"));
+		assert_eq!(full.geometry.coordinates, [26.5432, 38.1234, 10.0]);
+
+		let sparse = &response.features[1];
+		assert_eq!(sparse.id, "gfz2024lmno");
+		assert_eq!(sparse.geometry.coordinates, [-173.1, -15.5, 0.0]);
+		assert_eq!(sparse.properties.magnitude, None);
+		assert_eq!(sparse.properties.title, None);
+		assert_eq!(sparse.properties.net, None);
+		assert_eq!(sparse.properties.code, None);
+		assert_eq!(sparse.properties.magnitude_type, None);
+		assert_eq!(sparse.properties.event_type, None);
+		assert_eq!(sparse.properties.place.as_deref(), Some("TONGA ISLANDS"));
+		assert_eq!(response.bbox, Some(vec![-173.1, -15.5, 0.0, 26.5432, 38.1234, 10.0]));
+	}
+
+	#[test]
+	fn rejects_malformed_text() {
+		let short = "ev1|2024-06-10T12:31:40|38.1|26.5|10|EMSC|EMSC|EMSC|1|ml|3.4|EMSC\n";
+		assert!(matches!(parse_text_response(short, URL, &FdsnProfile::emsc()), Err(UsgsError::TextFormat(_))));
+
+		let latitude = "ev1|2024-06-10T12:31:40|north|26.5|10|EMSC|EMSC|EMSC|1|ml|3.4|EMSC|GREECE\n";
+		assert!(matches!(parse_text_response(latitude, URL, &FdsnProfile::emsc()), Err(UsgsError::TextFormat(_))));
+
+		let time = "ev1|10/06/2024|38.1|26.5|10|EMSC|EMSC|EMSC|1|ml|3.4|EMSC|GREECE\n";
+		assert!(matches!(parse_text_response(time, URL, &FdsnProfile::emsc()), Err(UsgsError::TextFormat(_))));
+	}
+}
@@ -0,0 +1,246 @@
+use chrono::{DateTime, NaiveDateTime};
+use quick_xml::escape::resolve_predefined_entity;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::{Reader, XmlVersion};
+use reqwest::{StatusCode, Url};
+use crate::error::error::UsgsError;
+use crate::models::ids::{EventId, NetworkCode};
+use crate::models::models::{EarthquakeFeatures, EarthquakeGeometry, EarthquakeMetadata, EarthquakeProperties, EarthquakeResponse};
+
+/// Stands in for the URL of feeds without a (valid) link.
+const UNKNOWN_FEED_URL: &str = "https://earthquake.usgs.gov/earthquakes/feed/";
+
+fn feed_error(err: impl std::fmt::Display) -> UsgsError {
+	UsgsError::Feed(err.to_string())
+}
+
+/// Atom `<entry>` or RSS `<item>` fields collected while parsing.
+#[derive(Debug, Default)]
+struct Entry {
+	id: Option<String>,
+	title: Option<String>,
+	link: Option<String>,
+	updated: Option<String>,
+	published: Option<String>,
+	summary: Option<String>,
+	point: Option<String>,
+	elevation: Option<String>,
+}
+
+/// Parses an Atom (`2024-01-01T12:00:00.000Z`) or RSS (RFC 2822) timestamp to
+/// milliseconds since the Unix epoch.
+fn parse_timestamp(value: &str) -> Option<u64> {
+	let value = value.trim();
+	let time = DateTime::parse_from_rfc3339(value)
+		.or_else(|_| DateTime::parse_from_rfc2822(value))
+		.ok()?;
+	u64::try_from(time.timestamp_millis()).ok()
+}
+
+/// Origin time from the `<dt>Time</dt><dd>2024-01-01 12:00:00 UTC</dd>` row of
+/// a USGS entry summary.
+fn summary_time(summary: &str) -> Option<u64> {
+	let row = &summary[summary.find("Time</dt>")?..];
+	let start = row.find("<dd>")? + "<dd>".len();
+	let end = start + row[start..].find("</dd>")?;
+	let time = NaiveDateTime::parse_from_str(row[start..end].trim(), "%Y-%m-%d %H:%M:%S UTC").ok()?;
+	u64::try_from(time.and_utc().timestamp_millis()).ok()
+}
+
+/// Splits a USGS title such as `M 4.6 - 100 km SSW of Town, Country` into
+/// magnitude and place.
+fn parse_title(title: &str) -> (Option<f64>, Option<String>) {
+	let Some(rest) = title.strip_prefix("M ") else {
+		return (None, Some(title.to_string()));
+	};
+	let (magnitude, place) = rest.split_once(" - ").unwrap_or((rest, ""));
+	let place = Some(place.trim()).filter(|place| !place.is_empty()).map(str::to_string);
+	(magnitude.trim().parse().ok(), place)
+}
+
+impl Entry {
+	fn into_feature(self) -> Result<EarthquakeFeatures, UsgsError> {
+		let point = self.point.ok_or_else(|| feed_error("entry without georss:point"))?;
+		let mut coordinates = point.split_whitespace().map(str::parse::<f32>);
+		let (Some(Ok(latitude)), Some(Ok(longitude))) = (coordinates.next(), coordinates.next()) else {
+			return Err(feed_error(format!("invalid georss:point `{}`", point)));
+		};
+		let depth = self.elevation.as_deref()
+			.and_then(|elevation| elevation.trim().parse::<f32>().ok())
+			.map(|elevation| -elevation / 1000.0)
+			.unwrap_or_default();
+
+		// USGS ids look like `urn:earthquake-usgs-gov:us:7000abcd`.
+		let urn = self.id.unwrap_or_default();
+		let (net, code) = match urn.rsplitn(3, ':').collect::<Vec<_>>().as_slice() {
+			[code, net, prefix] if prefix.starts_with("urn") => (Some(net.to_string()), Some(code.to_string())),
+			_ => (None, None),
+		};
+		let id = match (&net, &code) {
+			(Some(net), Some(code)) => format!("{}{}", net, code),
+			_ => urn,
+		};
+
+		let (magnitude, place) = self.title.as_deref().map(parse_title).unwrap_or_default();
+		let time = self.summary.as_deref().and_then(summary_time)
+			.or_else(|| self.published.as_deref().and_then(parse_timestamp))
+			.or_else(|| self.updated.as_deref().and_then(parse_timestamp));
+
+		Ok(EarthquakeFeatures {
+			feature_type: "Feature".to_string(),
+			properties: EarthquakeProperties {
+				magnitude,
+				place,
+				time,
+				updated_time: self.updated.as_deref().and_then(parse_timestamp),
+				url: self.link,
+				net: net.map(NetworkCode::from),
+				code,
+				event_type: Some("earthquake".to_string()),
+				title: self.title,
+				..Default::default()
+			},
+			geometry: EarthquakeGeometry {
+				geometry_type: "Point".to_string(),
+				coordinates: vec![longitude, latitude, depth],
+			},
+			id: EventId::from(id),
+			country: None,
+		})
+	}
+}
+
+fn attribute(element: &BytesStart, name: &str) -> Option<String> {
+	element.try_get_attribute(name).ok().flatten()
+		.and_then(|attribute| attribute.normalized_value(XmlVersion::Implicit1_0).ok().map(|value| value.into_owned()))
+}
+
+/// Parses a USGS Atom feed (or an RSS 2.0 feed with GeoRSS points) into the
+/// same response model as the GeoJSON API, so feed consumers can share
+/// downstream code.
+///
+/// Magnitude and place come from entry titles such as
+/// `M 4.6 - 100 km SSW of Town, Country`, the origin time from the entry
+/// summary (falling back to the publication or update time), and the depth
+/// from `georss:elev`. Fields not present in feeds are left as `None`;
+/// feeds without a valid link get a generic USGS feed URL as `metadata.url`.
+///
+/// ## Example
+/// ```rust
+/// use usgs_earthquake_api::parse_feed;
+///
+/// let atom = r#"<feed xmlns="http://www.w3.org/2005/Atom" xmlns:georss="http://www.georss.org/georss">
+///   <title>USGS Magnitude 2.5+ Earthquakes, Past Day</title>
+///   <updated>2024-01-01T12:30:00Z</updated>
+///   <link rel="self" href="https://earthquake.usgs.gov/earthquakes/feed/v1.0/summary/2.5_day.atom"/>
+///   <entry>
+///     <id>urn:earthquake-usgs-gov:us:7000abcd</id>
+///     <title>M 4.6 - 100 km SSW of Town, Country</title>
+///     <updated>2024-01-01T12:20:00.000Z</updated>
+///     <georss:point>-5.1 150.2</georss:point>
+///     <georss:elev>-10000</georss:elev>
+///   </entry>
+/// </feed>"#;
+///
+/// let response = parse_feed(atom).unwrap();
+/// assert_eq!(response.features[0].id, "us7000abcd");
+/// assert_eq!(response.features[0].properties.magnitude, Some(4.6));
+/// assert_eq!(response.features[0].geometry.depth_km(), 10.0);
+/// ```
+pub fn parse_feed(xml: &str) -> Result<EarthquakeResponse, UsgsError> {
+	let mut reader = Reader::from_str(xml);
+	let mut features = Vec::new();
+	let mut entry: Option<Entry> = None;
+	let mut feed_title = None;
+	let mut feed_url = None;
+	let mut feed_updated = None;
+	let mut text = String::new();
+
+	loop {
+		match reader.read_event().map_err(feed_error)? {
+			Event::Start(element) => {
+				if matches!(element.local_name().as_ref(), "entry" | "item") {
+					entry = Some(Entry::default());
+				}
+				if element.local_name().as_ref() == "link" {
+					set_link(&element, entry.as_mut(), &mut feed_url);
+				}
+				text.clear();
+			}
+			Event::Empty(element) if element.local_name().as_ref() == "link" => {
+				set_link(&element, entry.as_mut(), &mut feed_url);
+			}
+			Event::Text(content) => text.push_str(&content.html_content()),
+			Event::CData(content) => text.push_str(&content.html_content()),
+			Event::GeneralRef(reference) => {
+				if let Ok(Some(character)) = reference.resolve_char_ref() {
+					text.push(character);
+				} else if let Some(resolved) = resolve_predefined_entity(&reference) {
+					text.push_str(resolved);
+				}
+			}
+			Event::End(element) => {
+				let value = Some(text.trim().to_string()).filter(|value| !value.is_empty());
+				match (element.local_name().as_ref(), entry.as_mut()) {
+					("entry" | "item", _) => {
+						if let Some(entry) = entry.take() {
+							features.push(entry.into_feature()?);
+						}
+					}
+					("id" | "guid", Some(entry)) => entry.id = value,
+					("title", Some(entry)) => entry.title = value,
+					("link", Some(entry)) if entry.link.is_none() => entry.link = value,
+					("updated", Some(entry)) => entry.updated = value,
+					("published" | "pubDate", Some(entry)) => entry.published = value,
+					("summary" | "description", Some(entry)) => entry.summary = value,
+					("point", Some(entry)) => entry.point = value,
+					("elev", Some(entry)) => entry.elevation = value,
+					("title", None) if feed_title.is_none() => feed_title = value,
+					("updated" | "lastBuildDate" | "pubDate", None) if feed_updated.is_none() => feed_updated = value,
+					("link", None) if feed_url.is_none() => feed_url = value,
+					_ => {}
+				}
+				text.clear();
+			}
+			Event::Eof => break,
+			_ => {}
+		}
+	}
+
+	let mut response = EarthquakeResponse {
+		data_type: "FeatureCollection".to_string(),
+		metadata: EarthquakeMetadata {
+			generated_timestamp: feed_updated.as_deref()
+				.and_then(parse_timestamp)
+				.and_then(|ms| DateTime::from_timestamp_millis(ms as i64))
+				.unwrap_or_default(),
+			url: feed_url.as_deref()
+				.and_then(|url| Url::parse(url).ok())
+				.unwrap_or_else(|| Url::parse(UNKNOWN_FEED_URL).expect("valid fallback URL")),
+			title: feed_title.unwrap_or_default(),
+			status: StatusCode::OK,
+			api_version: String::new(),
+			count: features.len() as u32,
+			#[cfg(feature = "extra-fields")]
+			extra: Default::default(),
+		},
+		features,
+		bbox: None,
+	};
+	response.recompute_bbox();
+	Ok(response)
+}
+
+/// Atom links carry the URL in `href`; the entry uses the `alternate` (or
+/// first) link and the feed its `self` link.
+fn set_link(element: &BytesStart, entry: Option<&mut Entry>, feed_url: &mut Option<String>) {
+	let Some(href) = attribute(element, "href") else {
+		return;
+	};
+	let rel = attribute(element, "rel");
+	match entry {
+		Some(entry) if entry.link.is_none() || rel.as_deref() == Some("alternate") => entry.link = Some(href),
+		None if rel.as_deref() == Some("self") || feed_url.is_none() => *feed_url = Some(href),
+		_ => {}
+	}
+}
\ No newline at end of file
@@ -0,0 +1 @@
+pub mod feed;
\ No newline at end of file
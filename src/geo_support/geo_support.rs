@@ -0,0 +1,39 @@
+use geo::{Geometry, GeometryCollection, Point};
+use crate::models::models::{EarthquakeFeatures, EarthquakeResponse};
+
+impl EarthquakeFeatures {
+	/// Epicenter as a [`geo::Point`] (`x` longitude, `y` latitude).
+	pub fn to_point(&self) -> Point<f64> {
+		Point::new(self.geometry.longitude(), self.geometry.latitude())
+	}
+}
+
+impl EarthquakeResponse {
+	/// Epicenters as a [`geo::GeometryCollection`] of points, for the
+	/// algorithms of the `geo` crate.
+	///
+	/// The geometry at index `i` is the epicenter of `features[i]`, so the
+	/// features double as the index from geometries back to event ids.
+	///
+	/// ## Example
+	/// ```rust,no_run
+	/// use geo::{Centroid, ConvexHull};
+	/// use usgs_earthquake_api::UsgsClient;
+	///
+	/// #[tokio::main]
+	/// async fn main() {
+	///     let response = UsgsClient::new().query().min_magnitude(4.5).fetch().await.unwrap();
+	///     let epicenters = response.to_geometry_collection();
+	///     println!("centroid {:?}", epicenters.centroid());
+	///     println!("hull {:?}", epicenters.convex_hull());
+	///     for (geometry, event) in epicenters.iter().zip(&response.features) {
+	///         println!("{} at {:?}", event.id, geometry);
+	///     }
+	/// }
+	/// ```
+	pub fn to_geometry_collection(&self) -> GeometryCollection<f64> {
+		self.features.iter()
+			.map(|feature| Geometry::Point(feature.to_point()))
+			.collect()
+	}
+}
@@ -0,0 +1 @@
+pub mod geo_support;
\ No newline at end of file
@@ -0,0 +1,30 @@
+use std::collections::HashMap;
+use h3o::{CellIndex, LatLng, Resolution};
+use crate::models::models::{EarthquakeFeatures, EarthquakeResponse};
+
+impl EarthquakeFeatures {
+	/// H3 cell containing the epicenter at the given resolution.
+	///
+	/// Returns `None` if the coordinates are not valid.
+	pub fn h3_cell(&self, resolution: Resolution) -> Option<CellIndex> {
+		LatLng::new(self.geometry.latitude(), self.geometry.longitude())
+			.ok()
+			.map(|point| point.to_cell(resolution))
+	}
+}
+
+impl EarthquakeResponse {
+	/// Groups events by the H3 cell containing their epicenter, e.g. for
+	/// hexagonal heat maps.
+	///
+	/// Events with invalid coordinates are skipped.
+	pub fn group_by_h3(&self, resolution: Resolution) -> HashMap<CellIndex, Vec<&EarthquakeFeatures>> {
+		let mut groups: HashMap<CellIndex, Vec<&EarthquakeFeatures>> = HashMap::new();
+		for feature in &self.features {
+			if let Some(cell) = feature.h3_cell(resolution) {
+				groups.entry(cell).or_default().push(feature);
+			}
+		}
+		groups
+	}
+}
\ No newline at end of file
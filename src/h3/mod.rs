@@ -0,0 +1 @@
+pub mod h3;
\ No newline at end of file
@@ -8,16 +8,17 @@
 //! - Filter by magnitude range (`min_magnitude`, `max_magnitude`)
 //! - Filter by alert level (`AlertLevel`)
 //! - Order results (`OrderBy`)
-//! - Filter earthquakes by country code (using `country_boundaries` dataset).
+//! - Filter earthquakes by country code (using `country_boundaries` dataset,
+//!   behind the default `country-filter` feature).
+//! - Run the same query against other FDSN event services (`FdsnProfile`).
 //!
 //! ## Example
 //! ```rust,no_run
-//! use usgs_client::{UsgsClient, AlertLevel, OrderBy};
+//! use usgs_earthquake_api::{UsgsClient, AlertLevel, OrderBy};
 //!
 //! #[tokio::main]
 //! async fn main() {
-//!     use usgs_earthquake_api::OrderBy;
-//! let client = UsgsClient::new();
+//!     let client = UsgsClient::new();
 //!     let result = client
 //!         .query()
 //!         .filter_by_country_code("TR")
@@ -35,16 +36,136 @@
 //!     }
 //! }
 //! ```
+//!
+//! ## Other FDSN services
+//! ```rust,no_run
+//! use usgs_earthquake_api::{UsgsClient, FdsnProfile};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let emsc = UsgsClient::with_profile(FdsnProfile::emsc());
+//!     let result = emsc.query().start_time(2024, 1, 1, 0, 0).fetch().await;
+//! }
+//! ```
+
+#![allow(clippy::module_inception)]
 
+mod backfill;
+mod builder;
+mod cache;
+#[cfg(feature = "cities")]
+mod cities;
+mod compression;
+mod config;
+#[cfg(feature = "country-filter")]
+mod country;
+#[cfg(feature = "country-filter")]
+pub mod digest;
 mod error;
+pub mod export;
+mod fdsn;
+#[cfg(feature = "atom")]
+mod feed;
+#[cfg(feature = "h3")]
+mod h3;
+#[cfg(feature = "geo")]
+mod geo_support;
+#[cfg(feature = "metrics")]
+mod metrics;
 mod models;
+pub mod notify;
+#[cfg(feature = "plate-boundaries")]
+mod plates;
+#[cfg(feature = "plot")]
+pub mod plot;
+mod rate_limit;
+#[cfg(feature = "replay")]
+mod replay;
+mod retry;
+#[cfg(feature = "server")]
+mod server;
+pub mod sinks;
+#[cfg(feature = "stations")]
+pub mod stations;
+#[cfg(feature = "tsunami")]
+pub mod tsunami;
+pub mod stats;
+#[cfg(feature = "spatial-index")]
+mod spatial;
+#[cfg(test)]
+mod tests;
+#[cfg(feature = "time")]
+mod time_support;
+#[cfg(feature = "timezone-lookup")]
+mod timezone;
+#[cfg(feature = "utm")]
+mod utm;
+mod watch;
+mod windows;
 
+use std::collections::HashMap;
 use std::fmt::Display;
-use chrono::{Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
-use country_boundaries::{CountryBoundaries, LatLon, BOUNDARIES_ODBL_360X180};
-use reqwest::Client;
-use error::error::UsgsError;
-use crate::models::models::{EarthquakeResponse, EarthquakeFeatures};
+use std::str::FromStr;
+use std::sync::Arc;
+#[cfg(feature = "country-filter")]
+use std::sync::OnceLock;
+use chrono::{Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike, Utc};
+#[cfg(feature = "country-filter")]
+use country_boundaries::{CountryBoundaries, LatLon};
+use futures_util::{stream, StreamExt};
+use log::debug;
+use reqwest::{Client, Response, Url};
+#[cfg(feature = "compression")]
+use reqwest::header::ACCEPT_ENCODING;
+use cache::cache::stable_hash;
+use compression::compression::{read_body, Body};
+#[cfg(feature = "compression")]
+use compression::compression::ACCEPT_ENCODING_VALUE;
+#[cfg(feature = "country-filter")]
+use country::country::{bounding_box, country_at, country_extents, embedded_boundaries, enclosing_circle, CountryExtent};
+use fdsn::fdsn::parse_text_response;
+use rate_limit::rate_limit::RateLimiter;
+
+pub use backfill::backfill::{Backfill, BackfillReport};
+pub use builder::builder::UsgsClientBuilder;
+pub use cache::cache::{CacheBackend, DiskCache, MemoryCache, MemorySeenStore, SeenEventStore};
+#[cfg(feature = "redis")]
+pub use cache::redis_store::RedisStore;
+pub use config::config::{parse_time_expression, CircleConfig, QueryConfig};
+pub use error::error::{TimeoutPhase, UsgsError};
+pub use fdsn::fdsn::{FdsnProfile, ResponseFormat};
+pub use fdsn::capabilities::{Capabilities, ServiceParameter};
+#[cfg(feature = "metrics")]
+pub use metrics::metrics::MetricsExporter;
+#[cfg(feature = "atom")]
+pub use feed::feed::parse_feed;
+#[cfg(feature = "server")]
+pub use server::server::ProxyServer;
+pub use retry::retry::{RetryPolicy, RetryCause, ExponentialBackoff, NoRetry, RetryBudget};
+#[cfg(feature = "plate-boundaries")]
+pub use plates::plates::{PlateBoundaries, PlateBoundary};
+#[cfg(feature = "spatial-index")]
+pub use spatial::spatial::SpatialIndex;
+#[cfg(feature = "utm")]
+pub use utm::utm::UtmCoordinate;
+pub use watch::watch::{FieldChange, WatchEvent, Watcher};
+pub use windows::windows::{time_windows, ChunkBy, Interval, TimeWindows};
+pub use models::models::{EarthquakeResponse, RawEarthquakeResponse, EarthquakeMetadata, EarthquakeFeatures, EarthquakeProperties, EarthquakeGeometry, DepthClass, EventSummary, HealthStatus, MagnitudeBucket, Unit, parse_response, parse_response_with};
+#[cfg(feature = "cities")]
+pub use cities::cities::{Cities, City, NearestCity};
+pub use models::format::EventFormat;
+pub use models::place::Place;
+pub use models::product::{Product, ProductContent};
+pub use models::shakemap::{parse_intensity_contours, IntensityContour};
+pub use models::ids::{CatalogId, EventId, NetworkCode};
+#[cfg(feature = "country-filter")]
+pub use country::country::CountryPrefilter;
+#[cfg(feature = "replay")]
+pub use replay::replay::HttpMode;
+pub use models::schema::ParseMode;
+
+/// Narrows a query built by a long-running component (watcher, exporter).
+type ConfigureQuery = dyn for<'a> Fn(UsgsQuery<'a>) -> UsgsQuery<'a> + Send + Sync;
 
 fn local_time_as_utc() -> NaiveDateTime {
 	Utc::now().naive_utc()
@@ -53,10 +174,22 @@ fn local_time_as_utc() -> NaiveDateTime {
 fn local_time_to_utc(time: NaiveDateTime) -> NaiveDateTime {
 	let timezone = Local.from_local_datetime(&time).unwrap();
 	let utc = timezone.with_timezone(&Utc);
-	println!("{}", utc.naive_utc().to_string());
+	debug!("converted local time {} to UTC {}", time, utc.naive_utc());
 	utc.naive_utc()
 }
 
+fn format_url_time(time: NaiveDateTime) -> String {
+	time.format("%Y-%m-%dT%H:%M:%S").to_string()
+}
+
+fn parse_url_time(value: &str) -> Option<NaiveDateTime> {
+	let value = value.trim().trim_end_matches('Z').trim_end_matches(" UTC");
+	["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M"]
+		.iter()
+		.find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+		.or_else(|| NaiveDate::parse_from_str(value, "%Y-%m-%d").ok().map(|date| date.and_time(NaiveTime::MIN)))
+}
+
 fn generate_custom_time(year: i32, month: u32, day: u32, hour: u32, min: u32) -> NaiveDateTime {
 	let date = NaiveDate::from_ymd_opt(year, month, day).unwrap();
 	let time = NaiveTime::from_hms_opt(hour, min, 00).unwrap();
@@ -65,7 +198,11 @@ fn generate_custom_time(year: i32, month: u32, day: u32, hour: u32, min: u32) ->
 
 
 /// USGS earthquake alert levels.
-#[derive(Debug)]
+///
+/// Levels are ordered by severity (`Green < Yellow < Orange < Red`);
+/// [`AlertLevel::All`] sorts below every level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum AlertLevel {
 	/// Low alert level
 	Green,
@@ -83,6 +220,30 @@ pub enum AlertLevel {
 	All
 }
 
+impl AlertLevel {
+	fn severity(self) -> u8 {
+		match self {
+			AlertLevel::All => 0,
+			AlertLevel::Green => 1,
+			AlertLevel::Yellow => 2,
+			AlertLevel::Orange => 3,
+			AlertLevel::Red => 4,
+		}
+	}
+}
+
+impl PartialOrd for AlertLevel {
+	fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl Ord for AlertLevel {
+	fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+		self.severity().cmp(&other.severity())
+	}
+}
+
 pub enum OrderBy {
 	/// Order by time descending
 	Time,
@@ -100,62 +261,658 @@ pub enum OrderBy {
 
 /// Main USGS API client.
 ///
-/// Handles API requests and creates queries.
+/// Handles API requests and creates queries. Use [`UsgsClient::builder`]
+/// to configure timeouts, retries, rate limiting and defaults.
+#[derive(Clone)]
 pub struct UsgsClient {
-	/// Base URL of the USGS API
-	pub base_url: String,
-
-	/// HTTP client
-	pub client: Client,
+	base_url: String,
+	client: Client,
+	profile: FdsnProfile,
+	retry_policy: Arc<dyn RetryPolicy>,
+	retry_budget: Option<Arc<RetryBudget>>,
+	rate_limiter: Option<Arc<RateLimiter>>,
+	query_profiles: Arc<HashMap<String, QueryConfig>>,
+	parse_mode: ParseMode,
+	deadline: Option<std::time::Duration>,
+	#[cfg(feature = "compression")]
+	compression: bool,
+	#[cfg(feature = "replay")]
+	http_mode: HttpMode,
+	#[cfg(feature = "country-filter")]
+	country_code: String,
+	#[cfg(feature = "country-filter")]
+	country_boundaries: Arc<OnceLock<CountryBoundaries>>,
+	#[cfg(feature = "country-filter")]
+	country_extents: Arc<OnceLock<HashMap<String, CountryExtent>>>,
 }
 
 
 impl UsgsClient {
 	/// Creates a new [`UsgsClient`].
+	///
+	/// # Panics
+	/// Panics if the HTTP client cannot be built, e.g. when the TLS backend
+	/// fails to initialize. Use `UsgsClient::builder().build()?` to handle
+	/// that as an error instead.
 	pub fn new() -> Self {
-		Self {
-			base_url: "https://earthquake.usgs.gov/fdsnws/event/1/query?format=geojson".to_string(),
-			client: Client::new(),
+		Self::with_profile(FdsnProfile::usgs())
+	}
+
+	/// Creates a client for another FDSN event service using its default endpoint.
+	///
+	/// # Panics
+	/// Panics if the HTTP client cannot be built, see [`UsgsClient::new`];
+	/// `UsgsClient::builder().profile(profile).build()?` is the fallible
+	/// equivalent.
+	pub fn with_profile(profile: FdsnProfile) -> Self {
+		Self::builder().profile(profile).build().expect("Failed to build HTTP client")
+	}
+
+	/// Creates a client for an FDSN event 1.x service at `base_url`.
+	///
+	/// # Panics
+	/// Panics if the HTTP client cannot be built, see [`UsgsClient::new`];
+	/// `UsgsClient::builder().profile(profile).base_url(base_url).build()?`
+	/// is the fallible equivalent.
+	pub fn fdsn(base_url: &str, profile: FdsnProfile) -> Self {
+		Self::builder().profile(profile).base_url(base_url).build().expect("Failed to build HTTP client")
+	}
+
+	/// Returns a [`UsgsClientBuilder`] for configuring a client.
+	pub fn builder() -> UsgsClientBuilder {
+		UsgsClientBuilder::new()
+	}
+
+	/// Base URL of the queried service.
+	pub fn base_url(&self) -> &str {
+		&self.base_url
+	}
+
+	/// Compatibility profile of the targeted FDSN service.
+	pub fn profile(&self) -> &FdsnProfile {
+		&self.profile
+	}
+
+	/// Underlying HTTP client.
+	pub fn http_client(&self) -> &Client {
+		&self.client
+	}
+
+	/// Country boundaries used by the country filter, parsed on first use
+	/// (unless loaded from a file by the builder) and shared by all clones of
+	/// this client.
+	#[cfg(feature = "country-filter")]
+	pub(crate) fn country_boundaries(&self) -> &CountryBoundaries {
+		self.country_boundaries.get_or_init(embedded_boundaries)
+	}
+
+	/// Grid extents of all countries, computed on first use.
+	#[cfg(feature = "country-filter")]
+	pub(crate) fn country_extents(&self) -> &HashMap<String, CountryExtent> {
+		self.country_extents.get_or_init(|| country_extents(self.country_boundaries()))
+	}
+
+	/// ISO 3166-1 alpha-2 code of the country containing a point, or `None`
+	/// offshore, using the same boundaries as the country filter.
+	///
+	/// ## Example
+	/// ```rust
+	/// use usgs_earthquake_api::UsgsClient;
+	///
+	/// let client = UsgsClient::new();
+	/// assert_eq!(client.country_of(39.93, 32.86).as_deref(), Some("TR"));
+	/// ```
+	#[cfg(feature = "country-filter")]
+	pub fn country_of(&self, latitude: f64, longitude: f64) -> Option<String> {
+		country_at(self.country_boundaries(), latitude, longitude)
+	}
+
+	/// Sends a GET request honoring the configured rate limit, retries and
+	/// HTTP mode.
+	async fn send(&self, url: &str) -> Result<Response, UsgsError> {
+		#[cfg(feature = "replay")]
+		match &self.http_mode {
+			HttpMode::Live => {}
+			HttpMode::Record(directory) => return replay::replay::record(directory, url, self.send_live(url).await?).await,
+			HttpMode::Replay(directory) => return replay::replay::replay(directory, url).await,
+		}
+		self.send_live(url).await
+	}
+
+	/// Sends a GET request to the service honoring the configured rate limit and retries.
+	async fn send_live(&self, url: &str) -> Result<Response, UsgsError> {
+		let mut attempt = 0;
+		loop {
+			if let Some(limiter) = &self.rate_limiter {
+				limiter.acquire().await;
+			}
+
+			let request = self.client.get(url);
+			#[cfg(feature = "compression")]
+			let request = if self.compression {
+				request.header(ACCEPT_ENCODING, ACCEPT_ENCODING_VALUE)
+			} else {
+				request
+			};
+
+			let result = request.send().await;
+			let retryable = match &result {
+				Ok(response) if response.status().is_success() => false,
+				Ok(response) => self.retry_policy.is_retryable(&RetryCause::Status(response.status())),
+				Err(err) => self.retry_policy.is_retryable(&RetryCause::Error(err)),
+			};
+
+			if !retryable || attempt >= self.retry_policy.max_retries() {
+				return result.map_err(UsgsError::request);
+			}
+			if self.retry_budget.as_ref().is_some_and(|budget| !budget.try_withdraw()) {
+				debug!("request to {} failed, retry budget exhausted", url);
+				return result.map_err(UsgsError::request);
+			}
+
+			let delay = self.retry_policy.backoff(attempt);
+			debug!("request to {} failed, retrying in {:?}", url, delay);
+			tokio::time::sleep(delay).await;
+			attempt += 1;
 		}
 	}
 
 	/// Starts a new [`UsgsQuery`] with default parameters.
 	pub fn query(&self) -> UsgsQuery<'_> {
-		UsgsQuery {
-			client: &self.client,
-			base_url: self.base_url.clone(),
-			country_code: "US".to_string(),
-			start_time: None,
-			end_time: local_time_as_utc(),
-			min_magnitude: 0.0,
-			max_magnitude: 10.0,
-			alert_level: AlertLevel::All,
-			order_by: OrderBy::Time,
+		UsgsQuery::new(self)
+	}
+
+	/// Starts a new [`UsgsQuery`] from the query template registered as
+	/// `name` with [`UsgsClientBuilder::query_profile`].
+	///
+	/// The returned query can be refined further before fetching.
+	///
+	/// ## Example
+	/// ```rust,no_run
+	/// use usgs_earthquake_api::{QueryConfig, UsgsClient};
+	///
+	/// #[tokio::main]
+	/// async fn main() {
+	///     let turkey_m4 = QueryConfig {
+	///         start: Some("24h".to_string()),
+	///         min_magnitude: Some(4.0),
+	///         countries: Some(vec!["TR".to_string()]),
+	///         ..Default::default()
+	///     };
+	///     let client = UsgsClient::builder().query_profile("turkey_m4", turkey_m4).build().unwrap();
+	///     let result = client.query_profile("turkey_m4").unwrap().min_magnitude(5.0).fetch().await;
+	/// }
+	/// ```
+	pub fn query_profile(&self, name: &str) -> Result<UsgsQuery<'_>, UsgsError> {
+		self.query_profiles.get(name)
+			.ok_or_else(|| UsgsError::UnknownQueryProfile(name.to_string()))?
+			.to_query(self)
+	}
+
+	/// Executes several queries with at most `concurrency` requests in flight
+	/// and returns their results in the order of `queries`.
+	///
+	/// All requests share the client's rate limit and retry policy; a failing
+	/// query does not affect the others.
+	///
+	/// ## Example
+	/// ```rust,no_run
+	/// use usgs_earthquake_api::UsgsClient;
+	///
+	/// #[tokio::main]
+	/// async fn main() {
+	///     let client = UsgsClient::new();
+	///     let queries = ["TR", "GR", "IT"].map(|country| client.query().filter_by_country_code(country).start_time(2024, 1, 1, 0, 0));
+	///     for result in client.fetch_many(queries, 2).await {
+	///         match result {
+	///             Ok(res) => println!("{} events", res.features.len()),
+	///             Err(e) => eprintln!("Error: {}", e),
+	///         }
+	///     }
+	/// }
+	/// ```
+	pub async fn fetch_many<'a>(&'a self, queries: impl IntoIterator<Item = UsgsQuery<'a>>, concurrency: usize) -> Vec<Result<EarthquakeResponse, UsgsError>> {
+		stream::iter(queries)
+			.map(UsgsQuery::fetch)
+			.buffered(concurrency.max(1))
+			.collect()
+			.await
+	}
+
+	/// Names of the registered query templates.
+	pub fn query_profile_names(&self) -> impl Iterator<Item = &str> {
+		self.query_profiles.keys().map(String::as_str)
+	}
+
+	/// URL of another resource of the service, e.g. `version` next to `query`.
+	fn service_url(&self, resource: &str) -> String {
+		match self.base_url.trim_end_matches('/').rsplit_once('/') {
+			Some((service, _)) => format!("{}/{}", service, resource),
+			None => resource.to_string(),
+		}
+	}
+
+	/// Fetches a service resource as text, honoring the rate limit, retries
+	/// and deadline of the client.
+	async fn fetch_text(&self, url: &str) -> Result<String, UsgsError> {
+		let request = async {
+			let response = self.send(url).await?;
+			let status = response.status();
+			let body = read_body(response).await?;
+			if !status.is_success() {
+				return Err(UsgsError::api(status, &body.text));
+			}
+			Ok(body.text)
+		};
+		match self.deadline {
+			Some(deadline) => tokio::time::timeout(deadline, request).await.map_err(|_| UsgsError::Timeout(TimeoutPhase::Deadline))?,
+			None => request.await,
+		}
+	}
+
+	/// Version of the FDSN event service, from its `version` resource
+	/// (e.g. `"1.14.1"`).
+	pub async fn api_version(&self) -> Result<String, UsgsError> {
+		let version = self.fetch_text(&self.service_url("version")).await?;
+		Ok(version.trim().to_string())
+	}
+
+	/// Checks that the service is reachable and measures its latency, e.g.
+	/// for readiness probes of services depending on USGS.
+	///
+	/// ## Example
+	/// ```rust,no_run
+	/// use usgs_earthquake_api::UsgsClient;
+	///
+	/// #[tokio::main]
+	/// async fn main() {
+	///     match UsgsClient::new().health_check().await {
+	///         Ok(health) => println!("USGS {} answered in {:?}", health.version, health.latency),
+	///         Err(err) => eprintln!("USGS unavailable: {}", err),
+	///     }
+	/// }
+	/// ```
+	pub async fn health_check(&self) -> Result<HealthStatus, UsgsError> {
+		let started = std::time::Instant::now();
+		let version = self.api_version().await?;
+		Ok(HealthStatus {
+			version,
+			latency: started.elapsed(),
+		})
+	}
+
+	/// URL looking up a single event.
+	fn event_url(&self, event_id: &str, extra: &[(&str, &str)]) -> Result<String, UsgsError> {
+		let mut url = Url::parse(&self.base_url).map_err(|e| UsgsError::InvalidUrl(e.to_string()))?;
+		url.query_pairs_mut()
+			.append_pair("eventid", event_id)
+			.append_pair("format", self.profile.format_parameter())
+			.extend_pairs(extra);
+		Ok(url.to_string())
+	}
+
+	/// Fetches a single event by id.
+	///
+	/// Ids of associated events (e.g. a regional network's id of an event
+	/// also located by USGS) return the currently preferred event, whose
+	/// `id` may differ from `event_id`.
+	pub async fn event(&self, event_id: impl Into<EventId>) -> Result<EarthquakeFeatures, UsgsError> {
+		let event_id = event_id.into();
+		let url = self.event_url(&event_id, &[])?;
+		let body = match self.fetch_text(&url).await {
+			Err(UsgsError::Api { status: reqwest::StatusCode::NOT_FOUND, .. }) => return Err(UsgsError::EventNotFound(event_id)),
+			result => result?,
+		};
+		let event = match self.profile.format {
+			ResponseFormat::GeoJson if body.trim().is_empty() => None,
+			ResponseFormat::GeoJson => Some(serde_json::from_str(&body)?),
+			ResponseFormat::Text => parse_text_response(&body, &url, &self.profile)?.features.into_iter().next(),
+		};
+		event.ok_or(UsgsError::EventNotFound(event_id))
+	}
+
+	/// Resolves an event id to the id of the currently preferred event.
+	///
+	/// Stored ids can go stale when networks re-associate events; resolving
+	/// them before use follows the association (`ids`) to the event that
+	/// supersedes them.
+	///
+	/// ## Example
+	/// ```rust,no_run
+	/// use usgs_earthquake_api::UsgsClient;
+	///
+	/// #[tokio::main]
+	/// async fn main() {
+	///     let preferred = UsgsClient::new().resolve_event_id("ci38457511").await.unwrap();
+	///     println!("now known as {}", preferred);
+	/// }
+	/// ```
+	pub async fn resolve_event_id(&self, event_id: impl Into<EventId>) -> Result<EventId, UsgsError> {
+		Ok(self.event(event_id).await?.id)
+	}
+
+	/// Looks up a mainshock and starts a query for its aftershocks: events
+	/// within `radius_km` of its epicenter from its origin time for
+	/// `duration` (capped at now).
+	///
+	/// The query starts one second after the origin time so the mainshock
+	/// itself is excluded, and can be refined further before fetching. Fails
+	/// with [`UsgsError::MissingOriginTime`] if the mainshock has no origin
+	/// time.
+	///
+	/// ## Example
+	/// ```rust,no_run
+	/// use chrono::Duration;
+	/// use usgs_earthquake_api::UsgsClient;
+	///
+	/// #[tokio::main]
+	/// async fn main() {
+	///     let client = UsgsClient::new();
+	///     let query = client.aftershocks_of("us6000jllz", 100.0, Duration::days(30)).await.unwrap();
+	///     let aftershocks = query.min_magnitude(2.5).fetch().await.unwrap();
+	/// }
+	/// ```
+	pub async fn aftershocks_of(&self, event_id: impl Into<EventId>, radius_km: f64, duration: Duration) -> Result<UsgsQuery<'_>, UsgsError> {
+		let mainshock = self.event(event_id).await?;
+		let origin = mainshock.origin_time().ok_or_else(|| UsgsError::MissingOriginTime(mainshock.id.clone()))?.naive_utc();
+		let start = origin.with_nanosecond(0).unwrap_or(origin) + Duration::seconds(1);
+		let mut query = self.query()
+			.worldwide()
+			.circle(mainshock.geometry.latitude(), mainshock.geometry.longitude(), radius_km);
+		query.start_time = Some(start);
+		query.end_time = (origin + duration).min(local_time_as_utc());
+		Ok(query)
+	}
+
+	/// Detail document of an event with every product version, including
+	/// superseded ones (`includesuperseded=true`).
+	///
+	/// Returned as JSON since products are not modelled by this crate.
+	pub async fn event_history(&self, event_id: impl Into<EventId>) -> Result<serde_json::Value, UsgsError> {
+		let event_id = event_id.into();
+		if !self.profile.supports("includesuperseded") {
+			return Err(UsgsError::UnsupportedParameter("includesuperseded".to_string()));
+		}
+		let url = self.event_url(&event_id, &[("includesuperseded", "true")])?;
+		match self.fetch_text(&url).await {
+			Err(UsgsError::Api { status: reqwest::StatusCode::NOT_FOUND, .. }) => Err(UsgsError::EventNotFound(event_id)),
+			result => Ok(serde_json::from_str(&result?)?),
+		}
+	}
+
+	/// Discovers what the service supports from its `application.wadl`,
+	/// plus the USGS `application.json` document or the standard
+	/// `catalogs` and `contributors` resources.
+	///
+	/// The catalog and contributor lists are left empty if the service does
+	/// not publish them.
+	pub async fn capabilities(&self) -> Result<Capabilities, UsgsError> {
+		let mut capabilities = Capabilities::from_wadl(&self.fetch_text(&self.service_url("application.wadl")).await?)?;
+		let from_application = match self.fetch_text(&self.service_url("application.json")).await {
+			Ok(application) => capabilities.merge_application_json(&application).is_ok(),
+			Err(_) => false,
+		};
+		if !from_application {
+			if let Ok(catalogs) = self.fetch_text(&self.service_url("catalogs")).await {
+				capabilities.catalogs = Capabilities::parse_name_list(&catalogs, "Catalog").into_iter().map(CatalogId::from).collect();
+			}
+			if let Ok(contributors) = self.fetch_text(&self.service_url("contributors")).await {
+				capabilities.contributors = Capabilities::parse_name_list(&contributors, "Contributor");
+			}
 		}
+		Ok(capabilities)
+	}
+
+	/// Creates a client for an unknown FDSN event service at `base_url`,
+	/// configured from its [`capabilities`](UsgsClient::capabilities).
+	///
+	/// ## Example
+	/// ```rust,no_run
+	/// use usgs_earthquake_api::UsgsClient;
+	///
+	/// #[tokio::main]
+	/// async fn main() {
+	///     let client = UsgsClient::discover("https://service.iris.edu/fdsnws/event/1/query").await.unwrap();
+	///     println!("{:?}", client.profile());
+	/// }
+	/// ```
+	pub async fn discover(base_url: &str) -> Result<Self, UsgsError> {
+		let probe = Self::fdsn(base_url, FdsnProfile::standard("FDSN", base_url));
+		let capabilities = probe.capabilities().await?;
+		let host = Url::parse(base_url).ok().and_then(|url| url.host_str().map(str::to_string)).unwrap_or_else(|| base_url.to_string());
+		Ok(Self::fdsn(base_url, capabilities.profile(&host, base_url)))
+	}
+}
+
+impl Default for UsgsClient {
+	fn default() -> Self {
+		Self::new()
 	}
 }
 
+/// Number of events from which the country filter runs its lookups on the
+/// rayon thread pool; smaller responses are not worth the scheduling overhead.
+#[cfg(feature = "parallel")]
+const PARALLEL_COUNTRY_FILTER_THRESHOLD: usize = 2048;
+
+/// Client-side comparison of events set with [`UsgsQuery::order_by_key`].
+type FeatureOrdering<'a> = Box<dyn Fn(&EarthquakeFeatures, &EarthquakeFeatures) -> std::cmp::Ordering + Send + Sync + 'a>;
+
 /// Query builder for the USGS API.
 ///
 /// Allows filtering and customizing request parameters.
 pub struct UsgsQuery<'a> {
-	client: & 'a Client,
+	client: &'a UsgsClient,
+	profile: &'a FdsnProfile,
 	base_url: String,
-	country_code: String,
+	#[cfg(feature = "country-filter")]
+	country_codes: Vec<String>,
+	#[cfg(feature = "country-filter")]
+	country_prefilter: CountryPrefilter,
 	start_time: Option<NaiveDateTime>,
 	end_time: NaiveDateTime,
 	min_magnitude: f32,
 	max_magnitude: f32,
 	alert_level: AlertLevel,
 	order_by: OrderBy,
+	order_by_key: Option<FeatureOrdering<'a>>,
+	catalog: Option<CatalogId>,
+	scenario: bool,
+	circle: Option<(f64, f64, f64)>,
+	rectangle: Option<(f64, f64, f64, f64)>,
+	min_felt: Option<u32>,
+	min_significance: Option<u32>,
+	min_alert_level: Option<AlertLevel>,
+	include_deleted: bool,
+	include_superseded: bool,
+	deadline: Option<std::time::Duration>,
+	split_on_timeout: Option<Duration>,
 }
 
 //TODO: Add other queries from USGS API document.
 impl<'a> UsgsQuery<'a> {
 
+	/// Creates a new query with default parameters for the given client.
+	pub fn new(client: &'a UsgsClient) -> Self {
+		UsgsQuery {
+			client,
+			profile: &client.profile,
+			base_url: client.base_url.clone(),
+			#[cfg(feature = "country-filter")]
+			country_codes: [client.country_code.clone()].into_iter().filter(|code| !code.is_empty()).collect(),
+			#[cfg(feature = "country-filter")]
+			country_prefilter: CountryPrefilter::default(),
+			start_time: None,
+			end_time: local_time_as_utc(),
+			min_magnitude: 0.0,
+			max_magnitude: 10.0,
+			alert_level: AlertLevel::All,
+			order_by: OrderBy::Time,
+			order_by_key: None,
+			catalog: None,
+			scenario: false,
+			circle: None,
+			rectangle: None,
+			min_felt: None,
+			min_significance: None,
+			min_alert_level: None,
+			include_deleted: false,
+			include_superseded: false,
+			deadline: client.deadline,
+			split_on_timeout: None,
+		}
+	}
+
+	/// Significant earthquakes worldwide in the past 7 days.
+	///
+	/// Mirrors the USGS "Significant Earthquakes, Past Week" feed.
+	pub fn significant_last_week(client: &'a UsgsClient) -> Self {
+		Self::new(client)
+			.worldwide()
+			.since(Duration::days(7))
+			.min_significance(600)
+	}
+
+	/// M4.5+ earthquakes worldwide in the past day.
+	///
+	/// Mirrors the USGS "M4.5+ Earthquakes, Past Day" feed.
+	pub fn m45_plus_today(client: &'a UsgsClient) -> Self {
+		Self::new(client)
+			.worldwide()
+			.since(Duration::days(1))
+			.min_magnitude(4.5)
+	}
+
+	/// Felt earthquakes within `radius_km` of a point in the past 7 days.
+	pub fn felt_nearby(client: &'a UsgsClient, latitude: f64, longitude: f64, radius_km: f64) -> Self {
+		Self::new(client)
+			.worldwide()
+			.since(Duration::days(7))
+			.circle(latitude, longitude, radius_km)
+			.min_felt(1)
+	}
+
+	/// Parses an existing query URL (e.g. copied from the USGS site or from
+	/// `metadata.url`) into a builder that can be modified and re-executed.
+	///
+	/// The country filter is disabled since it is not part of the URL. A
+	/// `format` other than the one of the client's [`FdsnProfile`] is
+	/// rejected rather than silently replaced.
+	pub fn from_url(client: &'a UsgsClient, url: &str) -> Result<Self, UsgsError> {
+		let parsed = Url::parse(url).map_err(|e| UsgsError::InvalidUrl(e.to_string()))?;
+		let mut base_url = parsed.clone();
+		base_url.set_query(None);
+
+		let mut query = Self::new(client).worldwide();
+		query.base_url = base_url.to_string();
+
+		let mut latitude = None;
+		let mut longitude = None;
+		let mut radius_km = None;
+		let mut rectangle = [None; 4];
+
+		for (key, value) in parsed.query_pairs() {
+			let invalid = || UsgsError::InvalidUrl(format!("invalid value `{}` for `{}`", value, key));
+			match key.as_ref() {
+				"format" if !value.eq_ignore_ascii_case(query.profile.format_parameter()) => {
+					return Err(UsgsError::InvalidUrl(format!("unsupported format `{}`, this client requests `{}`", value, query.profile.format_parameter())));
+				}
+				"format" | "jsonerror" => {}
+				"starttime" => query.start_time = Some(parse_url_time(&value).ok_or_else(invalid)?),
+				"endtime" => query.end_time = parse_url_time(&value).ok_or_else(invalid)?,
+				"minmagnitude" => query.min_magnitude = value.parse().map_err(|_| invalid())?,
+				"maxmagnitude" => query.max_magnitude = value.parse().map_err(|_| invalid())?,
+				"alertlevel" => query.alert_level = value.parse()?,
+				"orderby" => query.order_by = value.parse()?,
+				"catalog" => query.catalog = Some(CatalogId::from(value.as_ref())),
+				"latitude" => latitude = Some(value.parse().map_err(|_| invalid())?),
+				"longitude" => longitude = Some(value.parse().map_err(|_| invalid())?),
+				"maxradiuskm" => radius_km = Some(value.parse().map_err(|_| invalid())?),
+				"minlatitude" => rectangle[0] = Some(value.parse().map_err(|_| invalid())?),
+				"maxlatitude" => rectangle[1] = Some(value.parse().map_err(|_| invalid())?),
+				"minlongitude" => rectangle[2] = Some(value.parse().map_err(|_| invalid())?),
+				"maxlongitude" => rectangle[3] = Some(value.parse().map_err(|_| invalid())?),
+				"minfelt" => query.min_felt = Some(value.parse().map_err(|_| invalid())?),
+				"minsig" => query.min_significance = Some(value.parse().map_err(|_| invalid())?),
+				"includedeleted" => query.include_deleted = value.parse().map_err(|_| invalid())?,
+				"includesuperseded" => query.include_superseded = value.parse().map_err(|_| invalid())?,
+				other => return Err(UsgsError::UnsupportedParameter(other.to_string())),
+			}
+		}
+
+		match (latitude, longitude, radius_km) {
+			(Some(latitude), Some(longitude), Some(radius_km)) => query.circle = Some((latitude, longitude, radius_km)),
+			(None, None, None) => {}
+			_ => return Err(UsgsError::InvalidUrl("latitude, longitude and maxradiuskm must be given together".to_string())),
+		}
+		if rectangle.iter().any(Option::is_some) {
+			let [min_latitude, max_latitude, min_longitude, max_longitude] = rectangle;
+			query.rectangle = Some((
+				min_latitude.unwrap_or(-90.0),
+				max_latitude.unwrap_or(90.0),
+				min_longitude.unwrap_or(-180.0),
+				max_longitude.unwrap_or(180.0),
+			));
+		}
+
+		Ok(query)
+	}
+
+	/// Disables the client-side country filter.
+	#[cfg(feature = "country-filter")]
+	fn worldwide(self) -> Self {
+		self.filter_by_country_code("")
+	}
+
+	#[cfg(not(feature = "country-filter"))]
+	fn worldwide(self) -> Self {
+		self
+	}
+
+	fn since(mut self, duration: Duration) -> Self {
+		self.start_time = Some(local_time_as_utc() - duration);
+		self
+	}
+
 	/// Filters earthquakes by country code (e.g., `"TR"`, `"US"`).
-	pub fn filter_by_country_code(mut self, country_code: &str) -> Self {
-		self.country_code = country_code.to_string();
+	///
+	/// An empty string disables the country filter.
+	#[cfg(feature = "country-filter")]
+	pub fn filter_by_country_code(self, country_code: &str) -> Self {
+		self.filter_by_country_codes(&[country_code])
+	}
+
+	/// Keeps events located inside any of the given countries.
+	///
+	/// The bounding box of the countries is sent as `minlatitude` ...
+	/// `maxlongitude` so the service prunes most other events (see
+	/// [`UsgsQuery::country_prefilter`]), and the exact boundaries are
+	/// checked client-side. An empty list disables the country filter.
+	#[cfg(feature = "country-filter")]
+	pub fn filter_by_country_codes(mut self, country_codes: &[&str]) -> Self {
+		self.country_codes = country_codes.iter().filter(|code| !code.is_empty()).map(|code| code.to_string()).collect();
+		self
+	}
+
+	/// Chooses how the country filter is pre-applied by the service, the
+	/// bounding box of the countries by default.
+	///
+	/// ## Example
+	/// ```rust
+	/// use usgs_earthquake_api::{CountryPrefilter, UsgsClient};
+	///
+	/// let client = UsgsClient::new();
+	/// let url = client.query()
+	///     .filter_by_country_code("JP")
+	///     .country_prefilter(CountryPrefilter::Circle)
+	///     .start_time(2024, 1, 1, 0, 0)
+	///     .build_url()
+	///     .unwrap();
+	/// assert!(url.contains("maxradiuskm=") && !url.contains("minlatitude="));
+	/// ```
+	#[cfg(feature = "country-filter")]
+	pub fn country_prefilter(mut self, prefilter: CountryPrefilter) -> Self {
+		self.country_prefilter = prefilter;
 		self
 	}
 
@@ -189,17 +946,152 @@ impl<'a> UsgsQuery<'a> {
 		self
 	}
 
+	/// Keeps events with an alert level of `level` or worse, e.g. orange and
+	/// red for [`AlertLevel::Orange`].
+	///
+	/// The service only matches single levels, so `Red` is queried directly
+	/// and lower thresholds are applied client-side. Events without an alert
+	/// level are dropped unless `level` is [`AlertLevel::All`]. This replaces
+	/// any level set with [`UsgsQuery::alert_level`].
+	pub fn min_alert_level(mut self, level: AlertLevel) -> Self {
+		self.min_alert_level = Some(level);
+		self.alert_level = if level == AlertLevel::Red { AlertLevel::Red } else { AlertLevel::All };
+		self
+	}
+
 	/// Sets the ordering method for the query.
 	pub fn order_by(mut self, order_by: OrderBy) -> Self {
 		self.order_by = order_by;
 		self
 	}
 
-	/// Executes the query against the USGS API.
+	/// Sorts the events by `key` after they are received, for orderings the
+	/// service does not offer (it only sorts by time and magnitude).
 	///
-	/// # Returns
-	/// `Result<EarthquakeResponse, UsgsError>`
-	pub async fn fetch(self) -> Result<EarthquakeResponse, UsgsError> {
+	/// The sort is ascending and stable, so events with equal keys keep the
+	/// server's [`OrderBy`] order; wrap the key in [`std::cmp::Reverse`] to
+	/// sort descending.
+	///
+	/// ## Example
+	/// ```rust,no_run
+	/// use std::cmp::Reverse;
+	/// use usgs_earthquake_api::UsgsClient;
+	///
+	/// #[tokio::main]
+	/// async fn main() {
+	///     let client = UsgsClient::new();
+	///     let response = client.query()
+	///         .min_magnitude(4.5)
+	///         .order_by_key(|feature| Reverse(feature.properties.sig))
+	///         .fetch()
+	///         .await
+	///         .unwrap();
+	/// }
+	/// ```
+	pub fn order_by_key<K: Ord>(mut self, key: impl Fn(&EarthquakeFeatures) -> K + Send + Sync + 'a) -> Self {
+		self.order_by_key = Some(Box::new(move |a, b| key(a).cmp(&key(b))));
+		self
+	}
+
+	/// Sends this query to a different endpoint or mirror (e.g. a caching
+	/// proxy) instead of the client's base URL.
+	pub fn base_url(mut self, base_url: &str) -> Self {
+		self.base_url = base_url.to_string();
+		self
+	}
+
+	/// Limits results to a specific catalog (e.g. `"us"`, `"ak"`).
+	pub fn catalog(mut self, catalog: impl Into<CatalogId>) -> Self {
+		self.catalog = Some(catalog.into());
+		self
+	}
+
+	/// Queries the scenario (hypothetical) event catalogs instead of real events.
+	///
+	/// Combine with [`UsgsQuery::catalog`] to select a specific scenario catalog.
+	pub fn scenario(mut self, scenario: bool) -> Self {
+		self.scenario = scenario;
+		self
+	}
+
+	/// Limits results to events within `radius_km` kilometers of a point.
+	pub fn circle(mut self, latitude: f64, longitude: f64, radius_km: f64) -> Self {
+		self.circle = Some((latitude, longitude, radius_km));
+		self
+	}
+
+	/// Limits results to events felt by at least `felt` people.
+	pub fn min_felt(mut self, felt: u32) -> Self {
+		self.min_felt = Some(felt);
+		self
+	}
+
+	/// Limits results to events with a significance index of at least `sig`.
+	pub fn min_significance(mut self, sig: u32) -> Self {
+		self.min_significance = Some(sig);
+		self
+	}
+
+	/// Also returns deleted events, with `status` set to `deleted`, so mirrors
+	/// can remove withdrawn events (see [`EarthquakeFeatures::is_deleted`]).
+	pub fn include_deleted(mut self) -> Self {
+		self.include_deleted = true;
+		self
+	}
+
+	/// Also returns superseded product versions (`includesuperseded=true`).
+	///
+	/// USGS only honours this together with an event id; see
+	/// [`UsgsClient::event_history`] for the history of a single event.
+	pub fn include_superseded(mut self) -> Self {
+		self.include_superseded = true;
+		self
+	}
+
+	/// Sets the overall deadline of this fetch, covering all attempts, retry
+	/// backoff and reading the body, overriding [`UsgsClientBuilder::deadline`].
+	pub fn deadline(mut self, deadline: std::time::Duration) -> Self {
+		self.deadline = Some(deadline);
+		self
+	}
+
+	/// Retries server-side timeouts (`503`/`504` or a read timeout) of
+	/// [`UsgsQuery::fetch`] by halving the time range, recursively down to
+	/// `min_window`, and merging the results.
+	///
+	/// Long time ranges frequently time out on the service; this returns
+	/// their events instead of the error. Merged events are de-duplicated by
+	/// id and ordered as requested. [`UsgsQuery::fetch_cached`] splits the
+	/// same way, while [`UsgsQuery::fetch_raw`] rejects split queries.
+	///
+	/// ## Example
+	/// ```rust,no_run
+	/// use chrono::Duration;
+	/// use usgs_earthquake_api::UsgsClient;
+	///
+	/// #[tokio::main]
+	/// async fn main() {
+	///     let client = UsgsClient::new();
+	///     let response = client.query()
+	///         .start_time(2000, 1, 1, 0, 0)
+	///         .min_magnitude(2.5)
+	///         .split_on_timeout(Duration::days(7))
+	///         .fetch()
+	///         .await
+	///         .unwrap();
+	/// }
+	/// ```
+	pub fn split_on_timeout(mut self, min_window: Duration) -> Self {
+		self.split_on_timeout = Some(min_window);
+		self
+	}
+
+	/// Validates the parameters and returns the fully built request URL
+	/// without performing the HTTP call.
+	///
+	/// Useful for logging, cache keys, debugging, or handing the URL to
+	/// another system.
+	pub fn build_url(&self) -> Result<String, UsgsError> {
 
 		if self.start_time.is_none() {
 			return Err(UsgsError::EmptyStartTime)
@@ -224,34 +1116,348 @@ impl<'a> UsgsQuery<'a> {
 		}
 
 
-		let mut url = format!("{}&starttime={}&endtime={}&minmagnitude={}&maxmagnitude={}&alertlevel={}&orderby={}"
-		                     ,self.base_url, start_time, self.end_time, self.min_magnitude, self.max_magnitude, self.alert_level.to_string(), self.order_by.to_string());
+		if let Some((latitude, longitude, radius_km)) = self.circle {
+			if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+				return Err(UsgsError::InvalidCoordinates)
+			}
+			if radius_km < 0.0 {
+				return Err(UsgsError::InvalidRadius)
+			}
+		}
 
-		if self.alert_level.to_string() == "all" {
-			url = format!("{}&starttime={}&endtime={}&minmagnitude={}&maxmagnitude={}&orderby={}"
-			                  ,self.base_url, start_time.and_utc(), self.end_time, self.min_magnitude, self.max_magnitude, self.order_by.to_string());
+		let extensions = [
+			("alertlevel", !matches!(self.alert_level, AlertLevel::All)),
+			("maxradiuskm", self.circle.is_some()),
+			("minfelt", self.min_felt.is_some()),
+			("minsig", self.min_significance.is_some()),
+			("includedeleted", self.include_deleted),
+			("includesuperseded", self.include_superseded),
+		];
+		for (parameter, used) in extensions {
+			if used && !self.profile.supports(parameter) {
+				return Err(UsgsError::UnsupportedParameter(parameter.to_string()))
+			}
 		}
 
-		let response = self.client.get(&url).send().await?;
-		let mut body: EarthquakeResponse = response.json().await?;
-		if !self.country_code.is_empty() {
-			let boundaries = CountryBoundaries::from_reader(BOUNDARIES_ODBL_360X180).expect("Failed to parse BOUNDARIES_ODBL_360X180");
-			let target_code = &self.country_code;
-			let filtered_features: Vec<EarthquakeFeatures> = body.features.into_iter()
-				.filter(|eq| {
-					let coordinates = &eq.geometry.coordinates;
-					let lon = coordinates[0] as f64;
-					let lat = coordinates[1] as f64;
-					let country_codes = boundaries.ids(LatLon::new(lat, lon).expect("Failed to parse LatLon"));
-					country_codes.contains(&&**target_code)
-				})
-			.collect();
+		let base_url = if self.scenario {
+			self.profile.scenario_base_url.as_ref().ok_or(UsgsError::UnsupportedParameter("scenario".to_string()))?
+		} else {
+			&self.base_url
+		};
+
+		let mut url = Url::parse(base_url).map_err(|e| UsgsError::InvalidUrl(e.to_string()))?;
+		url.query_pairs_mut().extend_pairs(self.parameters(start_time));
+		debug!("built query URL {}", url);
+
+		Ok(url.to_string())
+	}
+
+	/// Collects the query parameters sent to the service, in request order.
+	fn parameters(&self, start_time: NaiveDateTime) -> Vec<(&'static str, String)> {
+		let mut parameters = vec![
+			("format", self.profile.format_parameter().to_string()),
+		];
+		if self.profile.format == ResponseFormat::GeoJson && self.profile.supports("jsonerror") {
+			parameters.push(("jsonerror", "true".to_string()));
+		}
+		parameters.extend([
+			("starttime", format_url_time(start_time)),
+			("endtime", format_url_time(self.end_time)),
+			("minmagnitude", self.min_magnitude.to_string()),
+			("maxmagnitude", self.max_magnitude.to_string()),
+			("orderby", self.order_by.to_string()),
+		]);
+
+		if !matches!(self.alert_level, AlertLevel::All) {
+			parameters.push(("alertlevel", self.alert_level.to_string()));
+		}
+
+		if let Some(catalog) = &self.catalog {
+			parameters.push(("catalog", catalog.to_string()));
+		}
+
+		if let Some((latitude, longitude, radius_km)) = self.server_circle() {
+			parameters.push(("latitude", latitude.to_string()));
+			parameters.push(("longitude", longitude.to_string()));
+			parameters.push(("maxradiuskm", radius_km.to_string()));
+		}
+
+		if let Some((min_latitude, max_latitude, min_longitude, max_longitude)) = self.rectangle() {
+			parameters.push(("minlatitude", min_latitude.to_string()));
+			parameters.push(("maxlatitude", max_latitude.to_string()));
+			parameters.push(("minlongitude", min_longitude.to_string()));
+			parameters.push(("maxlongitude", max_longitude.to_string()));
+		}
+
+		if let Some(felt) = self.min_felt {
+			parameters.push(("minfelt", felt.to_string()));
+		}
+
+		if let Some(sig) = self.min_significance {
+			parameters.push(("minsig", sig.to_string()));
+		}
+
+		if self.include_deleted {
+			parameters.push(("includedeleted", "true".to_string()));
+		}
+
+		if self.include_superseded {
+			parameters.push(("includesuperseded", "true".to_string()));
+		}
+
+		parameters
+	}
+
+	/// Rectangle sent to the service: the one of a parsed URL, otherwise the
+	/// bounding box of the filtered countries, so the server drops most
+	/// events before the precise client-side boundary check.
+	#[cfg(feature = "country-filter")]
+	fn rectangle(&self) -> Option<(f64, f64, f64, f64)> {
+		self.rectangle.or_else(|| match self.country_prefilter {
+			CountryPrefilter::BoundingBox if !self.country_codes.is_empty() => bounding_box(self.client.country_extents(), &self.country_codes),
+			_ => None,
+		})
+	}
+
+	/// Circle sent to the service: the explicit one, otherwise the circle
+	/// enclosing the filtered countries with [`CountryPrefilter::Circle`]
+	/// if the service supports `maxradiuskm`.
+	#[cfg(feature = "country-filter")]
+	fn server_circle(&self) -> Option<(f64, f64, f64)> {
+		self.circle.or_else(|| match self.country_prefilter {
+			CountryPrefilter::Circle if !self.country_codes.is_empty() && self.profile.supports("maxradiuskm") => {
+				enclosing_circle(self.client.country_extents(), &self.country_codes)
+			}
+			_ => None,
+		})
+	}
+
+	#[cfg(not(feature = "country-filter"))]
+	fn server_circle(&self) -> Option<(f64, f64, f64)> {
+		self.circle
+	}
+
+	#[cfg(not(feature = "country-filter"))]
+	fn rectangle(&self) -> Option<(f64, f64, f64, f64)> {
+		self.rectangle
+	}
+
+	/// Alias for [`UsgsQuery::build_url`].
+	pub fn dry_run(&self) -> Result<String, UsgsError> {
+		self.build_url()
+	}
+
+	/// Keeps only the events located inside the configured countries.
+	#[cfg(feature = "country-filter")]
+	fn filter_by_country(&self, mut body: EarthquakeResponse) -> EarthquakeResponse {
+		if !self.country_codes.is_empty() {
+			let boundaries = self.client.country_boundaries();
+			let wanted = &self.country_codes;
+			let locate = |mut eq: EarthquakeFeatures| {
+				let coordinates = &eq.geometry.coordinates;
+				let lon = coordinates[0] as f64;
+				let lat = coordinates[1] as f64;
+				let country_codes = boundaries.ids(LatLon::new(lat, lon).expect("Failed to parse LatLon"));
+				let keep = wanted.iter().any(|code| country_codes.contains(&code.as_str()));
+				eq.country = country_codes.into_iter().find(|id| !id.contains('-')).map(str::to_string);
+				keep.then_some(eq)
+			};
+
+			#[cfg(feature = "parallel")]
+			let filtered_features: Vec<EarthquakeFeatures> = if body.features.len() >= PARALLEL_COUNTRY_FILTER_THRESHOLD {
+				use rayon::prelude::*;
+				body.features.into_par_iter().filter_map(locate).collect()
+			} else {
+				body.features.into_iter().filter_map(locate).collect()
+			};
+			#[cfg(not(feature = "parallel"))]
+			let filtered_features: Vec<EarthquakeFeatures> = body.features.into_iter().filter_map(locate).collect();
 
 			body.features = filtered_features;
 			body.metadata.count = body.features.len() as u32;
+			body.recompute_bbox();
+		}
+		body
+	}
+
+	/// Keeps only the events at or above the configured alert level.
+	fn filter_by_alert_level(&self, mut body: EarthquakeResponse) -> EarthquakeResponse {
+		if let Some(min) = self.min_alert_level.filter(|min| *min != AlertLevel::All) {
+			body.features.retain(|feature| feature.alert().is_some_and(|level| level >= min));
+			body.metadata.count = body.features.len() as u32;
+			body.recompute_bbox();
+		}
+		body
+	}
+
+	/// Executes the query against the USGS API.
+	///
+	/// # Returns
+	/// `Result<EarthquakeResponse, UsgsError>`
+	pub async fn fetch(self) -> Result<EarthquakeResponse, UsgsError> {
+		match self.split_on_timeout {
+			Some(min_window) => self.fetch_splitting(min_window).await,
+			None => Ok(self.fetch_raw().await?.response),
+		}
+	}
+
+	/// Executes the query and returns the untouched response body alongside
+	/// the typed (and client-side filtered) response.
+	///
+	/// Fails with [`UsgsError::Config`] for queries using
+	/// [`UsgsQuery::split_on_timeout`], since the events of a split query
+	/// come from several bodies; use [`UsgsQuery::fetch`] for those.
+	pub async fn fetch_raw(self) -> Result<RawEarthquakeResponse, UsgsError> {
+		if self.split_on_timeout.is_some() {
+			return Err(UsgsError::Config("split_on_timeout cannot be combined with fetch_raw".to_string()));
+		}
+		let url = self.build_url()?;
+		let body = self.fetch_body(&url).await?;
+		let downloaded_bytes = body.downloaded_bytes;
+		let raw = body.text;
+		let response = self.parse_body(&raw, &url)?;
+		Ok(RawEarthquakeResponse {
+			downloaded_bytes,
+			decompressed_bytes: raw.len() as u64,
+			raw,
+			response,
+		})
+	}
+
+	/// Requests a URL built from this query and returns its body.
+	async fn fetch_body(&self, url: &str) -> Result<Body, UsgsError> {
+		let request = async {
+			let response = self.client.send(url).await?;
+			let status = response.status();
+			if !status.is_success() {
+				let body = read_body(response).await.map(|body| body.text).unwrap_or_default();
+				return Err(UsgsError::api(status, &body));
+			}
+			read_body(response).await
+		};
+		match self.deadline {
+			Some(deadline) => tokio::time::timeout(deadline, request).await.map_err(|_| UsgsError::Timeout(TimeoutPhase::Deadline))?,
+			None => request.await,
+		}
+	}
+
+	/// Parses a response body of this query and applies the client-side filters.
+	fn parse_body(&self, raw: &str, url: &str) -> Result<EarthquakeResponse, UsgsError> {
+		Ok(self.filter(self.parse_unfiltered(raw, url)?))
+	}
+
+	/// Parses a response body of this query.
+	///
+	/// An empty body (as sent with `204 No Content`) is an empty response.
+	fn parse_unfiltered(&self, raw: &str, url: &str) -> Result<EarthquakeResponse, UsgsError> {
+		if raw.trim().is_empty() {
+			let url = Url::parse(url).map_err(|e| UsgsError::InvalidUrl(e.to_string()))?;
+			return Ok(EarthquakeResponse::empty(url));
+		}
+
+		let body: EarthquakeResponse = match self.profile.format {
+			ResponseFormat::GeoJson => parse_response_with(raw, self.client.parse_mode).map_err(|err| UsgsError::json_api(raw).unwrap_or(err))?,
+			ResponseFormat::Text => parse_text_response(raw, url, self.profile)?,
+		};
+		if body.metadata.status != reqwest::StatusCode::OK {
+			return Err(UsgsError::ApiStatus(body.metadata.status));
 		}
 		Ok(body)
+	}
+
+	/// Applies the client-side filters and ordering of this query.
+	fn filter(&self, body: EarthquakeResponse) -> EarthquakeResponse {
+		#[cfg(feature = "country-filter")]
+		let body = self.filter_by_country(body);
+		let mut body = self.filter_by_alert_level(body);
+		if let Some(compare) = &self.order_by_key {
+			body.features.sort_by(compare);
+		}
+		body
+	}
+
+	/// Stable hash of the normalized request, usable as a cache key across
+	/// processes and restarts.
+	///
+	/// Parameters are sorted and the start and end times truncated to the
+	/// minute, so repeated relative queries (e.g. "past 24 hours") share a
+	/// key within the same minute. Client-side filters such as the exact
+	/// country boundary check are not part of the key since they apply after
+	/// the response is received.
+	pub fn cache_key(&self) -> Result<String, UsgsError> {
+		let url = Url::parse(&self.build_url()?).map_err(|e| UsgsError::InvalidUrl(e.to_string()))?;
+		let mut parameters: Vec<(String, String)> = url.query_pairs()
+			.map(|(key, value)| match key.as_ref() {
+				"starttime" | "endtime" => (key.to_string(), value.chars().take(16).collect()),
+				_ => (key.to_string(), value.to_string()),
+			})
+			.collect();
+		parameters.sort();
 
+		let mut normalized = format!("{}{}{}", url.scheme(), url.host_str().unwrap_or_default(), url.path());
+		for (key, value) in parameters {
+			normalized.push_str(&format!("&{}={}", key, value));
+		}
+		Ok(format!("query:{:016x}", stable_hash(normalized.as_bytes())))
+	}
+
+	/// Executes the query unless a response for the same [`UsgsQuery::cache_key`]
+	/// younger than `ttl` is found in `cache`.
+	///
+	/// The untouched body is cached, so queries differing only in their
+	/// client-side filters share an entry. With
+	/// [`UsgsQuery::split_on_timeout`] the windows are fetched as by
+	/// [`UsgsQuery::fetch`] and their merged events are cached as GeoJSON
+	/// under a separate entry. Cache failures are logged and otherwise
+	/// ignored.
+	///
+	/// ## Example
+	/// ```rust,no_run
+	/// use std::time::Duration;
+	/// use usgs_earthquake_api::{DiskCache, UsgsClient};
+	///
+	/// #[tokio::main]
+	/// async fn main() {
+	///     let cache = DiskCache::new("/tmp/usgs-cache");
+	///     let client = UsgsClient::new();
+	///     let result = client.query().start_time(2024, 1, 1, 0, 0).fetch_cached(&cache, Duration::from_secs(300)).await;
+	/// }
+	/// ```
+	pub async fn fetch_cached(self, cache: &impl CacheBackend, ttl: std::time::Duration) -> Result<EarthquakeResponse, UsgsError> {
+		let key = match self.split_on_timeout {
+			Some(_) => format!("{}:split", self.cache_key()?),
+			None => self.cache_key()?,
+		};
+		match cache.get(&key).await {
+			Ok(Some(body)) => {
+				debug!("cache hit for {}", key);
+				let raw = String::from_utf8_lossy(&body);
+				return match self.split_on_timeout {
+					Some(_) => {
+						let merged = parse_response_with(&raw, self.client.parse_mode).map_err(|err| UsgsError::json_api(&raw).unwrap_or(err))?;
+						Ok(self.filter(merged))
+					}
+					None => self.parse_body(&raw, &self.build_url()?),
+				};
+			}
+			Ok(None) => {}
+			Err(err) => debug!("cache lookup for {} failed: {}", key, err),
+		}
+
+		let (raw, response) = match self.split_on_timeout {
+			Some(min_window) => {
+				let merged = self.fetch_windows(min_window).await?;
+				(serde_json::to_string(&merged)?, self.filter(merged))
+			}
+			None => {
+				let raw = self.fetch_raw().await?;
+				(raw.raw, raw.response)
+			}
+		};
+		if let Err(err) = cache.put(&key, raw.into_bytes(), ttl).await {
+			debug!("cache store for {} failed: {}", key, err);
+		}
+		Ok(response)
 	}
 }
 
@@ -279,4 +1485,33 @@ impl Display for OrderBy {
 		};
 		write!(f, "{}", s)
 	}
-}
\ No newline at end of file
+}
+
+impl FromStr for AlertLevel {
+	type Err = UsgsError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"green" => Ok(AlertLevel::Green),
+			"yellow" => Ok(AlertLevel::Yellow),
+			"orange" => Ok(AlertLevel::Orange),
+			"red" => Ok(AlertLevel::Red),
+			"all" => Ok(AlertLevel::All),
+			other => Err(UsgsError::InvalidUrl(format!("unknown alert level `{}`", other))),
+		}
+	}
+}
+
+impl FromStr for OrderBy {
+	type Err = UsgsError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"time" => Ok(OrderBy::Time),
+			"time-asc" => Ok(OrderBy::TimeAsc),
+			"magnitude" => Ok(OrderBy::Magnitude),
+			"magnitude-asc" => Ok(OrderBy::MagnitudeAsc),
+			other => Err(UsgsError::InvalidUrl(format!("unknown ordering `{}`", other))),
+		}
+	}
+}
@@ -0,0 +1,206 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use chrono::Utc;
+#[cfg(feature = "country-filter")]
+use country_boundaries::CountryBoundaries;
+#[cfg(feature = "country-filter")]
+use crate::country::country::{country_at, embedded_boundaries};
+use log::debug;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, ToSocketAddrs};
+use crate::error::error::UsgsError;
+use crate::models::models::EarthquakeFeatures;
+use crate::{ConfigureQuery, UsgsClient, UsgsQuery};
+
+/// Values of the last refresh.
+#[derive(Debug, Default)]
+struct Snapshot {
+	events_last_hour: usize,
+	events_last_day: usize,
+	max_magnitude_last_day: Option<f64>,
+	events_by_country: BTreeMap<String, usize>,
+	refreshes: u64,
+	refresh_errors: u64,
+	last_refresh: Option<i64>,
+}
+
+/// Keeps a query refreshed and exposes seismicity metrics in the Prometheus
+/// text format, for Grafana dashboards and alerting rules.
+///
+/// Every refresh fetches the events of the past 24 hours (narrowed by
+/// [`MetricsExporter::configure`]) and exports:
+/// - `usgs_events_last_hour` and `usgs_events_last_day`
+/// - `usgs_max_magnitude_last_day`
+/// - `usgs_events_last_day_by_country{country="TR"}` (`country-filter` feature)
+/// - `usgs_refreshes_total`, `usgs_refresh_errors_total` and
+///   `usgs_last_refresh_timestamp_seconds`
+///
+/// ## Example
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use usgs_earthquake_api::{MetricsExporter, UsgsClient};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     MetricsExporter::new(UsgsClient::new())
+///         .configure(|query| query.min_magnitude(2.5))
+///         .refresh_interval(Duration::from_secs(60))
+///         .serve("0.0.0.0:9184")
+///         .await
+///         .unwrap();
+/// }
+/// ```
+#[derive(Clone)]
+pub struct MetricsExporter {
+	client: UsgsClient,
+	configure: Arc<ConfigureQuery>,
+	refresh_interval: Duration,
+	snapshot: Arc<RwLock<Snapshot>>,
+	#[cfg(feature = "country-filter")]
+	boundaries: Arc<CountryBoundaries>,
+}
+
+impl std::fmt::Debug for MetricsExporter {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("MetricsExporter")
+			.field("refresh_interval", &self.refresh_interval)
+			.finish_non_exhaustive()
+	}
+}
+
+impl MetricsExporter {
+	/// Creates an exporter querying worldwide events with `client`.
+	pub fn new(client: UsgsClient) -> Self {
+		Self {
+			client,
+			configure: Arc::new(|query| query),
+			refresh_interval: Duration::from_secs(60),
+			snapshot: Arc::new(RwLock::new(Snapshot::default())),
+			#[cfg(feature = "country-filter")]
+			boundaries: Arc::new(embedded_boundaries()),
+		}
+	}
+
+	/// Narrows the refreshed query, e.g. by magnitude, region or catalog.
+	///
+	/// The time range is always the past 24 hours.
+	pub fn configure(mut self, configure: impl for<'a> Fn(UsgsQuery<'a>) -> UsgsQuery<'a> + Send + Sync + 'static) -> Self {
+		self.configure = Arc::new(configure);
+		self
+	}
+
+	/// Sets how often the query is refreshed by [`MetricsExporter::serve`] (default 60 seconds).
+	pub fn refresh_interval(mut self, interval: Duration) -> Self {
+		self.refresh_interval = interval;
+		self
+	}
+
+	#[cfg(feature = "country-filter")]
+	fn country(&self, event: &EarthquakeFeatures) -> Option<String> {
+		event.country.clone()
+			.or_else(|| country_at(&self.boundaries, event.geometry.latitude(), event.geometry.longitude()))
+	}
+
+	#[cfg(not(feature = "country-filter"))]
+	fn country(&self, _event: &EarthquakeFeatures) -> Option<String> {
+		None
+	}
+
+	/// Fetches the events of the past 24 hours and updates the metrics.
+	pub async fn refresh(&self) -> Result<(), UsgsError> {
+		let query = (self.configure)(self.client.query().worldwide().since(chrono::Duration::days(1)));
+		let result = query.fetch().await;
+		let mut snapshot = self.snapshot.write().expect("metrics lock poisoned");
+		snapshot.refreshes += 1;
+		let response = match result {
+			Ok(response) => response,
+			Err(err) => {
+				snapshot.refresh_errors += 1;
+				return Err(err);
+			}
+		};
+
+		let now = Utc::now().timestamp_millis();
+		let hour_ago = (now - 60 * 60 * 1000).max(0) as u64;
+		snapshot.events_last_hour = response.features.iter().filter(|event| event.properties.time.is_some_and(|time| time >= hour_ago)).count();
+		snapshot.events_last_day = response.features.len();
+		snapshot.max_magnitude_last_day = response.features.iter().filter_map(|event| event.properties.magnitude).reduce(f64::max);
+		snapshot.events_by_country.clear();
+		for event in &response.features {
+			if let Some(country) = self.country(event) {
+				*snapshot.events_by_country.entry(country).or_default() += 1;
+			}
+		}
+		snapshot.last_refresh = Some(now / 1000);
+		Ok(())
+	}
+
+	/// Renders the current metrics in the Prometheus text exposition format.
+	pub fn render(&self) -> String {
+		let snapshot = self.snapshot.read().expect("metrics lock poisoned");
+		let mut out = String::new();
+		let mut metric = |name: &str, kind: &str, help: &str, samples: &[(String, String)]| {
+			let _ = writeln!(out, "# HELP {} {}", name, help);
+			let _ = writeln!(out, "# TYPE {} {}", name, kind);
+			for (labels, value) in samples {
+				let _ = writeln!(out, "{}{} {}", name, labels, value);
+			}
+		};
+
+		metric("usgs_events_last_hour", "gauge", "Events in the past hour.", &[(String::new(), snapshot.events_last_hour.to_string())]);
+		metric("usgs_events_last_day", "gauge", "Events in the past 24 hours.", &[(String::new(), snapshot.events_last_day.to_string())]);
+		let max_magnitude = snapshot.max_magnitude_last_day.map(|m| m.to_string()).unwrap_or_else(|| "NaN".to_string());
+		metric("usgs_max_magnitude_last_day", "gauge", "Largest magnitude in the past 24 hours.", &[(String::new(), max_magnitude)]);
+		let by_country: Vec<(String, String)> = snapshot.events_by_country.iter()
+			.map(|(country, count)| (format!("{{country=\"{}\"}}", country), count.to_string()))
+			.collect();
+		metric("usgs_events_last_day_by_country", "gauge", "Events in the past 24 hours by epicenter country.", &by_country);
+		metric("usgs_refreshes_total", "counter", "Query refreshes attempted.", &[(String::new(), snapshot.refreshes.to_string())]);
+		metric("usgs_refresh_errors_total", "counter", "Query refreshes that failed.", &[(String::new(), snapshot.refresh_errors.to_string())]);
+		if let Some(last_refresh) = snapshot.last_refresh {
+			metric("usgs_last_refresh_timestamp_seconds", "gauge", "Unix time of the last successful refresh.", &[(String::new(), last_refresh.to_string())]);
+		}
+		out
+	}
+
+	/// Refreshes the query in the background and serves the metrics on
+	/// `GET /metrics` at `address` until an I/O error occurs.
+	pub async fn serve(self, address: impl ToSocketAddrs) -> Result<(), UsgsError> {
+		let listener = TcpListener::bind(address).await?;
+
+		let refresher = self.clone();
+		let refresh_task = tokio::spawn(async move {
+			let mut interval = tokio::time::interval(refresher.refresh_interval);
+			loop {
+				interval.tick().await;
+				if let Err(err) = refresher.refresh().await {
+					debug!("metrics refresh failed: {}", err);
+				}
+			}
+		});
+
+		let result = loop {
+			let (mut stream, _) = match listener.accept().await {
+				Ok(connection) => connection,
+				Err(err) => break Err(err.into()),
+			};
+			let exporter = self.clone();
+			tokio::spawn(async move {
+				let mut request = [0; 1024];
+				let read = stream.read(&mut request).await.unwrap_or(0);
+				let request = String::from_utf8_lossy(&request[..read]);
+				let response = if request.starts_with("GET /metrics ") {
+					let body = exporter.render();
+					format!("HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", body.len(), body)
+				} else {
+					"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string()
+				};
+				let _ = stream.write_all(response.as_bytes()).await;
+			});
+		};
+		refresh_task.abort();
+		result
+	}
+}
\ No newline at end of file
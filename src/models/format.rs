@@ -0,0 +1,131 @@
+use chrono::{TimeZone, Utc};
+use crate::models::models::{EarthquakeFeatures, Unit};
+
+/// Rounding rules for showing events in user interfaces, so every view
+/// renders the same values the same way.
+///
+/// Magnitudes are shown with one decimal, depths in whole units,
+/// coordinates with a configurable number of decimals (default 2, about
+/// 1 km) and origin times in a chosen time zone.
+///
+/// ## Example
+/// ```rust,no_run
+/// use chrono::FixedOffset;
+/// use usgs_earthquake_api::{EventFormat, UsgsClient};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let response = UsgsClient::new().query().min_magnitude(4.5).fetch().await.unwrap();
+///     let format = EventFormat::new()
+///         .coordinate_precision(3)
+///         .timezone(FixedOffset::east_opt(3 * 3600).unwrap());
+///     for event in &response.features {
+///         println!("{} {} {} {}", format.magnitude(event), format.depth(event), format.coordinates(event), format.time(event));
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct EventFormat<Tz: TimeZone = Utc> {
+	coordinate_precision: usize,
+	depth_unit: Unit,
+	timezone: Tz,
+	time_format: String,
+}
+
+impl EventFormat {
+	/// Creates a format showing coordinates with 2 decimals, depths in
+	/// kilometers and times in UTC.
+	pub fn new() -> Self {
+		Self {
+			coordinate_precision: 2,
+			depth_unit: Unit::Kilometers,
+			timezone: Utc,
+			time_format: "%Y-%m-%d %H:%M %Z".to_string(),
+		}
+	}
+}
+
+impl Default for EventFormat {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// `value` without the minus sign of a negative zero, e.g. a depth of
+/// -0.2 km rounded to whole kilometers.
+fn unsigned_zero(value: f64) -> f64 {
+	if value == 0.0 { 0.0 } else { value }
+}
+
+impl<Tz: TimeZone> EventFormat<Tz> {
+	/// Sets the number of decimals of coordinates.
+	pub fn coordinate_precision(mut self, decimals: usize) -> Self {
+		self.coordinate_precision = decimals;
+		self
+	}
+
+	/// Sets the unit of depths.
+	pub fn depth_unit(mut self, unit: Unit) -> Self {
+		self.depth_unit = unit;
+		self
+	}
+
+	/// Shows times in `timezone`, e.g. a `chrono::FixedOffset` or a
+	/// `chrono_tz::Tz`.
+	pub fn timezone<T: TimeZone>(self, timezone: T) -> EventFormat<T> {
+		EventFormat {
+			coordinate_precision: self.coordinate_precision,
+			depth_unit: self.depth_unit,
+			timezone,
+			time_format: self.time_format,
+		}
+	}
+
+	/// Sets the `chrono` format string of times (default `%Y-%m-%d %H:%M %Z`).
+	pub fn time_format(mut self, format: &str) -> Self {
+		self.time_format = format.to_string();
+		self
+	}
+
+	/// Magnitude with one decimal and its type, e.g. `M 5.4 mww`, or `M ?`.
+	pub fn magnitude(&self, event: &EarthquakeFeatures) -> String {
+		match (event.properties.magnitude, event.properties.magnitude_type.as_deref()) {
+			(Some(magnitude), Some(magnitude_type)) => format!("M {:.1} {}", magnitude, magnitude_type),
+			(Some(magnitude), None) => format!("M {:.1}", magnitude),
+			(None, _) => "M ?".to_string(),
+		}
+	}
+
+	/// Depth in whole units, e.g. `10 km`.
+	pub fn depth(&self, event: &EarthquakeFeatures) -> String {
+		let suffix = match self.depth_unit {
+			Unit::Kilometers => "km",
+			Unit::Miles => "mi",
+		};
+		format!("{} {}", unsigned_zero(event.geometry.depth(self.depth_unit).round()), suffix)
+	}
+
+	/// Epicenter with hemispheres, e.g. `37.23°N, 141.97°E`.
+	pub fn coordinates(&self, event: &EarthquakeFeatures) -> String {
+		let precision = self.coordinate_precision;
+		let scale = 10f64.powi(precision as i32);
+		let round = |value: f64| unsigned_zero((value * scale).round() / scale);
+		let (latitude, longitude) = (round(event.geometry.latitude()), round(event.geometry.longitude()));
+		format!(
+			"{:.*}°{}, {:.*}°{}",
+			precision, latitude.abs(), if latitude < 0.0 { 'S' } else { 'N' },
+			precision, longitude.abs(), if longitude < 0.0 { 'W' } else { 'E' },
+		)
+	}
+
+	/// Origin time in the configured time zone and format, or `unknown time`.
+	pub fn time(&self, event: &EarthquakeFeatures) -> String
+	where
+		Tz::Offset: std::fmt::Display,
+	{
+		match event.origin_time() {
+			Some(time) => time.with_timezone(&self.timezone).format(&self.time_format).to_string(),
+			None => "unknown time".to_string(),
+		}
+	}
+}
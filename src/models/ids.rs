@@ -0,0 +1,109 @@
+use std::borrow::Borrow;
+use std::fmt::{Display, Formatter};
+use std::ops::Deref;
+use serde::{Deserialize, Serialize};
+
+macro_rules! identifier {
+	($(#[$meta:meta])* $name:ident) => {
+		$(#[$meta])*
+		#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+		#[serde(transparent)]
+		pub struct $name(String);
+
+		impl $name {
+			/// Wraps an identifier.
+			pub fn new(id: impl Into<String>) -> Self {
+				Self(id.into())
+			}
+
+			/// The identifier as a string slice.
+			pub fn as_str(&self) -> &str {
+				&self.0
+			}
+
+			/// Unwraps the identifier.
+			pub fn into_string(self) -> String {
+				self.0
+			}
+		}
+
+		impl Display for $name {
+			fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+				f.write_str(&self.0)
+			}
+		}
+
+		impl Deref for $name {
+			type Target = str;
+
+			fn deref(&self) -> &str {
+				&self.0
+			}
+		}
+
+		impl AsRef<str> for $name {
+			fn as_ref(&self) -> &str {
+				&self.0
+			}
+		}
+
+		impl Borrow<str> for $name {
+			fn borrow(&self) -> &str {
+				&self.0
+			}
+		}
+
+		impl From<String> for $name {
+			fn from(id: String) -> Self {
+				Self(id)
+			}
+		}
+
+		impl From<&str> for $name {
+			fn from(id: &str) -> Self {
+				Self(id.to_string())
+			}
+		}
+
+		impl From<&$name> for $name {
+			fn from(id: &$name) -> Self {
+				id.clone()
+			}
+		}
+
+		impl From<$name> for String {
+			fn from(id: $name) -> Self {
+				id.0
+			}
+		}
+
+		impl PartialEq<str> for $name {
+			fn eq(&self, other: &str) -> bool {
+				self.0 == other
+			}
+		}
+
+		impl PartialEq<&str> for $name {
+			fn eq(&self, other: &&str) -> bool {
+				self.0 == *other
+			}
+		}
+	};
+}
+
+identifier! {
+	/// Event id as used by the `eventid` parameter and the GeoJSON `id`,
+	/// the network code followed by the event code (e.g. `us7000abcd`).
+	EventId
+}
+
+identifier! {
+	/// Code of a contributing seismic network (e.g. `us`, `ak`, `ci`), as in
+	/// the `net` property.
+	NetworkCode
+}
+
+identifier! {
+	/// Catalog name as accepted by the `catalog` parameter (e.g. `us`, `ak`).
+	CatalogId
+}
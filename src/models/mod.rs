@@ -1 +1,7 @@
-pub mod models;
\ No newline at end of file
+pub mod models;
+pub mod format;
+pub mod ids;
+pub mod place;
+pub mod product;
+pub mod schema;
+pub mod shakemap;
@@ -1,10 +1,18 @@
+use chrono::{DateTime, FixedOffset, TimeDelta, Utc};
+use reqwest::{StatusCode, Url};
 use serde::{Deserialize, Serialize};
+use crate::AlertLevel;
+use crate::error::error::UsgsError;
+use crate::models::ids::{EventId, NetworkCode};
+use crate::models::schema::ParseMode;
+#[cfg(feature = "extra-fields")]
+use serde_json::{Map, Value};
 
 
 /// Root response object from the USGS Earthquake API.
 ///
 /// Contains metadata, bounding box, and a list of earthquake features.
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct EarthquakeResponse {
 
 	/// Response type (always `"FeatureCollection"` for GeoJSON).
@@ -22,27 +30,56 @@ pub struct EarthquakeResponse {
 }
 
 
+/// Typed response together with the untouched body sent by the server.
+#[derive(Debug)]
+pub struct RawEarthquakeResponse {
+
+	/// Response body exactly as received, before any client-side filtering.
+	pub raw: String,
+
+	/// Parsed (and client-side filtered) response.
+	pub response: EarthquakeResponse,
+
+	/// Bytes received over the network (compressed size, if compressed).
+	pub downloaded_bytes: u64,
+
+	/// Size of the body after decompression.
+	pub decompressed_bytes: u64
+}
+
+
+/// Result of a successful [`UsgsClient::health_check`](crate::UsgsClient::health_check).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HealthStatus {
+	/// Version reported by the service.
+	pub version: String,
+
+	/// Time until the service answered, including retries.
+	pub latency: std::time::Duration,
+}
+
+
 /// Metadata returned by the USGS Earthquake API.
 ///
 /// Includes API version, request information, and count of features.
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct EarthquakeMetadata {
 
-	/// Unix timestamp when the data was generated.
-	#[serde(rename = "generated")]
-	pub generated_timestamp: u64,
+	/// Time the data was generated.
+	#[serde(rename = "generated", with = "chrono::serde::ts_milliseconds")]
+	pub generated_timestamp: DateTime<Utc>,
 
 	/// URL of the API request.
 	#[serde(rename = "url")]
-	pub url: String,
+	pub url: Url,
 
 	/// Human-readable title of the dataset.
 	#[serde(rename = "title")]
 	pub title: String,
 
-	/// HTTP-like status code of the response.
-	#[serde(rename = "status")]
-	pub status: u32,
+	/// HTTP status of the response.
+	#[serde(rename = "status", with = "status_code")]
+	pub status: StatusCode,
 
 	/// Version of the API used.
 	#[serde(rename = "api")]
@@ -50,12 +87,232 @@ pub struct EarthquakeMetadata {
 
 	/// Number of earthquake events returned.
 	#[serde(rename = "count")]
-	pub count: u32
+	pub count: u32,
+
+	/// Fields not modelled by this crate, preserved as-is.
+	#[cfg(feature = "extra-fields")]
+	#[serde(flatten)]
+	pub extra: Map<String, Value>
+}
+
+/// (De)serializes a [`StatusCode`] as its numeric code.
+mod status_code {
+	use reqwest::StatusCode;
+	use serde::{de, Deserialize, Deserializer, Serializer};
+
+	pub fn serialize<S: Serializer>(status: &StatusCode, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_u16(status.as_u16())
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<StatusCode, D::Error> {
+		let code = u16::deserialize(deserializer)?;
+		StatusCode::from_u16(code).map_err(|_| de::Error::custom(format!("invalid HTTP status {}", code)))
+	}
+}
+
+
+/// Up to 40 characters of `json` around a 1-based line and column.
+fn snippet(json: &str, line: usize, column: usize) -> String {
+	let Some(text) = json.lines().nth(line.saturating_sub(1)) else {
+		return String::new();
+	};
+	let position = text.char_indices().nth(column.saturating_sub(1)).map(|(i, _)| i).unwrap_or(text.len());
+	let start = text[..position].char_indices().rev().nth(19).map(|(i, _)| i).unwrap_or(0);
+	let end = text[position..].char_indices().nth(20).map(|(i, _)| position + i).unwrap_or(text.len());
+	text[start..end].to_string()
+}
+
+/// Parses a USGS GeoJSON document obtained from any channel (S3 dumps,
+/// Kafka, ...) into the crate's models.
+///
+/// Errors report the path of the offending field and a snippet of the
+/// surrounding JSON in [`UsgsError::Parse`].
+///
+/// ## Example
+/// ```rust
+/// use usgs_earthquake_api::{parse_response, UsgsError};
+///
+/// let json = r#"{"type":"FeatureCollection","metadata":{"generated":1704067200000,"url":"https://earthquake.usgs.gov/fdsnws/event/1/query","title":"USGS Earthquakes","status":200,"api":"1.14.1","count":1},
+///     "features":[{"type":"Feature","properties":{"mag":4.2},"geometry":{"type":"Point","coordinates":"1,2"},"id":"us1"}]}"#;
+///
+/// match parse_response(json) {
+///     Err(UsgsError::Parse { path, .. }) => assert_eq!(path, "features[0].geometry.coordinates"),
+///     other => panic!("unexpected {:?}", other),
+/// }
+/// ```
+pub fn parse_response(json: &str) -> Result<EarthquakeResponse, UsgsError> {
+	parse_response_with(json, ParseMode::Lenient)
+}
+
+/// Parses a USGS GeoJSON document like [`parse_response`], in the given
+/// [`ParseMode`].
+///
+/// In [`ParseMode::Strict`] fields not modelled by this crate are reported
+/// as [`UsgsError::Schema`] with their path, also when the `extra-fields`
+/// feature would otherwise preserve them, and the lenient numeric coercions
+/// are off: numbers sent as strings or booleans, floats for integer fields
+/// (`nst: 12.0`) and out-of-range values fail with [`UsgsError::Parse`]
+/// instead of being converted or dropped.
+///
+/// ## Example
+/// ```rust
+/// use usgs_earthquake_api::{parse_response_with, ParseMode, UsgsError};
+///
+/// let json = r#"{"type":"FeatureCollection","metadata":{"generated":1704067200000,"url":"https://earthquake.usgs.gov/fdsnws/event/1/query","title":"USGS Earthquakes","status":200,"api":"1.14.1","count":1},
+///     "features":[{"type":"Feature","properties":{"mag":4.2,"shaking":"strong"},"geometry":{"type":"Point","coordinates":[1.0,2.0,3.0]},"id":"us1"}]}"#;
+///
+/// assert!(parse_response_with(json, ParseMode::Lenient).is_ok());
+/// match parse_response_with(json, ParseMode::Strict) {
+///     Err(UsgsError::Schema(message)) => assert!(message.contains("features.0.properties.shaking")),
+///     other => panic!("unexpected {:?}", other),
+/// }
+/// ```
+pub fn parse_response_with(json: &str, mode: ParseMode) -> Result<EarthquakeResponse, UsgsError> {
+	let mut unknown = None;
+	let _strict = lenient::strict(mode == ParseMode::Strict);
+	let deserializer = &mut serde_json::Deserializer::from_str(json);
+	let mut record_unknown = |path: serde_ignored::Path| {
+		unknown.get_or_insert_with(|| path.to_string());
+	};
+	let deserializer = serde_ignored::Deserializer::new(deserializer, &mut record_unknown);
+	let response: EarthquakeResponse = serde_path_to_error::deserialize(deserializer).map_err(|err| {
+		let path = err.path().to_string();
+		let inner = err.into_inner();
+		let (line, column) = (inner.line(), inner.column());
+		UsgsError::Parse {
+			path,
+			line,
+			column,
+			message: inner.to_string().split(" at line ").next().unwrap_or_default().to_string(),
+			snippet: snippet(json, line, column),
+		}
+	})?;
+
+	if mode == ParseMode::Strict {
+		#[cfg(feature = "extra-fields")]
+		if unknown.is_none() {
+			unknown = response.unknown_field();
+		}
+		if let Some(path) = unknown {
+			return Err(UsgsError::Schema(format!("unknown field `{}`", path)));
+		}
+	}
+	Ok(response)
+}
+
+/// Numeric deserializers tolerating the odd values occasionally found in
+/// USGS responses: floats for integer fields (`nst: 12.0`), numbers sent as
+/// strings or booleans, and out-of-range values, which become `None` instead of failing
+/// the whole response. Empty strings become `None` as well; any other
+/// non-numeric string is still rejected.
+///
+/// While a [`strict`] scope is active (during [`ParseMode::Strict`] parsing)
+/// none of these coercions apply: values must be JSON numbers of the field's
+/// kind and in range, or `null`.
+mod lenient {
+	use std::cell::Cell;
+	use serde::de::Error;
+	use serde::{Deserialize, Deserializer};
+
+	thread_local! {
+		static STRICT: Cell<bool> = const { Cell::new(false) };
+	}
+
+	/// Disables the coercions on this thread until the guard is dropped.
+	pub struct StrictScope(bool);
+
+	pub fn strict(enabled: bool) -> StrictScope {
+		StrictScope(STRICT.replace(enabled))
+	}
+
+	impl Drop for StrictScope {
+		fn drop(&mut self) {
+			STRICT.set(self.0);
+		}
+	}
+
+	#[derive(Deserialize)]
+	#[serde(untagged)]
+	enum Raw {
+		Number(f64),
+		Flag(bool),
+		Text(String),
+		Other(serde::de::IgnoredAny),
+	}
+
+	fn number<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<f64>, D::Error> {
+		if STRICT.get() {
+			return Option::<f64>::deserialize(deserializer);
+		}
+		Ok(match Option::<Raw>::deserialize(deserializer)? {
+			Some(Raw::Number(value)) => Some(value),
+			Some(Raw::Flag(flag)) => Some(if flag { 1.0 } else { 0.0 }),
+			Some(Raw::Text(text)) if text.trim().is_empty() => None,
+			Some(Raw::Text(text)) => Some(text.trim().parse().map_err(|_| D::Error::custom(format!("expected a number, found {:?}", text)))?),
+			Some(Raw::Other(_)) | None => None,
+		}.filter(|value: &f64| value.is_finite()))
+	}
+
+	fn integer<'de, D: Deserializer<'de>, T: TryFrom<i64>>(deserializer: D) -> Result<Option<T>, D::Error> {
+		if STRICT.get() {
+			return Option::<i64>::deserialize(deserializer)?
+				.map(|value| T::try_from(value).map_err(|_| D::Error::custom(format!("integer {} out of range", value))))
+				.transpose();
+		}
+		Ok(number(deserializer)?.and_then(|value| T::try_from(value.round() as i64).ok()))
+	}
+
+	pub fn f64<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<f64>, D::Error> {
+		number(deserializer)
+	}
+
+	pub fn f32<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<f32>, D::Error> {
+		Ok(number(deserializer)?.map(|value| value as f32))
+	}
+
+	pub fn u64<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u64>, D::Error> {
+		integer(deserializer)
+	}
+
+	pub fn u32<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u32>, D::Error> {
+		integer(deserializer)
+	}
+
+	pub fn u8<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u8>, D::Error> {
+		integer(deserializer)
+	}
+
+	pub fn i32<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<i32>, D::Error> {
+		integer(deserializer)
+	}
 }
 
+/// (De)serializes lists sent as comma-separated strings with leading and
+/// trailing commas, like `ids`; an empty list is written as `null`.
+mod comma_separated {
+	use serde::{Deserialize, Deserializer, Serializer};
+	use crate::models::ids::EventId;
+
+	pub fn serialize<S: Serializer>(ids: &[EventId], serializer: S) -> Result<S::Ok, S::Error> {
+		if ids.is_empty() {
+			return serializer.serialize_none();
+		}
+		let joined: Vec<&str> = ids.iter().map(EventId::as_str).collect();
+		serializer.serialize_str(&format!(",{},", joined.join(",")))
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<EventId>, D::Error> {
+		Ok(Option::<String>::deserialize(deserializer)?.unwrap_or_default()
+			.split(',')
+			.map(str::trim)
+			.filter(|id| !id.is_empty())
+			.map(EventId::from)
+			.collect())
+	}
+}
 
 /// Represents a single earthquake feature (event).
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct EarthquakeFeatures {
 
 	/// Feature type (usually `"Feature"`).
@@ -69,17 +326,22 @@ pub struct EarthquakeFeatures {
 	pub geometry: EarthquakeGeometry,
 
 	/// Unique identifier for the earthquake.
-	pub id: String
+	pub id: EventId,
+
+	/// ISO 3166-1 alpha-2 code of the epicenter country, when already
+	/// resolved by the client-side country filter. Not part of the GeoJSON.
+	#[serde(skip)]
+	pub country: Option<String>,
 }
 
 
 /// Detailed properties of an earthquake event.
 ///
 /// All fields are optional since not every event provides complete data.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Default)]
 pub struct EarthquakeProperties {
-	/// Magnitude of the earthquake.
-	#[serde(rename = "mag")]
+	/// Magnitude of the earthquake (can be negative for tiny events).
+	#[serde(rename = "mag", default, deserialize_with = "lenient::f64")]
 	pub magnitude: Option<f64>,
 
 	/// Location description (e.g., `"10km NE of City"`).
@@ -87,15 +349,15 @@ pub struct EarthquakeProperties {
 	pub place: Option<String>,
 
 	/// Event timestamp (milliseconds since Unix epoch).
-	#[serde(rename = "time")]
+	#[serde(rename = "time", default, deserialize_with = "lenient::u64")]
 	pub time: Option<u64>,
 
 	/// Last updated timestamp.
-	#[serde(rename = "updated")]
+	#[serde(rename = "updated", default, deserialize_with = "lenient::u64")]
 	pub updated_time: Option<u64>,
 
 	/// Timezone offset in minutes.
-	#[serde(rename = "tz")]
+	#[serde(rename = "tz", default, deserialize_with = "lenient::i32")]
 	pub tz: Option<i32>,
 
 	/// Event detail URL.
@@ -107,44 +369,47 @@ pub struct EarthquakeProperties {
 	pub detail: Option<String>,
 
 	/// Number of people who reported feeling the event.
-	#[serde(rename = "felt")]
+	#[serde(rename = "felt", default, deserialize_with = "lenient::u32")]
 	pub felt: Option<u32>,
 
 	/// Community Internet Intensity (perceived shaking).
-	#[serde(rename = "cdi")]
+	#[serde(rename = "cdi", default, deserialize_with = "lenient::f32")]
 	pub cdi: Option<f32>,
 
 	/// Modified Mercalli Intensity (measured shaking).
-	#[serde(rename = "mmi")]
+	#[serde(rename = "mmi", default, deserialize_with = "lenient::f32")]
 	pub mmi: Option<f32>,
 
 	/// Alert level (`green`, `yellow`, `orange`, `red`).
 	#[serde(rename = "alert")]
 	pub alert_level: Option<String>,
 
-	/// Status of the event (`reviewed`, `automatic`, etc.).
+	/// Status of the event (`reviewed`, `automatic`, `deleted`, etc.).
 	#[serde(rename = "status")]
 	pub status: Option<String>,
 
 	/// Whether the earthquake triggered a tsunami (0 = no, 1 = yes).
-	#[serde(rename = "tsunami")]
+	#[serde(rename = "tsunami", default, deserialize_with = "lenient::u8")]
 	pub tsunami: Option<u8>,
 
 	/// Significance index of the earthquake.
-	#[serde(rename = "sig")]
+	#[serde(rename = "sig", default, deserialize_with = "lenient::u32")]
 	pub sig: Option<u32>,
 
 	/// Network identifier.
 	#[serde(rename = "net")]
-	pub net: Option<String>,
+	pub net: Option<NetworkCode>,
 
-	/// Event code within the network.
+	/// Event code within the network (e.g. `7000abcd`). Kept as a string
+	/// since it is not an event id by itself: the id is `net` followed by
+	/// `code`, see [`EarthquakeFeatures::id`].
 	#[serde(rename = "code")]
 	pub code: Option<String>,
 
-	/// List of event IDs from multiple sources.
-	#[serde(rename = "ids")]
-	pub ids: Option<String>,
+	/// Ids of the event from every contributing network, sent by USGS as a
+	/// comma-separated list (`,us7000abcd,at00sew1ab,`).
+	#[serde(rename = "ids", default, with = "comma_separated")]
+	pub ids: Vec<EventId>,
 
 	/// Sources contributing to the event.
 	#[serde(rename = "sources")]
@@ -155,20 +420,20 @@ pub struct EarthquakeProperties {
 	pub types: Option<String>,
 
 	/// Number of seismic stations used.
-	#[serde(rename = "nst")]
+	#[serde(rename = "nst", default, deserialize_with = "lenient::u32")]
 	pub nst: Option<u32>,
 
 	/// Minimum distance to a station (degrees).
-	#[serde(rename = "dmin")]
+	#[serde(rename = "dmin", default, deserialize_with = "lenient::f64")]
 	pub dmin: Option<f64>,
 
 	/// Root-mean-square travel time residual.
-	#[serde(rename = "rms")]
+	#[serde(rename = "rms", default, deserialize_with = "lenient::f32")]
 	pub rms: Option<f32>,
 
 	/// Azimuthal gap between stations.
-	#[serde(rename = "gap")]
-	pub gap: Option<u32>,
+	#[serde(rename = "gap", default, deserialize_with = "lenient::f32")]
+	pub gap: Option<f32>,
 
 	/// Type of magnitude used (e.g., `"mb"`, `"ml"`).
 	#[serde(rename = "magType")]
@@ -181,12 +446,17 @@ pub struct EarthquakeProperties {
 	/// Title for the event (often a combination of magnitude + place).
 	#[serde(rename = "title")]
 	pub title: Option<String>,
+
+	/// Fields not modelled by this crate, preserved as-is.
+	#[cfg(feature = "extra-fields")]
+	#[serde(flatten)]
+	pub extra: Map<String, Value>,
 }
 
 /// Geometric data for an earthquake event.
 ///
 /// Contains coordinates and geometry type.
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct EarthquakeGeometry {
 	/// Geometry type (always `"Point"` for earthquakes).
 	#[serde(rename = "type")]
@@ -195,4 +465,738 @@ pub struct EarthquakeGeometry {
 	/// Coordinates in `[longitude, latitude, depth]` order.
 	#[serde(rename = "coordinates")]
 	pub coordinates: Vec<f32>,
-}
\ No newline at end of file
+}
+
+/// Mean Earth radius in kilometers, used for great-circle distances.
+pub(crate) const EARTH_RADIUS_KM: f64 = 6371.0088;
+
+const KM_PER_MILE: f64 = 1.609344;
+
+/// Great-circle distance in kilometers between two `(latitude, longitude)` points.
+pub(crate) fn haversine_km(from: (f64, f64), to: (f64, f64)) -> f64 {
+	let (lat1, lat2) = (from.0.to_radians(), to.0.to_radians());
+	let d_lat = lat2 - lat1;
+	let d_lon = (to.1 - from.1).to_radians();
+	let a = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.0).sin().powi(2);
+	2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+}
+
+/// Initial great-circle bearing in radians from `from` to `to`.
+pub(crate) fn initial_bearing(from: (f64, f64), to: (f64, f64)) -> f64 {
+	let (lat1, lat2) = (from.0.to_radians(), to.0.to_radians());
+	let d_lon = (to.1 - from.1).to_radians();
+	let y = d_lon.sin() * lat2.cos();
+	let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * d_lon.cos();
+	y.atan2(x)
+}
+
+/// Unit of length for depths and distances.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+	/// Kilometers
+	Kilometers,
+
+	/// Statute miles
+	Miles,
+}
+
+impl Unit {
+	/// Converts a value in kilometers into this unit.
+	pub fn from_km(self, km: f64) -> f64 {
+		match self {
+			Unit::Kilometers => km,
+			Unit::Miles => km / KM_PER_MILE,
+		}
+	}
+}
+
+/// Conventional depth class of a hypocenter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum DepthClass {
+	/// Shallower than 70 km
+	Shallow,
+
+	/// 70 to 300 km
+	Intermediate,
+
+	/// Deeper than 300 km
+	Deep,
+}
+
+impl DepthClass {
+	/// Class of a hypocenter at `depth_km`.
+	pub fn from_depth_km(depth_km: f64) -> Self {
+		if depth_km < 70.0 {
+			DepthClass::Shallow
+		} else if depth_km <= 300.0 {
+			DepthClass::Intermediate
+		} else {
+			DepthClass::Deep
+		}
+	}
+}
+
+impl std::fmt::Display for DepthClass {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str(match self {
+			DepthClass::Shallow => "shallow",
+			DepthClass::Intermediate => "intermediate",
+			DepthClass::Deep => "deep",
+		})
+	}
+}
+
+impl EarthquakeResponse {
+	/// Path of the first field preserved in an `extra` map, for
+	/// [`ParseMode::Strict`].
+	#[cfg(feature = "extra-fields")]
+	fn unknown_field(&self) -> Option<String> {
+		if let Some(key) = self.metadata.extra.keys().next() {
+			return Some(format!("metadata.{}", key));
+		}
+		self.features.iter().enumerate().find_map(|(i, feature)| {
+			feature.properties.extra.keys().next().map(|key| format!("features.{}.properties.{}", i, key))
+		})
+	}
+
+	/// Response without events, for services answering "no data" with an
+	/// empty body or `204 No Content`.
+	pub fn empty(url: Url) -> Self {
+		EarthquakeResponse {
+			data_type: "FeatureCollection".to_string(),
+			features: Vec::new(),
+			metadata: EarthquakeMetadata {
+				generated_timestamp: Utc::now(),
+				url,
+				title: String::new(),
+				status: StatusCode::OK,
+				api_version: String::new(),
+				count: 0,
+				#[cfg(feature = "extra-fields")]
+				extra: Default::default(),
+			},
+			bbox: None,
+		}
+	}
+
+	/// Reads a GeoJSON response, e.g. a previously saved archive, so it can
+	/// be filtered, analysed and exported offline.
+	pub fn from_reader(mut reader: impl std::io::Read) -> Result<Self, UsgsError> {
+		let mut json = String::new();
+		reader.read_to_string(&mut json)?;
+		parse_response(&json)
+	}
+
+	/// Loads a GeoJSON response saved at `path`.
+	///
+	/// ## Example
+	/// ```rust,no_run
+	/// use usgs_earthquake_api::EarthquakeResponse;
+	///
+	/// let response = EarthquakeResponse::from_path("archive/2024-01.geojson").unwrap();
+	/// println!("{} events", response.len());
+	/// ```
+	pub fn from_path(path: impl AsRef<std::path::Path>) -> Result<Self, UsgsError> {
+		Self::from_reader(std::fs::File::open(path)?)
+	}
+
+	/// Time the server generated this response, from `metadata.generated`.
+	pub fn generated_at(&self) -> DateTime<Utc> {
+		self.metadata.generated_timestamp
+	}
+
+	/// Time elapsed since the server generated this response.
+	///
+	/// Feeds and CDN caches may serve responses that are several minutes old.
+	pub fn age(&self) -> TimeDelta {
+		Utc::now() - self.generated_at()
+	}
+
+	/// Iterates over the events.
+	pub fn iter(&self) -> std::slice::Iter<'_, EarthquakeFeatures> {
+		self.features.iter()
+	}
+
+	/// Number of events.
+	pub fn len(&self) -> usize {
+		self.features.len()
+	}
+
+	/// Whether the response holds no events.
+	pub fn is_empty(&self) -> bool {
+		self.features.is_empty()
+	}
+
+	/// Splits the events into pages of `page_size` events for list views;
+	/// the last page may be shorter. A `page_size` of 0 is treated as 1.
+	///
+	/// ## Example
+	/// ```rust,no_run
+	/// use usgs_earthquake_api::EarthquakeResponse;
+	///
+	/// let response = EarthquakeResponse::from_path("archive/2024-01.geojson").unwrap();
+	/// for (number, page) in response.pages(25).enumerate() {
+	///     println!("page {} of {}: {} events", number + 1, response.page_count(25), page.len());
+	/// }
+	/// ```
+	pub fn pages(&self, page_size: usize) -> std::slice::Chunks<'_, EarthquakeFeatures> {
+		self.features.chunks(page_size.max(1))
+	}
+
+	/// Number of pages of `page_size` events, 0 for an empty response.
+	pub fn page_count(&self, page_size: usize) -> usize {
+		self.features.len().div_ceil(page_size.max(1))
+	}
+
+	/// Events of the zero-based page `index`, empty past the last page.
+	pub fn page(&self, index: usize, page_size: usize) -> &[EarthquakeFeatures] {
+		let page_size = page_size.max(1);
+		let start = index.saturating_mul(page_size).min(self.features.len());
+		let end = start.saturating_add(page_size).min(self.features.len());
+		&self.features[start..end]
+	}
+
+	/// Magnitudes of the events that have one.
+	pub fn magnitudes(&self) -> impl Iterator<Item = f64> + '_ {
+		self.features.iter().filter_map(|feature| feature.properties.magnitude)
+	}
+
+	/// Epicenters of the events as `(latitude, longitude)` in degrees.
+	pub fn epicenters(&self) -> impl Iterator<Item = (f64, f64)> + '_ {
+		self.features.iter().map(|feature| (feature.geometry.latitude(), feature.geometry.longitude()))
+	}
+
+	/// Event with the largest magnitude.
+	pub fn strongest(&self) -> Option<&EarthquakeFeatures> {
+		self.features.iter()
+			.filter(|feature| feature.properties.magnitude.is_some())
+			.max_by(|a, b| a.properties.magnitude.partial_cmp(&b.properties.magnitude).unwrap_or(std::cmp::Ordering::Equal))
+	}
+
+	/// Event with the earliest origin time.
+	pub fn earliest(&self) -> Option<&EarthquakeFeatures> {
+		self.features.iter().filter(|feature| feature.properties.time.is_some()).min_by_key(|feature| feature.properties.time)
+	}
+
+	/// Event with the latest origin time.
+	pub fn latest(&self) -> Option<&EarthquakeFeatures> {
+		self.features.iter().filter(|feature| feature.properties.time.is_some()).max_by_key(|feature| feature.properties.time)
+	}
+
+	/// The `n` events with the largest magnitude, strongest first.
+	///
+	/// Events without a magnitude are skipped.
+	pub fn top_by_magnitude(&self, n: usize) -> Vec<&EarthquakeFeatures> {
+		top_n(&self.features, n, |feature| feature.properties.magnitude)
+	}
+
+	/// The `n` events with the highest significance index, most significant first.
+	///
+	/// Events without a significance index are skipped.
+	pub fn top_by_significance(&self, n: usize) -> Vec<&EarthquakeFeatures> {
+		top_n(&self.features, n, |feature| feature.properties.sig.map(f64::from))
+	}
+
+	/// Sorts the events into magnitude bands between `edges`, e.g.
+	/// `&[2.0, 4.0, 6.0]` gives the bands `M2–4`, `M4–6` and `M6+`.
+	///
+	/// Bands include their lower edge. A leading `M<2` band collects the
+	/// events below the first edge and is only listed when there are any.
+	/// Events without a magnitude are skipped.
+	///
+	/// ## Example
+	/// ```rust,no_run
+	/// use usgs_earthquake_api::EarthquakeResponse;
+	///
+	/// let response = EarthquakeResponse::from_path("archive/2024-01.geojson").unwrap();
+	/// for band in response.bucket_by_magnitude(&[2.0, 4.0, 6.0]) {
+	///     println!("{}: {}", band.label, band.len());
+	/// }
+	/// ```
+	pub fn bucket_by_magnitude(&self, edges: &[f64]) -> Vec<MagnitudeBucket<'_>> {
+		let mut edges: Vec<f64> = edges.iter().copied().filter(|edge| edge.is_finite()).collect();
+		edges.sort_by(f64::total_cmp);
+		edges.dedup();
+		let Some(&first) = edges.first() else {
+			return Vec::new();
+		};
+
+		let mut buckets = vec![MagnitudeBucket { label: format!("M<{}", first), min: None, max: Some(first), events: Vec::new() }];
+		for (index, &min) in edges.iter().enumerate() {
+			let max = edges.get(index + 1).copied();
+			let label = match max {
+				Some(max) => format!("M{}–{}", min, max),
+				None => format!("M{}+", min),
+			};
+			buckets.push(MagnitudeBucket { label, min: Some(min), max, events: Vec::new() });
+		}
+		for feature in &self.features {
+			if let Some(magnitude) = feature.properties.magnitude.filter(|magnitude| !magnitude.is_nan()) {
+				// Number of edges at or below the magnitude is the index of its band.
+				let band = edges.partition_point(|edge| *edge <= magnitude);
+				buckets[band].events.push(feature);
+			}
+		}
+		if buckets[0].events.is_empty() {
+			buckets.remove(0);
+		}
+		buckets
+	}
+
+	/// Keeps only events reported felt by at least `n` people through "Did
+	/// You Feel It?", the client-side counterpart of
+	/// [`UsgsQuery::min_felt`](crate::UsgsQuery::min_felt).
+	pub fn filter_felt_at_least(&mut self, n: u32) {
+		self.retain_features(|feature| feature.properties.felt.is_some_and(|felt| felt >= n));
+	}
+
+	/// Keeps only events of the given depth class.
+	pub fn filter_by_depth_class(&mut self, class: DepthClass) {
+		self.retain_features(|feature| feature.geometry.depth_class() == class);
+	}
+
+	/// Keeps only events whose maximum reported intensity (CDI) lies in `range`,
+	/// e.g. `4.0..=6.0` or `5.0..`. Events without a CDI are dropped.
+	pub fn filter_cdi_range(&mut self, range: impl std::ops::RangeBounds<f32>) {
+		self.retain_features(|feature| feature.properties.cdi.is_some_and(|cdi| range.contains(&cdi)));
+	}
+
+	/// Merges solutions of the same earthquake reported by several networks
+	/// into one event, for cleaner public-facing feeds.
+	///
+	/// Events within `time_window` and `distance_km` of the first event of
+	/// a group join that group. Of each group the event with the most
+	/// associated ids (the preferred solution lists the others), then the
+	/// highest significance, is kept, and the ids of the others are added
+	/// to its `ids`, so [`EarthquakeFeatures::is_known_as`] still finds them.
+	/// Events without an origin time are kept as they are.
+	pub fn collapse_duplicates(&mut self, time_window: TimeDelta, distance_km: f64) {
+		let window_ms = time_window.num_milliseconds().unsigned_abs();
+		let mut order: Vec<(u64, usize)> = self.features.iter().enumerate()
+			.filter_map(|(index, feature)| Some((feature.properties.time?, index)))
+			.collect();
+		order.sort_unstable();
+
+		// Groups are opened in time order, so those whose first event is
+		// out of the window of the current one sit at the front.
+		let mut groups: Vec<Vec<usize>> = Vec::new();
+		let mut open: std::collections::VecDeque<(u64, usize)> = std::collections::VecDeque::new();
+		for (time, index) in order {
+			while open.front().is_some_and(|(first, _)| time - first > window_ms) {
+				open.pop_front();
+			}
+			let epicenter = |index: usize| (self.features[index].geometry.latitude(), self.features[index].geometry.longitude());
+			let joined = open.iter()
+				.find(|(_, group)| haversine_km(epicenter(groups[*group][0]), epicenter(index)) <= distance_km)
+				.map(|(_, group)| *group);
+			match joined {
+				Some(group) => groups[group].push(index),
+				None => {
+					open.push_back((time, groups.len()));
+					groups.push(vec![index]);
+				}
+			}
+		}
+
+		let mut removed = vec![false; self.features.len()];
+		for group in groups.into_iter().filter(|group| group.len() > 1) {
+			let representative = *group.iter()
+				.max_by_key(|index| {
+					let feature = &self.features[**index];
+					(feature.associated_ids().len(), feature.properties.sig, std::cmp::Reverse(**index))
+				})
+				.expect("group is not empty");
+			let mut ids = self.features[representative].associated_ids();
+			for &index in &group {
+				for id in self.features[index].associated_ids() {
+					if !ids.contains(&id) {
+						ids.push(id);
+					}
+				}
+				removed[index] = index != representative;
+			}
+			self.features[representative].properties.ids = ids;
+		}
+
+		let mut removed = removed.into_iter();
+		self.retain_features(|_| !removed.next().expect("one flag per feature"));
+	}
+
+	fn retain_features(&mut self, keep: impl FnMut(&EarthquakeFeatures) -> bool) {
+		self.features.retain(keep);
+		self.metadata.count = self.features.len() as u32;
+		self.recompute_bbox();
+	}
+
+	/// Recomputes `bbox` from the current features, in the USGS
+	/// `[min_lon, min_lat, min_depth, max_lon, max_lat, max_depth]` layout.
+	///
+	/// Sets `bbox` to `None` when there are no features. Called automatically
+	/// after the built-in client-side filters.
+	pub fn recompute_bbox(&mut self) {
+		let mut points = self.features.iter().map(|feature| &feature.geometry.coordinates).filter(|c| c.len() >= 3);
+		self.bbox = points.next().map(|first| {
+			let mut bbox = vec![first[0], first[1], first[2], first[0], first[1], first[2]];
+			for coordinates in points {
+				for axis in 0..3 {
+					bbox[axis] = bbox[axis].min(coordinates[axis]);
+					bbox[axis + 3] = bbox[axis + 3].max(coordinates[axis]);
+				}
+			}
+			bbox
+		});
+	}
+}
+
+/// Magnitude band of [`EarthquakeResponse::bucket_by_magnitude`].
+#[derive(Debug, Clone)]
+pub struct MagnitudeBucket<'a> {
+	/// Display label, e.g. `M4–6`, `M6+` or `M<2`.
+	pub label: String,
+
+	/// Lower edge (inclusive), `None` for the band below the first edge.
+	pub min: Option<f64>,
+
+	/// Upper edge (exclusive), `None` for the last band.
+	pub max: Option<f64>,
+
+	/// Events of the band, in response order.
+	pub events: Vec<&'a EarthquakeFeatures>,
+}
+
+impl MagnitudeBucket<'_> {
+	/// Number of events in the band.
+	pub fn len(&self) -> usize {
+		self.events.len()
+	}
+
+	/// Whether the band holds no events.
+	pub fn is_empty(&self) -> bool {
+		self.events.is_empty()
+	}
+}
+
+/// The `n` features with the largest `key`, descending, by partial selection
+/// rather than a full sort.
+fn top_n(features: &[EarthquakeFeatures], n: usize, key: impl Fn(&EarthquakeFeatures) -> Option<f64>) -> Vec<&EarthquakeFeatures> {
+	if n == 0 {
+		return Vec::new();
+	}
+	let mut ranked: Vec<(f64, &EarthquakeFeatures)> = features.iter()
+		.filter_map(|feature| key(feature).map(|value| (value, feature)))
+		.collect();
+	let descending = |a: &(f64, &EarthquakeFeatures), b: &(f64, &EarthquakeFeatures)| b.0.total_cmp(&a.0);
+	if n < ranked.len() {
+		ranked.select_nth_unstable_by(n - 1, descending);
+		ranked.truncate(n);
+	}
+	ranked.sort_by(descending);
+	ranked.into_iter().map(|(_, feature)| feature).collect()
+}
+
+impl IntoIterator for EarthquakeResponse {
+	type Item = EarthquakeFeatures;
+	type IntoIter = std::vec::IntoIter<EarthquakeFeatures>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.features.into_iter()
+	}
+}
+
+impl<'a> IntoIterator for &'a EarthquakeResponse {
+	type Item = &'a EarthquakeFeatures;
+	type IntoIter = std::slice::Iter<'a, EarthquakeFeatures>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.features.iter()
+	}
+}
+
+/// Condensed view of an event for UI lists, see [`EarthquakeFeatures::summary`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct EventSummary {
+	/// Unique identifier of the event.
+	pub id: EventId,
+
+	/// Magnitude, if known.
+	pub magnitude: Option<f64>,
+
+	/// Location description (e.g., `"12 km NE of Town, Country"`).
+	pub place: Option<String>,
+
+	/// Origin time, if known.
+	pub time: Option<DateTime<Utc>>,
+
+	/// Depth of the hypocenter in kilometers.
+	pub depth_km: f64,
+
+	/// PAGER alert level, if any.
+	pub alert: Option<AlertLevel>,
+
+	/// Event detail page.
+	pub url: Option<String>,
+}
+
+impl EarthquakeFeatures {
+	/// PAGER alert level of the event, if any.
+	pub fn alert(&self) -> Option<AlertLevel> {
+		self.properties.alert_level.as_deref()?.parse().ok()
+	}
+
+	/// Whether the event was withdrawn (`status` is `deleted`), as returned
+	/// by queries with [`UsgsQuery::include_deleted`](crate::UsgsQuery::include_deleted).
+	pub fn is_deleted(&self) -> bool {
+		self.properties.status.as_deref() == Some("deleted")
+	}
+
+	/// Ids of every network solution associated with this event, from the
+	/// `ids` property (falling back to `id`).
+	pub fn associated_ids(&self) -> Vec<EventId> {
+		if self.properties.ids.is_empty() { vec![self.id.clone()] } else { self.properties.ids.clone() }
+	}
+
+	/// Whether `event_id` is this event or one associated with it.
+	pub fn is_known_as(&self, event_id: &str) -> bool {
+		self.id == event_id || self.associated_ids().iter().any(|id| *id == event_id)
+	}
+
+	/// Origin time of the event, if known.
+	pub fn origin_time(&self) -> Option<DateTime<Utc>> {
+		DateTime::from_timestamp_millis(self.properties.time? as i64)
+	}
+
+	/// Origin time in the local time of the epicenter, i.e. what the clocks
+	/// showed there.
+	///
+	/// With the `timezone-lookup` feature the time zone is looked up by
+	/// coordinates, honoring daylight saving time. Otherwise, or where the
+	/// lookup finds nothing, the `tz` offset of the event is used, which
+	/// USGS no longer fills in for recent events.
+	///
+	/// ## Example
+	/// ```rust,no_run
+	/// use usgs_earthquake_api::UsgsClient;
+	///
+	/// #[tokio::main]
+	/// async fn main() {
+	///     let event = UsgsClient::new().event("us6000jllz").await.unwrap();
+	///     if let Some(local) = event.local_time() {
+	///         println!("{} local time", local.format("%Y-%m-%d %H:%M %:z"));
+	///     }
+	/// }
+	/// ```
+	pub fn local_time(&self) -> Option<DateTime<FixedOffset>> {
+		let origin = self.origin_time()?;
+		#[cfg(feature = "timezone-lookup")]
+		if let Some(timezone) = self.timezone() {
+			return Some(origin.with_timezone(&timezone).fixed_offset());
+		}
+		let offset = FixedOffset::east_opt(self.properties.tz?.checked_mul(60)?)?;
+		Some(origin.with_timezone(&offset))
+	}
+
+	/// Condensed copy of the fields UI lists usually show.
+	pub fn summary(&self) -> EventSummary {
+		EventSummary {
+			id: self.id.clone(),
+			magnitude: self.properties.magnitude,
+			place: self.properties.place.clone(),
+			time: self.origin_time(),
+			depth_km: self.geometry.depth_km(),
+			alert: self.alert(),
+			url: self.properties.url.clone(),
+		}
+	}
+}
+
+/// Canonical one-line description,
+/// `M 5.4 - 12 km NE of Town, Country (2024-02-06 01:17 UTC, depth 10 km)`.
+impl std::fmt::Display for EarthquakeFeatures {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self.properties.magnitude {
+			Some(magnitude) => write!(f, "M {:.1}", magnitude)?,
+			None => write!(f, "M ?")?,
+		}
+		write!(f, " - {} (", self.properties.place.as_deref().unwrap_or("Unknown location"))?;
+		if let Some(time) = self.origin_time() {
+			write!(f, "{}, ", time.format("%Y-%m-%d %H:%M UTC"))?;
+		}
+		write!(f, "depth {:.0} km)", self.geometry.depth_km())
+	}
+}
+
+impl EarthquakeGeometry {
+	/// Longitude of the epicenter in degrees.
+	pub fn longitude(&self) -> f64 {
+		self.coordinates.first().copied().unwrap_or_default() as f64
+	}
+
+	/// Latitude of the epicenter in degrees.
+	pub fn latitude(&self) -> f64 {
+		self.coordinates.get(1).copied().unwrap_or_default() as f64
+	}
+
+	/// Depth of the hypocenter in kilometers.
+	pub fn depth_km(&self) -> f64 {
+		self.coordinates.get(2).copied().unwrap_or_default() as f64
+	}
+
+	/// Depth of the hypocenter in miles.
+	pub fn depth_miles(&self) -> f64 {
+		Unit::Miles.from_km(self.depth_km())
+	}
+
+	/// Depth of the hypocenter in the given unit.
+	pub fn depth(&self, unit: Unit) -> f64 {
+		unit.from_km(self.depth_km())
+	}
+
+	/// Depth class of the hypocenter.
+	pub fn depth_class(&self) -> DepthClass {
+		DepthClass::from_depth_km(self.depth_km())
+	}
+
+	/// Great-circle (haversine) distance from the epicenter to a point.
+	pub fn distance_to(&self, latitude: f64, longitude: f64, unit: Unit) -> f64 {
+		unit.from_km(haversine_km((self.latitude(), self.longitude()), (latitude, longitude)))
+	}
+
+	/// Straight-line (hypocentral) distance in kilometers from the hypocenter
+	/// to a site at `elevation_m` meters above sea level.
+	///
+	/// Unlike [`EarthquakeGeometry::distance_to`], which measures along the
+	/// surface from the epicenter, this accounts for the depth: an event 10 km
+	/// deep right below a site at sea level is 10 km away.
+	pub fn distance_3d_km(&self, latitude: f64, longitude: f64, elevation_m: f64) -> f64 {
+		let angle = haversine_km((self.latitude(), self.longitude()), (latitude, longitude)) / EARTH_RADIUS_KM;
+		let hypocenter = EARTH_RADIUS_KM - self.depth_km();
+		let site = EARTH_RADIUS_KM + elevation_m / 1000.0;
+		(hypocenter * hypocenter + site * site - 2.0 * hypocenter * site * angle.cos()).max(0.0).sqrt()
+	}
+
+	/// Well-Known Text of the hypocenter, `POINT Z (lon lat -depth)` with
+	/// the depth in kilometers as a negative height.
+	pub fn to_wkt(&self) -> String {
+		let [longitude, latitude, depth] = [0, 1, 2].map(|i| self.coordinates.get(i).copied().unwrap_or_default());
+		// `0.0 - depth` rather than `-depth` so a zero depth is not written as `-0`.
+		format!("POINT Z ({} {} {})", longitude, latitude, 0.0 - depth)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json::{json, Value};
+	use super::*;
+	use crate::tests::tests::{feature, fixture, response, QUERY_URL};
+
+	/// The `us7000mz4k` event of the `all_hour` fixture, which has none of
+	/// the numeric quirks, with `properties` replacing its own.
+	fn with_properties(properties: Value) -> String {
+		let mut body: Value = serde_json::from_str(&fixture("all_hour.geojson")).unwrap();
+		let mut event = body["features"][1].take();
+		event["properties"].as_object_mut().unwrap().extend(properties.as_object().unwrap().clone());
+		body["features"] = json!([event]);
+		body["metadata"]["count"] = json!(1);
+		body.to_string()
+	}
+
+	fn properties(properties: Value, mode: ParseMode) -> Result<EarthquakeProperties, UsgsError> {
+		parse_response_with(&with_properties(properties), mode).map(|mut response| response.features.remove(0).properties)
+	}
+
+	#[test]
+	fn lenient_accepts_usgs_numeric_quirks() {
+		let response = parse_response(&fixture("all_hour.geojson")).unwrap();
+		assert_eq!(response.metadata.count, 4);
+		assert_eq!(response.features.len(), 4);
+
+		let geysers = &response.features[0].properties;
+		assert_eq!(geysers.magnitude, Some(-0.3));
+		assert_eq!(geysers.felt, None);
+		assert_eq!(geysers.nst, Some(12));
+		assert_eq!(geysers.gap, Some(86.5));
+		assert_eq!(geysers.tz, None);
+		assert_eq!(geysers.time, Some(1718027452190));
+
+		let japan = &response.features[1].properties;
+		assert_eq!(japan.felt, Some(3));
+		assert_eq!(japan.gap, Some(120.25));
+		assert_eq!(japan.sig, Some(314));
+
+		let alaska = &response.features[2].properties;
+		assert_eq!((alaska.nst, alaska.gap, alaska.dmin), (None, None, None));
+
+		assert!(matches!(parse_response_with(&fixture("all_hour.geojson"), ParseMode::Strict), Err(UsgsError::Parse { path, .. }) if path == "features[0].properties.nst"));
+	}
+
+	#[test]
+	fn lenient_coerces_numeric_strings() {
+		let properties = properties(json!({ "mag": "4.5", "felt": " 3 ", "nst": "", "gap": "120.25" }), ParseMode::Lenient).unwrap();
+		assert_eq!(properties.magnitude, Some(4.5));
+		assert_eq!(properties.felt, Some(3));
+		assert_eq!(properties.nst, None);
+		assert_eq!(properties.gap, Some(120.25));
+	}
+
+	#[test]
+	fn lenient_rejects_non_numeric_strings() {
+		let err = properties(json!({ "mag": "strong" }), ParseMode::Lenient).unwrap_err();
+		assert!(matches!(&err, UsgsError::Parse { path, .. } if path == "features[0].properties.mag"), "{:?}", err);
+	}
+
+	#[test]
+	fn strict_skips_coercions() {
+		assert!(properties(json!({ "mag": -0.3, "felt": null, "nst": 12, "gap": 86.5 }), ParseMode::Strict).is_ok());
+		for quirk in [json!({ "nst": 12.0 }), json!({ "mag": "4.5" }), json!({ "tsunami": true }), json!({ "felt": -1 })] {
+			let err = properties(quirk.clone(), ParseMode::Strict).unwrap_err();
+			assert!(matches!(err, UsgsError::Parse { .. }), "{}: {:?}", quirk, err);
+		}
+	}
+
+	#[test]
+	fn ids_are_parsed_into_event_ids() {
+		let response = parse_response(&fixture("all_hour.geojson")).unwrap();
+		let japan = &response.features[1];
+		assert_eq!(japan.properties.ids, ["at00sew1ab", "us7000mz4k"]);
+		assert_eq!(japan.properties.code.as_deref(), Some("7000mz4k"));
+
+		let json = serde_json::to_value(japan).unwrap();
+		assert_eq!(json["properties"]["ids"], ",at00sew1ab,us7000mz4k,");
+		let empty = serde_json::to_value(EarthquakeProperties::default()).unwrap();
+		assert_eq!(empty["ids"], Value::Null);
+		assert!(properties(json!({ "ids": null }), ParseMode::Strict).unwrap().ids.is_empty());
+	}
+
+	/// `(id, ids, latitude, longitude, time, sig)` of a test event.
+	type Event<'a> = (&'a str, &'a str, f64, f64, Option<u64>, u32);
+
+	fn catalog(events: &[Event<'_>]) -> EarthquakeResponse {
+		let features = events.iter()
+			.map(|&(id, ids, latitude, longitude, time, sig)| feature(id, [longitude, latitude, 10.0], json!({ "mag": 5.0, "time": time, "sig": sig, "ids": ids, "type": "earthquake" })))
+			.collect();
+		response(QUERY_URL, features)
+	}
+
+	#[test]
+	fn collapse_duplicates_merges_network_solutions() {
+		let time = 1718027452190;
+		let mut response = catalog(&[
+			("pt24162000", ",pt24162000,", 38.05, 142.55, Some(time + 20_000), 1700),
+			("us7000mz4k", ",at00sew1ab,us7000mz4k,", 38.1, 142.4, Some(time), 1650),
+			("nc75012345", ",nc75012345,", 38.8, -122.8, Some(time + 5_000), 20),
+			("us7000mz5q", ",us7000mz5q,", 38.1, 142.4, Some(time + 600_000), 400),
+			("us7000mz9x", ",us7000mz9x,", 38.1, 142.4, None, 300),
+		]);
+		response.collapse_duplicates(TimeDelta::minutes(1), 50.0);
+
+		let ids: Vec<&str> = response.features.iter().map(|feature| feature.id.as_str()).collect();
+		assert_eq!(ids, ["us7000mz4k", "nc75012345", "us7000mz5q", "us7000mz9x"]);
+		assert_eq!(response.metadata.count, 4);
+
+		let merged = &response.features[0];
+		assert_eq!(merged.properties.ids, ["at00sew1ab", "us7000mz4k", "pt24162000"]);
+		assert!(merged.is_known_as("pt24162000"));
+		assert_eq!(merged.properties.sig, Some(1650));
+		assert_eq!(response.features[1].properties.ids, ["nc75012345"]);
+	}
+}
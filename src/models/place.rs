@@ -0,0 +1,114 @@
+use std::fmt;
+use std::str::FromStr;
+use crate::models::models::EarthquakeFeatures;
+
+/// 16-point compass directions, clockwise from north.
+const COMPASS_POINTS: [&str; 16] = ["N", "NNE", "NE", "ENE", "E", "ESE", "SE", "SSE", "S", "SSW", "SW", "WSW", "W", "WNW", "NW", "NNW"];
+
+/// Structured form of a USGS `place` string such as
+/// `"10 km NE of Town, Region"`.
+///
+/// Places without a relative position (e.g. `"south of the Fiji Islands"`
+/// or `"Central Mid-Atlantic Ridge"`) only have a `locality`.
+///
+/// ## Example
+/// ```rust
+/// use usgs_earthquake_api::Place;
+///
+/// let place: Place = "12 km NE of Pazarcık, Turkey".parse().unwrap();
+/// assert_eq!(place.distance_km, Some(12.0));
+/// assert_eq!(place.bearing.as_deref(), Some("NE"));
+/// assert_eq!(place.locality, "Pazarcık");
+/// assert_eq!(place.region.as_deref(), Some("Turkey"));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Place {
+	/// Distance from the locality in kilometers.
+	pub distance_km: Option<f64>,
+
+	/// Compass direction from the locality (e.g. `"NE"`, `"SSW"`).
+	pub bearing: Option<String>,
+
+	/// Nearby locality or named region.
+	pub locality: String,
+
+	/// State, country or region after the last comma, if any.
+	pub region: Option<String>,
+}
+
+impl Place {
+	/// Bearing from the locality in degrees clockwise from north.
+	pub fn bearing_degrees(&self) -> Option<f64> {
+		let bearing = self.bearing.as_deref()?;
+		COMPASS_POINTS.iter().position(|point| *point == bearing).map(|i| i as f64 * 22.5)
+	}
+}
+
+/// Splits `"12 km NE of Town"` into distance, bearing and locality.
+fn relative_position(text: &str) -> Option<(f64, String, &str)> {
+	let (position, locality) = text.split_once(" of ")?;
+	let mut words = position.split_whitespace();
+	let first = words.next()?;
+	let (distance, unit, bearing) = match first.strip_suffix("km") {
+		Some(distance) if !distance.is_empty() => (distance, "km", words.next()?),
+		_ => (first, words.next()?, words.next()?),
+	};
+	if words.next().is_some() || !COMPASS_POINTS.contains(&bearing) {
+		return None;
+	}
+	let distance: f64 = distance.parse().ok()?;
+	let distance_km = match unit {
+		"km" => distance,
+		"mi" => distance * 1.609344,
+		_ => return None,
+	};
+	Some((distance_km, bearing.to_string(), locality.trim()))
+}
+
+impl FromStr for Place {
+	type Err = std::convert::Infallible;
+
+	fn from_str(place: &str) -> Result<Self, Self::Err> {
+		let place = place.trim();
+		let (rest, region) = match place.rsplit_once(", ") {
+			Some((rest, region)) => (rest, Some(region.trim().to_string())),
+			None => (place, None),
+		};
+		Ok(match relative_position(rest) {
+			Some((distance_km, bearing, locality)) => Place {
+				distance_km: Some(distance_km),
+				bearing: Some(bearing),
+				locality: locality.to_string(),
+				region,
+			},
+			None => Place {
+				distance_km: None,
+				bearing: None,
+				locality: rest.trim().to_string(),
+				region,
+			},
+		})
+	}
+}
+
+impl fmt::Display for Place {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if let (Some(distance), Some(bearing)) = (self.distance_km, &self.bearing) {
+			write!(f, "{} km {} of ", distance, bearing)?;
+		}
+		write!(f, "{}", self.locality)?;
+		if let Some(region) = &self.region {
+			write!(f, ", {}", region)?;
+		}
+		Ok(())
+	}
+}
+
+impl EarthquakeFeatures {
+	/// The `place` property split into distance, bearing, locality and region.
+	pub fn parsed_place(&self) -> Option<Place> {
+		self.properties.place.as_deref()
+			.filter(|place| !place.trim().is_empty())
+			.and_then(|place| place.parse().ok())
+	}
+}
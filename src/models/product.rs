@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::error::error::UsgsError;
+use crate::models::models::EarthquakeFeatures;
+use crate::UsgsClient;
+
+/// Product attached to an event in its detail document, e.g. a ShakeMap,
+/// a moment tensor or a PAGER estimate.
+///
+/// Products of a type are listed preferred first.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Product {
+	/// Product type (e.g. `shakemap`).
+	#[serde(rename = "type")]
+	pub product_type: String,
+
+	/// Network that contributed the product.
+	pub source: String,
+
+	/// Product code, usually the event id of the contributing network.
+	pub code: String,
+
+	/// `UPDATE`, or `DELETE` for withdrawn products.
+	pub status: String,
+
+	/// Last update in milliseconds since the epoch.
+	pub update_time: Option<u64>,
+
+	/// Product-specific properties, all as strings.
+	#[serde(default)]
+	pub properties: HashMap<String, String>,
+
+	/// Files of the product keyed by path (e.g. `download/grid.xml`).
+	#[serde(default)]
+	pub contents: HashMap<String, ProductContent>,
+}
+
+/// File of a [`Product`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProductContent {
+	/// MIME type of the file.
+	pub content_type: Option<String>,
+
+	/// Size in bytes.
+	pub length: Option<u64>,
+
+	/// Download URL.
+	pub url: String,
+}
+
+impl Product {
+	/// Download URL of the file at `path`, if the product has one.
+	pub fn content_url(&self, path: &str) -> Option<&str> {
+		self.contents.get(path).map(|content| content.url.as_str())
+	}
+}
+
+impl EarthquakeFeatures {
+	/// Event page on the USGS website, for opening in a browser.
+	pub fn open_url(&self) -> Option<&str> {
+		self.properties.url.as_deref()
+	}
+
+	/// GeoJSON detail document of the event, listing its products.
+	pub fn detail_url(&self) -> Option<&str> {
+		self.properties.detail.as_deref()
+	}
+
+	/// Fetches the detail document of the event with `client`, from
+	/// `detail` or, for events without one, by id.
+	///
+	/// Returned as JSON; see [`EarthquakeFeatures::fetch_shakemap`] for a
+	/// typed product.
+	///
+	/// ## Example
+	/// ```rust,no_run
+	/// use usgs_earthquake_api::UsgsClient;
+	///
+	/// #[tokio::main]
+	/// async fn main() {
+	///     let client = UsgsClient::new();
+	///     let response = client.query().min_magnitude(6.0).fetch().await.unwrap();
+	///     if let Some(event) = response.strongest() {
+	///         let detail = event.fetch_detail(&client).await.unwrap();
+	///         println!("{}", detail["properties"]["products"]);
+	///     }
+	/// }
+	/// ```
+	pub async fn fetch_detail(&self, client: &UsgsClient) -> Result<Value, UsgsError> {
+		let url = match self.detail_url() {
+			Some(url) => url.to_string(),
+			None => client.event_url(&self.id, &[])?,
+		};
+		match client.fetch_text(&url).await {
+			Err(UsgsError::Api { status: reqwest::StatusCode::NOT_FOUND, .. }) => Err(UsgsError::EventNotFound(self.id.clone())),
+			result => Ok(serde_json::from_str(&result?)?),
+		}
+	}
+
+	/// Fetches the preferred ShakeMap of the event, or `None` if it has none.
+	pub async fn fetch_shakemap(&self, client: &UsgsClient) -> Result<Option<Product>, UsgsError> {
+		let detail = self.fetch_detail(client).await?;
+		match detail.pointer("/properties/products/shakemap/0") {
+			Some(product) => Ok(Some(serde_json::from_value(product.clone())?)),
+			None => Ok(None),
+		}
+	}
+}
@@ -0,0 +1,13 @@
+/// How strictly GeoJSON responses are checked while parsing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ParseMode {
+	/// Tolerates unknown fields and coerces odd numeric values.
+	#[default]
+	Lenient,
+
+	/// Rejects fields not modelled by this crate and numeric values the
+	/// lenient mode would coerce (strings, booleans, floats for integer
+	/// fields, out-of-range values), e.g. in CI to catch USGS schema drift
+	/// early.
+	Strict,
+}
@@ -0,0 +1,119 @@
+use serde::Deserialize;
+use serde_json::Value;
+use crate::error::error::UsgsError;
+use crate::models::models::EarthquakeFeatures;
+use crate::models::product::Product;
+use crate::UsgsClient;
+
+/// Path of the intensity contours within a ShakeMap product.
+pub const MMI_CONTOURS: &str = "download/cont_mi.json";
+
+/// Contour of equal shaking intensity from a ShakeMap.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IntensityContour {
+	/// Modified Mercalli Intensity along the contour.
+	pub mmi: f64,
+
+	/// Display color suggested by ShakeMap (e.g. `#7af8ff`).
+	pub color: Option<String>,
+
+	/// Contour lines as `(latitude, longitude)` vertices; polygon rings are
+	/// closed, with the last vertex repeating the first.
+	pub lines: Vec<Vec<(f64, f64)>>,
+}
+
+#[derive(Deserialize)]
+struct ContourCollection {
+	features: Vec<ContourFeature>,
+}
+
+#[derive(Deserialize)]
+struct ContourFeature {
+	properties: ContourProperties,
+	geometry: Value,
+}
+
+#[derive(Deserialize)]
+struct ContourProperties {
+	value: f64,
+	color: Option<String>,
+}
+
+/// Vertex lists of a GeoJSON line or polygon geometry of any nesting.
+fn lines_of(geometry: &Value) -> Result<Vec<Vec<(f64, f64)>>, UsgsError> {
+	let depth = match geometry["type"].as_str() {
+		Some("LineString") => 1,
+		Some("MultiLineString" | "Polygon") => 2,
+		Some("MultiPolygon") => 3,
+		other => return Err(UsgsError::Schema(format!("unexpected contour geometry {:?}", other))),
+	};
+	let mut lines = Vec::new();
+	collect_lines(&geometry["coordinates"], depth, &mut lines)?;
+	Ok(lines)
+}
+
+fn collect_lines(coordinates: &Value, depth: usize, lines: &mut Vec<Vec<(f64, f64)>>) -> Result<(), UsgsError> {
+	let items = coordinates.as_array().ok_or_else(|| UsgsError::Schema("contour coordinates are not an array".to_string()))?;
+	if depth > 1 {
+		return items.iter().try_for_each(|item| collect_lines(item, depth - 1, lines));
+	}
+	let line = items.iter()
+		.map(|point| match (point[1].as_f64(), point[0].as_f64()) {
+			(Some(latitude), Some(longitude)) => Ok((latitude, longitude)),
+			_ => Err(UsgsError::Schema(format!("invalid contour vertex {}", point))),
+		})
+		.collect::<Result<_, _>>()?;
+	lines.push(line);
+	Ok(())
+}
+
+/// Parses a ShakeMap `cont_mi.json` document into intensity contours,
+/// ordered from weakest to strongest shaking.
+pub fn parse_intensity_contours(json: &str) -> Result<Vec<IntensityContour>, UsgsError> {
+	let collection: ContourCollection = serde_json::from_str(json)?;
+	let mut contours = collection.features.into_iter()
+		.map(|feature| Ok(IntensityContour {
+			mmi: feature.properties.value,
+			color: feature.properties.color,
+			lines: lines_of(&feature.geometry)?,
+		}))
+		.collect::<Result<Vec<_>, UsgsError>>()?;
+	contours.sort_by(|a, b| a.mmi.total_cmp(&b.mmi));
+	Ok(contours)
+}
+
+impl Product {
+	/// Fetches the intensity contours of this ShakeMap, or `None` if the
+	/// product has no `cont_mi.json`.
+	pub async fn fetch_intensity_contours(&self, client: &UsgsClient) -> Result<Option<Vec<IntensityContour>>, UsgsError> {
+		match self.content_url(MMI_CONTOURS) {
+			Some(url) => parse_intensity_contours(&client.fetch_text(url).await?).map(Some),
+			None => Ok(None),
+		}
+	}
+}
+
+impl EarthquakeFeatures {
+	/// Fetches the intensity contours of the preferred ShakeMap of the
+	/// event, or `None` without a ShakeMap or contours.
+	///
+	/// ## Example
+	/// ```rust,no_run
+	/// use usgs_earthquake_api::UsgsClient;
+	///
+	/// #[tokio::main]
+	/// async fn main() {
+	///     let client = UsgsClient::new();
+	///     let event = client.event("us6000jllz").await.unwrap();
+	///     for contour in event.fetch_intensity_contours(&client).await.unwrap().unwrap_or_default() {
+	///         println!("MMI {} in {} lines", contour.mmi, contour.lines.len());
+	///     }
+	/// }
+	/// ```
+	pub async fn fetch_intensity_contours(&self, client: &UsgsClient) -> Result<Option<Vec<IntensityContour>>, UsgsError> {
+		match self.fetch_shakemap(client).await? {
+			Some(shakemap) => shakemap.fetch_intensity_contours(client).await,
+			None => Ok(None),
+		}
+	}
+}
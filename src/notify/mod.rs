@@ -0,0 +1,3 @@
+mod notify;
+
+pub use notify::*;
\ No newline at end of file
@@ -0,0 +1,212 @@
+use chrono::{DateTime, Utc};
+use crate::export::escape_html;
+use crate::models::models::EarthquakeFeatures;
+
+/// Default template of [`MessageTemplate`], the one-line summary.
+pub const DEFAULT_TEMPLATE: &str = "{summary}";
+
+/// Markup a message is rendered in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageFormat {
+	/// Plain text
+	Text,
+
+	/// Markdown, as understood by Discord, Matrix or GitHub
+	Markdown,
+
+	/// HTML fragment, e.g. for e-mails
+	Html,
+
+	/// Slack `mrkdwn`
+	Slack,
+}
+
+impl MessageFormat {
+	/// Escapes `text` so it is displayed literally in this format.
+	pub fn escape(self, text: &str) -> String {
+		match self {
+			MessageFormat::Text => text.to_string(),
+			MessageFormat::Markdown => {
+				let mut escaped = String::with_capacity(text.len());
+				for c in text.chars() {
+					if matches!(c, '\\' | '*' | '_' | '~' | '`' | '[' | ']' | '(' | ')' | '#' | '>' | '|') {
+						escaped.push('\\');
+					}
+					escaped.push(c);
+				}
+				escaped
+			}
+			MessageFormat::Html => escape_html(text),
+			MessageFormat::Slack => text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;"),
+		}
+	}
+
+	fn bold(self, text: &str) -> String {
+		match self {
+			MessageFormat::Text => text.to_string(),
+			MessageFormat::Markdown => format!("**{}**", text),
+			MessageFormat::Html => format!("<b>{}</b>", text),
+			MessageFormat::Slack => format!("*{}*", text),
+		}
+	}
+
+	fn link(self, text: &str, url: Option<&str>) -> String {
+		match (self, url) {
+			(MessageFormat::Markdown, Some(url)) => format!("[{}]({})", text, url),
+			(MessageFormat::Html, Some(url)) => format!(r#"<a href="{}">{}</a>"#, escape_html(url), text),
+			(MessageFormat::Slack, Some(url)) => format!("<{}|{}>", url, text),
+			_ => text.to_string(),
+		}
+	}
+}
+
+/// Formats an integer with `,` thousands separators (`1203` → `1,203`).
+pub fn group_thousands(value: u64) -> String {
+	let digits = value.to_string();
+	let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+	for (i, digit) in digits.chars().enumerate() {
+		if i > 0 && (digits.len() - i).is_multiple_of(3) {
+			grouped.push(',');
+		}
+		grouped.push(digit);
+	}
+	grouped
+}
+
+fn magnitude(event: &EarthquakeFeatures) -> String {
+	event.properties.magnitude.map(|m| format!("{:.1}", m)).unwrap_or_else(|| "?".to_string())
+}
+
+fn depth(event: &EarthquakeFeatures) -> String {
+	format!("{:.0}", event.geometry.depth_km())
+}
+
+/// Placeholder values of an event, escaped for `format`.
+///
+/// These are the values substituted by [`MessageTemplate::render`]; pass
+/// them to a template engine of your choice for anything more elaborate.
+/// Missing values are empty strings.
+pub fn fields(event: &EarthquakeFeatures, format: MessageFormat) -> Vec<(&'static str, String)> {
+	let properties = &event.properties;
+	let text = |value: Option<&str>| format.escape(value.unwrap_or_default());
+	vec![
+		("summary", summary(event, format)),
+		("id", text(Some(&event.id))),
+		("magnitude", magnitude(event)),
+		("mag_type", text(properties.magnitude_type.as_deref())),
+		("place", text(properties.place.as_deref())),
+		("depth_km", depth(event)),
+		("latitude", format!("{:.3}", event.geometry.latitude())),
+		("longitude", format!("{:.3}", event.geometry.longitude())),
+		("alert", text(properties.alert_level.as_deref())),
+		("felt", properties.felt.map(|felt| group_thousands(felt as u64)).unwrap_or_default()),
+		("time", properties.time
+			.and_then(|ms| DateTime::<Utc>::from_timestamp_millis(ms as i64))
+			.map(|time| time.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+			.unwrap_or_default()),
+		("tsunami", if properties.tsunami == Some(1) { "tsunami".to_string() } else { String::new() }),
+		("url", text(properties.url.as_deref())),
+	]
+}
+
+/// One-line summary of an event, such as
+/// `M 6.8 — 10 km NE of X, depth 22 km, red alert, felt by 1,203`.
+///
+/// The magnitude is bold and the place links to the event page except in
+/// plain text. The alert level and felt count are left out when unknown.
+pub fn summary(event: &EarthquakeFeatures, format: MessageFormat) -> String {
+	let properties = &event.properties;
+	let mut parts = vec![
+		format.link(&format.escape(properties.place.as_deref().unwrap_or("Unknown location")), properties.url.as_deref()),
+		format!("depth {} km", depth(event)),
+	];
+	if let Some(alert) = &properties.alert_level {
+		parts.push(format!("{} alert", format.escape(alert)));
+	}
+	if let Some(felt) = properties.felt.filter(|felt| *felt > 0) {
+		parts.push(format!("felt by {}", group_thousands(felt as u64)));
+	}
+	if properties.tsunami == Some(1) {
+		parts.push("tsunami possible".to_string());
+	}
+	format!("{} — {}", format.bold(&format!("M {}", magnitude(event))), parts.join(", "))
+}
+
+/// Renders events into notification messages from a template.
+///
+/// The template may contain these placeholders, replaced with the values of
+/// [`fields`]: `{summary}`, `{id}`, `{magnitude}`, `{mag_type}`, `{place}`,
+/// `{depth_km}`, `{latitude}`, `{longitude}`, `{alert}`, `{felt}`, `{time}`,
+/// `{tsunami}` and `{url}`. Values are escaped for the message format, the
+/// template itself is used as-is.
+///
+/// ## Example
+/// ```rust,no_run
+/// use usgs_earthquake_api::UsgsClient;
+/// use usgs_earthquake_api::notify::{MessageFormat, MessageTemplate};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let template = MessageTemplate::new("{summary}\n{time} · {url}").format(MessageFormat::Markdown);
+///     let response = UsgsClient::new().query().min_magnitude(5.0).fetch().await.unwrap();
+///     for event in &response.features {
+///         println!("{}", template.render(event));
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct MessageTemplate {
+	template: String,
+	format: MessageFormat,
+}
+
+impl MessageTemplate {
+	/// Creates a plain text template.
+	pub fn new(template: impl Into<String>) -> Self {
+		Self {
+			template: template.into(),
+			format: MessageFormat::Text,
+		}
+	}
+
+	/// Sets the markup the message is rendered in (default plain text).
+	pub fn format(mut self, format: MessageFormat) -> Self {
+		self.format = format;
+		self
+	}
+
+	/// Renders the message for an event.
+	///
+	/// Unknown placeholders are kept as written.
+	pub fn render(&self, event: &EarthquakeFeatures) -> String {
+		let fields = fields(event, self.format);
+		let mut message = String::with_capacity(self.template.len());
+		let mut rest = self.template.as_str();
+		while let Some(start) = rest.find('{') {
+			message.push_str(&rest[..start]);
+			rest = &rest[start..];
+			let value = rest.find('}').and_then(|end| {
+				let value = fields.iter().find(|(name, _)| *name == &rest[1..end])?;
+				Some((end, &value.1))
+			});
+			match value {
+				Some((end, value)) => {
+					message.push_str(value);
+					rest = &rest[end + 1..];
+				}
+				None => {
+					message.push('{');
+					rest = &rest[1..];
+				}
+			}
+		}
+		message.push_str(rest);
+		message
+	}
+}
+
+impl Default for MessageTemplate {
+	fn default() -> Self {
+		Self::new(DEFAULT_TEMPLATE)
+	}
+}
\ No newline at end of file
@@ -0,0 +1 @@
+pub mod plates;
\ No newline at end of file
@@ -0,0 +1,218 @@
+use serde_json::Value;
+use crate::error::error::UsgsError;
+use crate::models::models::{haversine_km, initial_bearing, EarthquakeFeatures, EarthquakeResponse, EARTH_RADIUS_KM};
+
+/// A named plate boundary polyline.
+#[derive(Debug, Clone)]
+pub struct PlateBoundary {
+	/// Boundary name (e.g. `"AF-AN"`), if present in the dataset.
+	pub name: Option<String>,
+
+	/// Vertices as `(latitude, longitude)` pairs.
+	pub points: Vec<(f64, f64)>,
+}
+
+/// Set of tectonic plate boundaries.
+///
+/// Load a GeoJSON dataset of `LineString`/`MultiLineString` features, such as
+/// the PB2002 model (Bird, 2003) distributed as `PB2002_boundaries.json`.
+#[derive(Debug, Clone, Default)]
+pub struct PlateBoundaries {
+	/// Boundary polylines.
+	pub boundaries: Vec<PlateBoundary>,
+}
+
+fn parse_line(coordinates: &Value) -> Option<Vec<(f64, f64)>> {
+	coordinates.as_array()?.iter()
+		.map(|point| {
+			let point = point.as_array()?;
+			Some((point.get(1)?.as_f64()?, point.first()?.as_f64()?))
+		})
+		.collect()
+}
+
+/// Distance in kilometers from `point` to the great-circle arc `a`-`b`.
+fn distance_to_arc_km(point: (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+	let arc_length = haversine_km(a, b) / EARTH_RADIUS_KM;
+	let distance = haversine_km(a, point) / EARTH_RADIUS_KM;
+	let bearing_difference = initial_bearing(a, point) - initial_bearing(a, b);
+
+	let cross_track = (distance.sin() * bearing_difference.sin()).asin();
+	let along_track = (distance.cos() / cross_track.cos()).clamp(-1.0, 1.0).acos() * bearing_difference.cos().signum();
+
+	if (0.0..=arc_length).contains(&along_track) {
+		cross_track.abs() * EARTH_RADIUS_KM
+	} else {
+		haversine_km(a, point).min(haversine_km(b, point))
+	}
+}
+
+impl PlateBoundaries {
+	/// Parses a GeoJSON `FeatureCollection` of plate boundary lines.
+	///
+	/// The boundary name is taken from the `Name` or `name` property.
+	pub fn from_geojson(geojson: &str) -> Result<Self, UsgsError> {
+		let document: Value = serde_json::from_str(geojson)?;
+		let features = document.get("features").and_then(Value::as_array)
+			.ok_or_else(|| UsgsError::PlateBoundaries("missing `features` array".to_string()))?;
+
+		let mut boundaries = Vec::new();
+		for feature in features {
+			let properties = feature.get("properties");
+			let name = properties
+				.and_then(|p| p.get("Name").or_else(|| p.get("name")))
+				.and_then(Value::as_str)
+				.map(|name| name.to_string());
+
+			let geometry = feature.get("geometry")
+				.ok_or_else(|| UsgsError::PlateBoundaries("feature without geometry".to_string()))?;
+			let coordinates = &geometry["coordinates"];
+			let lines = match geometry["type"].as_str() {
+				Some("LineString") => vec![parse_line(coordinates)],
+				Some("MultiLineString") => coordinates.as_array().map(|lines| lines.iter().map(parse_line).collect()).unwrap_or_default(),
+				other => return Err(UsgsError::PlateBoundaries(format!("unsupported geometry type {:?}", other))),
+			};
+
+			for points in lines {
+				let points = points.ok_or_else(|| UsgsError::PlateBoundaries("invalid coordinates".to_string()))?;
+				boundaries.push(PlateBoundary { name: name.clone(), points });
+			}
+		}
+
+		Ok(Self { boundaries })
+	}
+
+	/// Reads a GeoJSON plate boundary dataset from a file.
+	pub fn from_path(path: impl AsRef<std::path::Path>) -> Result<Self, UsgsError> {
+		let geojson = std::fs::read_to_string(path).map_err(|err| UsgsError::PlateBoundaries(err.to_string()))?;
+		Self::from_geojson(&geojson)
+	}
+
+	/// Nearest boundary to a point and the distance to it in kilometers.
+	pub fn nearest(&self, latitude: f64, longitude: f64) -> Option<(&PlateBoundary, f64)> {
+		let point = (latitude, longitude);
+		self.boundaries.iter()
+			.filter_map(|boundary| {
+				let distance = match boundary.points.as_slice() {
+					[single] => haversine_km(*single, point),
+					points => points.windows(2)
+						.map(|pair| distance_to_arc_km(point, pair[0], pair[1]))
+						.fold(None, |min: Option<f64>, d| Some(min.map_or(d, |m| m.min(d))))?,
+				};
+				Some((boundary, distance))
+			})
+			.min_by(|a, b| a.1.total_cmp(&b.1))
+	}
+
+	/// Distance in kilometers from a point to the nearest boundary.
+	pub fn distance_km(&self, latitude: f64, longitude: f64) -> Option<f64> {
+		self.nearest(latitude, longitude).map(|(_, distance)| distance)
+	}
+}
+
+impl EarthquakeFeatures {
+	/// Distance in kilometers from the epicenter to the nearest plate boundary.
+	pub fn distance_to_plate_boundary_km(&self, boundaries: &PlateBoundaries) -> Option<f64> {
+		boundaries.distance_km(self.geometry.latitude(), self.geometry.longitude())
+	}
+}
+
+impl EarthquakeResponse {
+	/// Keeps only interplate events, i.e. within `max_km` of a plate boundary.
+	pub fn filter_interplate(&mut self, boundaries: &PlateBoundaries, max_km: f64) {
+		self.retain_by_plate_distance(boundaries, |distance| distance <= max_km);
+	}
+
+	/// Keeps only intraplate events, i.e. at least `min_km` from any plate boundary.
+	pub fn filter_intraplate(&mut self, boundaries: &PlateBoundaries, min_km: f64) {
+		self.retain_by_plate_distance(boundaries, |distance| distance >= min_km);
+	}
+
+	fn retain_by_plate_distance(&mut self, boundaries: &PlateBoundaries, keep: impl Fn(f64) -> bool) {
+		self.features.retain(|feature| feature.distance_to_plate_boundary_km(boundaries).is_some_and(&keep));
+		self.metadata.count = self.features.len() as u32;
+		self.recompute_bbox();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json::json;
+	use super::*;
+	use crate::tests::tests::{feature, response, QUERY_URL};
+
+	/// A ridge along the prime meridian from 10°S to 10°N, a two-part
+	/// boundary in the Pacific and a single vertex.
+	const BOUNDARIES: &str = r#"{"type":"FeatureCollection","features":[
+		{"type":"Feature","properties":{"Name":"AF-EU"},"geometry":{"type":"LineString","coordinates":[[0.0,-10.0],[0.0,10.0]]}},
+		{"type":"Feature","properties":{"name":"PA-NA"},"geometry":{"type":"MultiLineString","coordinates":[[[-150.0,40.0],[-140.0,40.0]],[[-130.0,40.0],[-120.0,40.0]]]}},
+		{"type":"Feature","properties":{},"geometry":{"type":"LineString","coordinates":[[90.0,-60.0]]}}
+	]}"#;
+
+	fn km(degrees: f64) -> f64 {
+		degrees * EARTH_RADIUS_KM.to_radians()
+	}
+
+	#[test]
+	fn from_geojson_reads_lines_and_names() {
+		let boundaries = PlateBoundaries::from_geojson(BOUNDARIES).unwrap();
+		let names: Vec<Option<&str>> = boundaries.boundaries.iter().map(|boundary| boundary.name.as_deref()).collect();
+		assert_eq!(names, [Some("AF-EU"), Some("PA-NA"), Some("PA-NA"), None]);
+		assert_eq!(boundaries.boundaries[0].points, [(-10.0, 0.0), (10.0, 0.0)]);
+
+		for invalid in [r#"{"type":"FeatureCollection"}"#, r#"{"features":[{"geometry":{"type":"Point","coordinates":[0.0,0.0]}}]}"#, r#"{"features":[{"geometry":{"type":"LineString","coordinates":[["a",0.0]]}}]}"#] {
+			assert!(matches!(PlateBoundaries::from_geojson(invalid), Err(UsgsError::PlateBoundaries(_))), "{}", invalid);
+		}
+		assert!(matches!(PlateBoundaries::from_path("/nonexistent/PB2002_boundaries.json"), Err(UsgsError::PlateBoundaries(_))));
+	}
+
+	#[test]
+	fn nearest_measures_to_the_closest_segment() {
+		let boundaries = PlateBoundaries::from_geojson(BOUNDARIES).unwrap();
+
+		// Across the ridge, the distance is perpendicular to it.
+		let (ridge, distance) = boundaries.nearest(5.0, 1.0).unwrap();
+		assert_eq!(ridge.name.as_deref(), Some("AF-EU"));
+		assert!((distance - km(1.0) * 5f64.to_radians().cos()).abs() < 1.0, "{}", distance);
+
+		// Beyond its end, to the nearest vertex.
+		assert!((boundaries.distance_km(15.0, 0.0).unwrap() - km(5.0)).abs() < 1e-6);
+
+		// The gap between the parts of a multi-line boundary is not a boundary.
+		let (boundary, distance) = boundaries.nearest(40.0, -135.0).unwrap();
+		assert_eq!(boundary.name.as_deref(), Some("PA-NA"));
+		assert!(distance > 400.0, "{}", distance);
+
+		let (single, distance) = boundaries.nearest(-61.0, 90.0).unwrap();
+		assert_eq!(single.points.len(), 1);
+		assert!((distance - km(1.0)).abs() < 1e-6);
+
+		assert_eq!(PlateBoundaries::default().distance_km(0.0, 0.0), None);
+	}
+
+	#[test]
+	fn interplate_and_intraplate_filters_split_by_distance() {
+		let boundaries = PlateBoundaries::from_geojson(BOUNDARIES).unwrap();
+		let catalog = || response(QUERY_URL, vec![
+			feature("ridge", [0.2, 1.0, 10.0], json!({ "mag": 5.0 })),
+			feature("craton", [20.0, 0.0, 10.0], json!({ "mag": 4.0 })),
+			feature("offset", [-0.8, -3.0, 10.0], json!({ "mag": 4.5 })),
+		]);
+
+		let mut interplate = catalog();
+		interplate.filter_interplate(&boundaries, 100.0);
+		let ids: Vec<&str> = interplate.features.iter().map(|feature| feature.id.as_str()).collect();
+		assert_eq!(ids, ["ridge", "offset"]);
+		assert_eq!(interplate.metadata.count, 2);
+
+		let mut intraplate = catalog();
+		intraplate.filter_intraplate(&boundaries, 100.0);
+		let ids: Vec<&str> = intraplate.features.iter().map(|feature| feature.id.as_str()).collect();
+		assert_eq!(ids, ["craton"]);
+		assert_eq!(intraplate.metadata.count, 1);
+
+		let mut none = catalog();
+		none.filter_interplate(&PlateBoundaries::default(), 100.0);
+		assert!(none.features.is_empty());
+	}
+}
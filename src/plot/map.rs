@@ -0,0 +1,122 @@
+use std::path::Path;
+use country_boundaries::LatLon;
+use plotters::prelude::*;
+use crate::country::country::embedded_boundaries;
+use crate::error::error::UsgsError;
+use crate::models::models::{EarthquakeFeatures, EarthquakeResponse};
+
+const SEA: RGBColor = RGBColor(214, 230, 245);
+const LAND: RGBColor = RGBColor(236, 232, 220);
+
+/// Property used to color the epicenters of an [`epicenter_map`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorBy {
+	/// Shallow (< 70 km) red, intermediate (70-300 km) yellow, deep (> 300 km) blue.
+	Depth,
+
+	/// PAGER alert level color; gray for events without an alert.
+	Alert,
+}
+
+impl ColorBy {
+	fn color(self, feature: &EarthquakeFeatures) -> RGBColor {
+		match self {
+			ColorBy::Depth => match feature.geometry.depth_km() {
+				depth if depth < 70.0 => RGBColor(215, 48, 39),
+				depth if depth < 300.0 => RGBColor(254, 204, 62),
+				_ => RGBColor(49, 104, 178),
+			},
+			ColorBy::Alert => match feature.properties.alert_level.as_deref() {
+				Some("green") => RGBColor(26, 152, 80),
+				Some("yellow") => RGBColor(254, 224, 20),
+				Some("orange") => RGBColor(253, 141, 60),
+				Some("red") => RGBColor(215, 25, 28),
+				_ => RGBColor(120, 120, 120),
+			},
+		}
+	}
+}
+
+fn map_error(err: impl std::fmt::Display) -> UsgsError {
+	UsgsError::Plot(err.to_string())
+}
+
+/// Map extent as `(min_lon, max_lon, min_lat, max_lat)`, padded around the
+/// events, or the whole world if there are none.
+fn extent(response: &EarthquakeResponse) -> (f64, f64, f64, f64) {
+	let mut points = response.features.iter().map(|f| (f.geometry.longitude(), f.geometry.latitude()));
+	let Some(first) = points.next() else {
+		return (-180.0, 180.0, -90.0, 90.0);
+	};
+	let (min_lon, max_lon, min_lat, max_lat) = points.fold((first.0, first.0, first.1, first.1), |(a, b, c, d), (lon, lat)| {
+		(a.min(lon), b.max(lon), c.min(lat), d.max(lat))
+	});
+	let padding = ((max_lon - min_lon).max(max_lat - min_lat) * 0.15).max(2.0);
+	(
+		(min_lon - padding).max(-180.0),
+		(max_lon + padding).min(180.0),
+		(min_lat - padding).max(-90.0),
+		(max_lat + padding).min(90.0),
+	)
+}
+
+/// Renders the epicenters over a coarse land/sea basemap to a bitmap image
+/// (PNG, JPEG, ... by extension of `path`).
+///
+/// Circles are sized by magnitude and colored according to `color_by`. The
+/// basemap is derived from the embedded country boundaries dataset, so no
+/// external tile service is needed.
+pub fn epicenter_map(response: &EarthquakeResponse, path: impl AsRef<Path>, size: (u32, u32), color_by: ColorBy) -> Result<(), UsgsError> {
+	let boundaries = embedded_boundaries();
+	let (min_lon, max_lon, min_lat, max_lat) = extent(response);
+
+	let root = BitMapBackend::new(path.as_ref(), size).into_drawing_area();
+	root.fill(&WHITE).map_err(map_error)?;
+
+	let mut chart = ChartBuilder::on(&root)
+		.margin(12)
+		.x_label_area_size(30)
+		.y_label_area_size(40)
+		.build_cartesian_2d(min_lon..max_lon, min_lat..max_lat)
+		.map_err(map_error)?;
+
+	chart.plotting_area().fill(&SEA).map_err(map_error)?;
+
+	// Sample the boundaries dataset on a grid of roughly two pixels per cell.
+	let (width, height) = chart.plotting_area().dim_in_pixel();
+	let lon_step = (max_lon - min_lon) / (width.max(2) / 2) as f64;
+	let lat_step = (max_lat - min_lat) / (height.max(2) / 2) as f64;
+	let mut land = Vec::new();
+	let mut lat = min_lat;
+	while lat < max_lat {
+		let mut lon = min_lon;
+		while lon < max_lon {
+			let center = LatLon::new((lat + lat_step / 2.0).clamp(-90.0, 90.0), (lon + lon_step / 2.0).clamp(-180.0, 180.0));
+			if center.is_ok_and(|center| !boundaries.ids(center).is_empty()) {
+				land.push(Rectangle::new([(lon, lat), (lon + lon_step, lat + lat_step)], LAND.filled()));
+			}
+			lon += lon_step;
+		}
+		lat += lat_step;
+	}
+	chart.draw_series(land).map_err(map_error)?;
+
+	chart.configure_mesh()
+		.disable_mesh()
+		.x_desc("Longitude")
+		.y_desc("Latitude")
+		.draw()
+		.map_err(map_error)?;
+
+	let mut features: Vec<&EarthquakeFeatures> = response.features.iter().collect();
+	features.sort_by(|a, b| b.properties.magnitude.unwrap_or_default().total_cmp(&a.properties.magnitude.unwrap_or_default()));
+	chart.draw_series(features.into_iter().map(|feature| {
+		let magnitude = feature.properties.magnitude.unwrap_or_default().max(0.0);
+		let radius = (magnitude * magnitude / 2.0).max(2.0) as i32;
+		EmptyElement::at((feature.geometry.longitude(), feature.geometry.latitude()))
+			+ Circle::new((0, 0), radius, color_by.color(feature).mix(0.75).filled())
+			+ Circle::new((0, 0), radius, BLACK.mix(0.6))
+	})).map_err(map_error)?;
+
+	root.present().map_err(map_error)
+}
\ No newline at end of file
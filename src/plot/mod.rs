@@ -0,0 +1,7 @@
+mod plot;
+#[cfg(feature = "map")]
+mod map;
+
+pub use plot::*;
+#[cfg(feature = "map")]
+pub use map::*;
\ No newline at end of file
@@ -0,0 +1,120 @@
+use std::path::Path;
+use chrono::{DateTime, Duration, Utc};
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use crate::error::error::UsgsError;
+use crate::models::models::EarthquakeResponse;
+
+fn plot_error(err: impl std::fmt::Display) -> UsgsError {
+	UsgsError::Plot(err.to_string())
+}
+
+fn event_times(response: &EarthquakeResponse) -> Vec<(DateTime<Utc>, f64)> {
+	let mut events: Vec<(DateTime<Utc>, f64)> = response.features.iter()
+		.filter_map(|f| {
+			let time = DateTime::from_timestamp_millis(f.properties.time? as i64)?;
+			Some((time, f.properties.magnitude?))
+		})
+		.collect();
+	events.sort_by_key(|(time, _)| *time);
+	events
+}
+
+fn time_range(events: &[(DateTime<Utc>, f64)]) -> std::ops::Range<DateTime<Utc>> {
+	match (events.first(), events.last()) {
+		(Some((first, _)), Some((last, _))) if first < last => *first..*last,
+		(Some((first, _)), _) => (*first - Duration::hours(1))..(*first + Duration::hours(1)),
+		_ => {
+			let now = Utc::now();
+			(now - Duration::days(1))..now
+		}
+	}
+}
+
+/// Renders to an SVG file if `path` ends in `.svg`, otherwise to a bitmap
+/// (PNG, JPEG, ... by extension).
+fn render(path: &Path, size: (u32, u32), draw: impl Fn(DrawingArea<SVGBackend, Shift>) -> Result<(), UsgsError>, draw_bitmap: impl Fn(DrawingArea<BitMapBackend, Shift>) -> Result<(), UsgsError>) -> Result<(), UsgsError> {
+	let is_svg = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("svg"));
+	if is_svg {
+		let root = SVGBackend::new(path, size).into_drawing_area();
+		draw(root)
+	} else {
+		let root = BitMapBackend::new(path, size).into_drawing_area();
+		draw_bitmap(root)
+	}
+}
+
+fn draw_magnitude_time<DB: DrawingBackend>(root: DrawingArea<DB, Shift>, response: &EarthquakeResponse) -> Result<(), UsgsError> {
+	let events = event_times(response);
+	let max_magnitude = (events.iter().map(|(_, m)| *m).fold(1.0, f64::max) + 0.5).ceil();
+	let min_magnitude = events.iter().map(|(_, m)| *m).fold(max_magnitude, f64::min).floor().min(0.0);
+
+	root.fill(&WHITE).map_err(plot_error)?;
+	let mut chart = ChartBuilder::on(&root)
+		.caption("Magnitude vs. time", ("sans-serif", 24))
+		.margin(16)
+		.x_label_area_size(40)
+		.y_label_area_size(48)
+		.build_cartesian_2d(time_range(&events), min_magnitude..max_magnitude)
+		.map_err(plot_error)?;
+
+	chart.configure_mesh()
+		.x_desc("Time (UTC)")
+		.y_desc("Magnitude")
+		.x_label_formatter(&|time| time.format("%Y-%m-%d").to_string())
+		.draw()
+		.map_err(plot_error)?;
+
+	chart.draw_series(events.iter().map(|(time, magnitude)| {
+		Circle::new((*time, *magnitude), (*magnitude).max(1.0) as i32 + 1, RED.mix(0.6).filled())
+	})).map_err(plot_error)?;
+
+	root.present().map_err(plot_error)
+}
+
+fn draw_cumulative_count<DB: DrawingBackend>(root: DrawingArea<DB, Shift>, response: &EarthquakeResponse) -> Result<(), UsgsError> {
+	let events = event_times(response);
+	let points: Vec<(DateTime<Utc>, u32)> = events.iter().enumerate()
+		.map(|(index, (time, _))| (*time, index as u32 + 1))
+		.collect();
+
+	root.fill(&WHITE).map_err(plot_error)?;
+	let mut chart = ChartBuilder::on(&root)
+		.caption("Cumulative number of events", ("sans-serif", 24))
+		.margin(16)
+		.x_label_area_size(40)
+		.y_label_area_size(56)
+		.build_cartesian_2d(time_range(&events), 0u32..(points.len() as u32).max(1))
+		.map_err(plot_error)?;
+
+	chart.configure_mesh()
+		.x_desc("Time (UTC)")
+		.y_desc("Events")
+		.x_label_formatter(&|time| time.format("%Y-%m-%d").to_string())
+		.draw()
+		.map_err(plot_error)?;
+
+	chart.draw_series(LineSeries::new(points, BLUE.stroke_width(2))).map_err(plot_error)?;
+
+	root.present().map_err(plot_error)
+}
+
+/// Draws a magnitude-vs-time scatter plot of the events to `path`.
+///
+/// The format is chosen by extension: `.svg` for SVG, otherwise a bitmap
+/// such as PNG.
+pub fn magnitude_vs_time(response: &EarthquakeResponse, path: impl AsRef<Path>, size: (u32, u32)) -> Result<(), UsgsError> {
+	render(path.as_ref(), size,
+		|root| draw_magnitude_time(root, response),
+		|root| draw_magnitude_time(root, response))
+}
+
+/// Draws the cumulative number of events over time to `path`.
+///
+/// The format is chosen by extension: `.svg` for SVG, otherwise a bitmap
+/// such as PNG.
+pub fn cumulative_count(response: &EarthquakeResponse, path: impl AsRef<Path>, size: (u32, u32)) -> Result<(), UsgsError> {
+	render(path.as_ref(), size,
+		|root| draw_cumulative_count(root, response),
+		|root| draw_cumulative_count(root, response))
+}
\ No newline at end of file
@@ -0,0 +1,30 @@
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::{sleep_until, Instant};
+
+/// Spaces out requests so that at most one is sent per `interval`.
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+	interval: Duration,
+	next_slot: Mutex<Instant>,
+}
+
+impl RateLimiter {
+	/// Creates a limiter allowing `max_requests` requests every `per`.
+	pub(crate) fn new(max_requests: u32, per: Duration) -> Self {
+		Self {
+			interval: per / max_requests.max(1),
+			next_slot: Mutex::new(Instant::now()),
+		}
+	}
+
+	/// Waits until the next request slot is available and reserves it.
+	pub(crate) async fn acquire(&self) {
+		let mut next_slot = self.next_slot.lock().await;
+		let now = Instant::now();
+		if *next_slot > now {
+			sleep_until(*next_slot).await;
+		}
+		*next_slot = (*next_slot).max(now) + self.interval;
+	}
+}
\ No newline at end of file
@@ -0,0 +1 @@
+pub mod retry;
\ No newline at end of file
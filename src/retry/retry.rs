@@ -0,0 +1,206 @@
+use std::collections::hash_map::RandomState;
+use std::fmt::Debug;
+use std::hash::BuildHasher;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use reqwest::StatusCode;
+
+/// Why a request attempt failed.
+#[derive(Debug)]
+pub enum RetryCause<'a> {
+	/// The server answered with an unsuccessful HTTP status.
+	Status(StatusCode),
+
+	/// The request could not be completed (connection error, timeout, ...).
+	Error(&'a reqwest::Error),
+}
+
+/// Decides whether and when failed requests are retried.
+///
+/// Implement this to fit strict latency budgets or custom error handling,
+/// and install it with [`UsgsClientBuilder::retry_policy`](crate::UsgsClientBuilder::retry_policy).
+pub trait RetryPolicy: Debug + Send + Sync {
+	/// Maximum number of retries after the first attempt.
+	fn max_retries(&self) -> u32;
+
+	/// Delay before retry number `attempt` (starting at 0).
+	fn backoff(&self, attempt: u32) -> Duration;
+
+	/// Returns `true` if the failed attempt should be retried.
+	fn is_retryable(&self, cause: &RetryCause<'_>) -> bool {
+		match cause {
+			RetryCause::Status(status) => status.is_server_error() || *status == StatusCode::TOO_MANY_REQUESTS,
+			RetryCause::Error(err) => err.is_timeout() || err.is_connect(),
+		}
+	}
+}
+
+/// Retries connection errors, timeouts, 429 and 5xx responses with a
+/// doubling delay.
+///
+/// With `jitter` each delay is drawn uniformly from the upper half of the
+/// doubled delay, so tasks failing together do not retry in lockstep.
+#[derive(Debug, Clone)]
+pub struct ExponentialBackoff {
+	/// Maximum number of retries after the first attempt.
+	pub max_retries: u32,
+
+	/// Delay before the first retry.
+	pub base_delay: Duration,
+
+	/// Upper bound for a single delay.
+	pub max_delay: Duration,
+
+	/// Randomizes each delay between half and all of its nominal value.
+	pub jitter: bool,
+}
+
+impl ExponentialBackoff {
+	/// Creates a policy retrying up to `max_retries` times starting at `base_delay`.
+	pub fn new(max_retries: u32, base_delay: Duration) -> Self {
+		Self {
+			max_retries,
+			base_delay,
+			max_delay: Duration::from_secs(60),
+			jitter: true,
+		}
+	}
+}
+
+impl RetryPolicy for ExponentialBackoff {
+	fn max_retries(&self) -> u32 {
+		self.max_retries
+	}
+
+	fn backoff(&self, attempt: u32) -> Duration {
+		let delay = self.base_delay.saturating_mul(2u32.saturating_pow(attempt)).min(self.max_delay);
+		if self.jitter {
+			delay.mul_f64(0.5 + 0.5 * random_fraction())
+		} else {
+			delay
+		}
+	}
+}
+
+/// Never retries.
+#[derive(Debug, Clone, Copy)]
+pub struct NoRetry;
+
+impl RetryPolicy for NoRetry {
+	fn max_retries(&self) -> u32 {
+		0
+	}
+
+	fn backoff(&self, _attempt: u32) -> Duration {
+		Duration::ZERO
+	}
+}
+/// Uniform random number in `[0, 1)`, good enough for spreading retries.
+fn random_fraction() -> f64 {
+	(RandomState::new().hash_one(Instant::now()) >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Token bucket limiting the retries of all requests sharing a client.
+///
+/// Every retry takes one token and tokens refill at a steady rate, so a
+/// burst of failing queries across tasks gives up instead of multiplying
+/// into a retry storm. First attempts are never limited.
+///
+/// ## Example
+/// ```rust
+/// use std::time::Duration;
+/// use usgs_earthquake_api::{RetryBudget, UsgsClient};
+///
+/// let client = UsgsClient::builder()
+///     .max_retries(3)
+///     .retry_budget(RetryBudget::new(10, 1.0))
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug)]
+pub struct RetryBudget {
+	capacity: f64,
+	refill_per_second: f64,
+	state: Mutex<(f64, Instant)>,
+}
+
+impl RetryBudget {
+	/// Creates a full budget of `capacity` retries, refilling
+	/// `refill_per_second` retries per second.
+	pub fn new(capacity: u32, refill_per_second: f64) -> Self {
+		Self {
+			capacity: capacity as f64,
+			refill_per_second: refill_per_second.max(0.0),
+			state: Mutex::new((capacity as f64, Instant::now())),
+		}
+	}
+
+	/// Takes a token for one retry, returning `false` if the budget is exhausted.
+	pub fn try_withdraw(&self) -> bool {
+		let mut state = self.state.lock().expect("retry budget lock poisoned");
+		let (tokens, last_refill) = &mut *state;
+		let now = Instant::now();
+		*tokens = (*tokens + now.duration_since(*last_refill).as_secs_f64() * self.refill_per_second).min(self.capacity);
+		*last_refill = now;
+		if *tokens >= 1.0 {
+			*tokens -= 1.0;
+			true
+		} else {
+			false
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn backoff_doubles_up_to_the_maximum() {
+		let policy = ExponentialBackoff { max_delay: Duration::from_secs(5), jitter: false, ..ExponentialBackoff::new(3, Duration::from_millis(500)) };
+		let delays: Vec<Duration> = (0..5).map(|attempt| policy.backoff(attempt)).collect();
+		assert_eq!(delays, [500, 1000, 2000, 4000, 5000].map(Duration::from_millis));
+		assert_eq!(policy.backoff(u32::MAX), Duration::from_secs(5));
+	}
+
+	#[test]
+	fn jitter_stays_in_the_upper_half() {
+		let policy = ExponentialBackoff::new(3, Duration::from_secs(1));
+		assert!(policy.jitter);
+		let delays: Vec<Duration> = (0..200).map(|_| policy.backoff(2)).collect();
+		for delay in &delays {
+			assert!((Duration::from_secs(2)..=Duration::from_secs(4)).contains(delay), "{:?}", delay);
+		}
+		assert!(delays.iter().any(|delay| *delay != delays[0]));
+	}
+
+	#[test]
+	fn default_retries_server_errors_and_throttling() {
+		let policy = ExponentialBackoff::new(3, Duration::from_secs(1));
+		for status in [StatusCode::TOO_MANY_REQUESTS, StatusCode::INTERNAL_SERVER_ERROR, StatusCode::SERVICE_UNAVAILABLE] {
+			assert!(policy.is_retryable(&RetryCause::Status(status)), "{}", status);
+		}
+		for status in [StatusCode::BAD_REQUEST, StatusCode::NOT_FOUND] {
+			assert!(!policy.is_retryable(&RetryCause::Status(status)), "{}", status);
+		}
+		assert_eq!((NoRetry.max_retries(), NoRetry.backoff(3)), (0, Duration::ZERO));
+	}
+
+	#[test]
+	fn budget_is_spent_and_refilled() {
+		let budget = RetryBudget::new(3, 0.5);
+		assert_eq!([budget.try_withdraw(), budget.try_withdraw(), budget.try_withdraw(), budget.try_withdraw()], [true, true, true, false]);
+
+		// Two seconds refill one token.
+		budget.state.lock().unwrap().1 -= Duration::from_secs(2);
+		assert!(budget.try_withdraw());
+		assert!(!budget.try_withdraw());
+
+		// Refilling stops at the capacity.
+		budget.state.lock().unwrap().1 -= Duration::from_secs(3600);
+		assert_eq!((0..5).filter(|_| budget.try_withdraw()).count(), 3);
+
+		let empty = RetryBudget::new(0, 10.0);
+		assert!(!empty.try_withdraw());
+	}
+}
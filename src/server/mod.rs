@@ -0,0 +1 @@
+pub mod server;
\ No newline at end of file
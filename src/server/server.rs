@@ -0,0 +1,230 @@
+use std::sync::Arc;
+use std::time::Duration;
+use axum::extract::{RawQuery, State};
+use axum::http::header::{HeaderName, CONTENT_TYPE};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use log::debug;
+use reqwest::Url;
+use tokio::net::{TcpListener, ToSocketAddrs};
+use tokio::sync::Semaphore;
+use crate::cache::cache::{CacheBackend, MemoryCache};
+use crate::error::error::UsgsError;
+use crate::{UsgsClient, UsgsQuery};
+
+/// Header telling whether a response was served from the cache (`HIT`) or fetched (`MISS`).
+pub const CACHE_HEADER: HeaderName = HeaderName::from_static("x-cache");
+
+/// Path the query endpoint is served on, mirroring FDSN event services.
+pub const QUERY_PATH: &str = "/fdsnws/event/1/query";
+
+struct ProxyState<C> {
+	client: UsgsClient,
+	cache: C,
+	ttl: Duration,
+	upstream: Semaphore,
+}
+
+/// Small HTTP server exposing the client's query API, so several internal
+/// applications share one cached and rate-limited upstream connection.
+///
+/// `GET /fdsnws/event/1/query?...` accepts the parameters understood by
+/// [`UsgsQuery::from_url`], validates them and answers with the upstream
+/// body. Responses are cached under the sorted request parameters for the
+/// configured time to live, with an `X-Cache: HIT`/`MISS` header; upstream
+/// requests honor the client's rate limit and retries, and at most
+/// [`ProxyServer::max_concurrent_upstream`] of them run at once.
+/// `GET /health` answers `200 OK`.
+///
+/// ## Example
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use usgs_earthquake_api::{ProxyServer, UsgsClient};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let client = UsgsClient::builder().rate_limit(2, Duration::from_secs(1)).build().unwrap();
+///     ProxyServer::new(client)
+///         .ttl(Duration::from_secs(30))
+///         .serve("0.0.0.0:8080")
+///         .await
+///         .unwrap();
+/// }
+/// ```
+pub struct ProxyServer<C: CacheBackend = MemoryCache> {
+	client: UsgsClient,
+	cache: C,
+	ttl: Duration,
+	max_concurrent_upstream: usize,
+}
+
+impl ProxyServer {
+	/// Creates a server forwarding to `client`, caching in memory for 60 seconds.
+	pub fn new(client: UsgsClient) -> Self {
+		Self {
+			client,
+			cache: MemoryCache::new(),
+			ttl: Duration::from_secs(60),
+			max_concurrent_upstream: 4,
+		}
+	}
+}
+
+impl<C: CacheBackend + 'static> ProxyServer<C> {
+	/// Stores responses in another [`CacheBackend`], e.g. one shared between replicas.
+	pub fn cache<B: CacheBackend>(self, cache: B) -> ProxyServer<B> {
+		ProxyServer {
+			client: self.client,
+			cache,
+			ttl: self.ttl,
+			max_concurrent_upstream: self.max_concurrent_upstream,
+		}
+	}
+
+	/// Sets how long responses are cached (default 60 seconds).
+	pub fn ttl(mut self, ttl: Duration) -> Self {
+		self.ttl = ttl;
+		self
+	}
+
+	/// Sets how many upstream requests may run at the same time (default 4).
+	pub fn max_concurrent_upstream(mut self, max: usize) -> Self {
+		self.max_concurrent_upstream = max.max(1);
+		self
+	}
+
+	/// Builds the [`Router`], to be nested into an existing axum application.
+	pub fn router(self) -> Router {
+		let state = Arc::new(ProxyState {
+			client: self.client,
+			cache: self.cache,
+			ttl: self.ttl,
+			upstream: Semaphore::new(self.max_concurrent_upstream),
+		});
+		Router::new()
+			.route(QUERY_PATH, get(query::<C>))
+			.route("/health", get(|| async { "OK" }))
+			.with_state(state)
+	}
+
+	/// Serves on `address` until an I/O error occurs.
+	pub async fn serve(self, address: impl ToSocketAddrs) -> Result<(), UsgsError> {
+		let listener = TcpListener::bind(address).await?;
+		axum::serve(listener, self.router()).await?;
+		Ok(())
+	}
+}
+
+fn error_response(err: UsgsError) -> Response {
+	let status = match &err {
+		UsgsError::Api { status, .. } if status.is_client_error() => StatusCode::BAD_REQUEST,
+		UsgsError::Request(_) | UsgsError::Decompression(_) | UsgsError::Io(_) | UsgsError::UnsupportedEncoding(_) | UsgsError::Json(_) | UsgsError::TextFormat(_)
+			| UsgsError::Api { .. } | UsgsError::ApiStatus(_) => StatusCode::BAD_GATEWAY,
+		_ => StatusCode::BAD_REQUEST,
+	};
+	(status, err.to_string()).into_response()
+}
+
+fn body_response(body: Vec<u8>, content_type: &'static str, hit: bool) -> Response {
+	([(CONTENT_TYPE, content_type), (CACHE_HEADER, if hit { "HIT" } else { "MISS" })], body).into_response()
+}
+
+/// Content type of the body requested by `url`, from its `format` parameter.
+fn content_type(url: &str) -> &'static str {
+	let format = Url::parse(url).ok()
+		.and_then(|url| url.query_pairs().find(|(key, _)| key == "format").map(|(_, value)| value.to_ascii_lowercase()));
+	match format.as_deref() {
+		Some("geojson" | "json") => "application/json",
+		Some("text") => "text/plain; charset=utf-8",
+		Some("csv") => "text/csv; charset=utf-8",
+		Some("xml" | "quakeml") => "application/xml",
+		Some("kml") => "application/vnd.google-earth.kml+xml",
+		_ => "application/octet-stream",
+	}
+}
+
+/// Cache key of a request: its parameters in sorted order, so the same
+/// query written differently shares an entry.
+fn cache_key(url: &str) -> String {
+	let mut parameters: Vec<(String, String)> = Url::parse(url)
+		.map(|url| url.query_pairs().into_owned().collect())
+		.unwrap_or_default();
+	parameters.sort();
+	let query: Vec<String> = parameters.into_iter().map(|(key, value)| format!("{}={}", key, value)).collect();
+	format!("proxy:{}", query.join("&"))
+}
+
+async fn query<C: CacheBackend>(State(state): State<Arc<ProxyState<C>>>, RawQuery(parameters): RawQuery) -> Response {
+	let url = format!("{}?{}", state.client.base_url(), parameters.unwrap_or_default());
+	let query = match UsgsQuery::from_url(&state.client, &url) {
+		Ok(query) => query,
+		Err(err) => return error_response(err),
+	};
+	// The body has the format of the validated upstream request.
+	let content_type = match query.build_url() {
+		Ok(upstream) => content_type(&upstream),
+		Err(err) => return error_response(err),
+	};
+	let key = cache_key(&url);
+
+	match state.cache.get(&key).await {
+		Ok(Some(body)) => return body_response(body, content_type, true),
+		Ok(None) => {}
+		Err(err) => debug!("proxy cache lookup failed: {}", err),
+	}
+
+	let _permit = state.upstream.acquire().await.expect("upstream semaphore closed");
+	// Another request may have filled the cache while this one was waiting.
+	if let Ok(Some(body)) = state.cache.get(&key).await {
+		return body_response(body, content_type, true);
+	}
+
+	match query.fetch_raw().await {
+		Ok(raw) => {
+			let body = raw.raw.into_bytes();
+			if let Err(err) = state.cache.put(&key, body.clone(), state.ttl).await {
+				debug!("proxy cache store failed: {}", err);
+			}
+			body_response(body, content_type, false)
+		}
+		Err(err) => error_response(err),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::FdsnProfile;
+
+	#[test]
+	fn content_type_follows_the_upstream_format() {
+		let request = "https://earthquake.usgs.gov/fdsnws/event/1/query?format=text&starttime=2024-01-01";
+
+		let usgs = UsgsClient::new();
+		let upstream = UsgsQuery::from_url(&usgs, "https://earthquake.usgs.gov/fdsnws/event/1/query?starttime=2024-01-01").and_then(|query| query.build_url()).unwrap();
+		assert_eq!(content_type(&upstream), "application/json");
+
+		let emsc = UsgsClient::with_profile(FdsnProfile::emsc());
+		let upstream = UsgsQuery::from_url(&emsc, request).and_then(|query| query.build_url()).unwrap();
+		assert_eq!(content_type(&upstream), "text/plain; charset=utf-8");
+
+		assert_eq!(content_type("https://x/query?format=text"), "text/plain; charset=utf-8");
+		assert_eq!(content_type("https://x/query?format=QuakeML"), "application/xml");
+		assert_eq!(content_type("https://x/query?starttime=2024-01-01"), "application/octet-stream");
+	}
+
+	#[test]
+	fn unsupported_formats_are_rejected() {
+		let usgs = UsgsClient::new();
+		let request = "https://earthquake.usgs.gov/fdsnws/event/1/query?format=text&starttime=2024-01-01";
+		let Err(err) = UsgsQuery::from_url(&usgs, request) else { panic!("format=text accepted") };
+		assert!(matches!(err, UsgsError::InvalidUrl(_)), "{:?}", err);
+		assert_eq!(error_response(err).status(), StatusCode::BAD_REQUEST);
+
+		let emsc = UsgsClient::with_profile(FdsnProfile::emsc());
+		assert!(UsgsQuery::from_url(&emsc, "https://www.seismicportal.eu/fdsnws/event/1/query?format=geojson").is_err());
+		assert!(UsgsQuery::from_url(&usgs, "https://earthquake.usgs.gov/fdsnws/event/1/query?format=GeoJSON").is_ok());
+	}
+}
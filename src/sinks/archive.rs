@@ -0,0 +1,246 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use chrono::{DateTime, Utc};
+use log::debug;
+use object_store::path::Path;
+use object_store::{ObjectStore, PutOptions, PutPayload};
+use crate::error::error::UsgsError;
+use crate::models::models::EarthquakeFeatures;
+use super::Sink;
+
+/// File format of archived objects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+	/// One GeoJSON `Feature` per line.
+	NdJson,
+
+	/// Flat Parquet table with one row per event (`parquet` feature).
+	#[cfg(feature = "parquet")]
+	Parquet,
+}
+
+impl ArchiveFormat {
+	fn extension(self) -> &'static str {
+		match self {
+			ArchiveFormat::NdJson => "ndjson",
+			#[cfg(feature = "parquet")]
+			ArchiveFormat::Parquet => "parquet",
+		}
+	}
+}
+
+/// Granularity of the time partitions objects are written to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Partitioning {
+	/// `year=2024/`
+	Year,
+
+	/// `year=2024/month=02/`
+	Month,
+
+	/// `year=2024/month=02/day=05/`
+	Day,
+
+	/// `year=2024/month=02/day=05/hour=13/`
+	Hour,
+}
+
+impl Partitioning {
+	/// Hive-style partition of an event time given in milliseconds since the
+	/// Unix epoch, or `year=unknown` without a valid time.
+	pub fn partition(self, time: Option<u64>) -> String {
+		let Some(time) = time.and_then(|ms| DateTime::<Utc>::from_timestamp_millis(ms as i64)) else {
+			return "year=unknown".to_string();
+		};
+		let format = match self {
+			Partitioning::Year => "year=%Y",
+			Partitioning::Month => "year=%Y/month=%m",
+			Partitioning::Day => "year=%Y/month=%m/day=%d",
+			Partitioning::Hour => "year=%Y/month=%m/day=%d/hour=%H",
+		};
+		time.format(format).to_string()
+	}
+}
+
+/// Writes events to S3-compatible (or any other `object_store`) storage as
+/// time-partitioned objects for long-term archiving.
+///
+/// Events are grouped by the partition of their origin time and each group
+/// is written as a new object
+/// `<prefix>/year=2024/month=02/day=05/part-<millis>-<first id>.ndjson`, so
+/// the archive can be read directly by engines understanding Hive-style
+/// partitions (Athena, DuckDB, Spark, ...). Objects are never overwritten;
+/// deduplicate the events beforehand, e.g. with a
+/// [`SeenEventStore`](crate::SeenEventStore).
+///
+/// ## Example
+/// ```rust,no_run
+/// use std::sync::Arc;
+/// use object_store::aws::AmazonS3Builder;
+/// use usgs_earthquake_api::UsgsClient;
+/// use usgs_earthquake_api::sinks::Sink;
+/// use usgs_earthquake_api::sinks::archive::ArchiveSink;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let s3 = AmazonS3Builder::from_env().with_bucket_name("quake-archive").build().unwrap();
+///     let sink = ArchiveSink::new(Arc::new(s3)).prefix("usgs/events");
+///     let response = UsgsClient::new().query().min_magnitude(2.5).fetch().await.unwrap();
+///     sink.send_all(&response.features).await.unwrap();
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ArchiveSink {
+	store: Arc<dyn ObjectStore>,
+	prefix: String,
+	format: ArchiveFormat,
+	partitioning: Partitioning,
+}
+
+impl ArchiveSink {
+	/// Creates a sink writing NDJSON objects partitioned by day to `store`.
+	pub fn new(store: Arc<dyn ObjectStore>) -> Self {
+		Self {
+			store,
+			prefix: String::new(),
+			format: ArchiveFormat::NdJson,
+			partitioning: Partitioning::Day,
+		}
+	}
+
+	/// Sets the path prefix objects are written under.
+	pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+		self.prefix = prefix.into().trim_matches('/').to_string();
+		self
+	}
+
+	/// Sets the object format (default NDJSON).
+	pub fn format(mut self, format: ArchiveFormat) -> Self {
+		self.format = format;
+		self
+	}
+
+	/// Sets the partition granularity (default daily).
+	pub fn partitioning(mut self, partitioning: Partitioning) -> Self {
+		self.partitioning = partitioning;
+		self
+	}
+
+	/// Location of a new object holding `events` of the given partition.
+	fn location(&self, partition: &str, events: &[&EarthquakeFeatures]) -> Path {
+		let first_id = events.first().map(|event| event.id.as_str()).unwrap_or("empty");
+		let name = format!("part-{}-{}.{}", Utc::now().timestamp_millis(), first_id, self.format.extension());
+		if self.prefix.is_empty() {
+			Path::from(format!("{}/{}", partition, name))
+		} else {
+			Path::from(format!("{}/{}/{}", self.prefix, partition, name))
+		}
+	}
+
+	fn encode(&self, events: &[&EarthquakeFeatures]) -> Result<Vec<u8>, UsgsError> {
+		match self.format {
+			ArchiveFormat::NdJson => {
+				let mut body = Vec::new();
+				for event in events {
+					serde_json::to_writer(&mut body, event)?;
+					body.push(b'\n');
+				}
+				Ok(body)
+			}
+			#[cfg(feature = "parquet")]
+			ArchiveFormat::Parquet => parquet::encode(events),
+		}
+	}
+
+	/// Writes `events` as one object per partition.
+	pub async fn write(&self, events: &[EarthquakeFeatures]) -> Result<(), UsgsError> {
+		let mut partitions: BTreeMap<String, Vec<&EarthquakeFeatures>> = BTreeMap::new();
+		for event in events {
+			partitions.entry(self.partitioning.partition(event.properties.time)).or_default().push(event);
+		}
+
+		for (partition, events) in partitions {
+			let location = self.location(&partition, &events);
+			let body = self.encode(&events)?;
+			debug!("archiving {} events to {}", events.len(), location);
+			self.store.put_opts(&location, PutPayload::from(body), PutOptions::default()).await
+				.map_err(|err| UsgsError::Archive(err.to_string()))?;
+		}
+		Ok(())
+	}
+}
+
+impl Sink for ArchiveSink {
+	async fn send(&self, event: &EarthquakeFeatures) -> Result<(), UsgsError> {
+		self.write(std::slice::from_ref(event)).await
+	}
+
+	async fn send_all(&self, events: &[EarthquakeFeatures]) -> Result<(), UsgsError> {
+		self.write(events).await
+	}
+}
+
+#[cfg(feature = "parquet")]
+mod parquet {
+	use std::sync::Arc;
+	use arrow_array::{ArrayRef, Float64Array, RecordBatch, StringArray, TimestampMillisecondArray, UInt32Array, UInt8Array};
+	use arrow_schema::{DataType, Field, Schema, TimeUnit};
+	use ::parquet::arrow::ArrowWriter;
+	use crate::error::error::UsgsError;
+	use crate::models::models::EarthquakeFeatures;
+
+	fn strings<'a>(events: &[&'a EarthquakeFeatures], field: impl Fn(&'a EarthquakeFeatures) -> Option<&'a str>) -> ArrayRef {
+		Arc::new(events.iter().map(|event| field(event)).collect::<StringArray>())
+	}
+
+	/// Encodes the events as a Parquet file with one row per event.
+	pub(super) fn encode(events: &[&EarthquakeFeatures]) -> Result<Vec<u8>, UsgsError> {
+		let schema = Arc::new(Schema::new(vec![
+			Field::new("id", DataType::Utf8, false),
+			Field::new("time", DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())), true),
+			Field::new("updated", DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())), true),
+			Field::new("latitude", DataType::Float64, false),
+			Field::new("longitude", DataType::Float64, false),
+			Field::new("depth_km", DataType::Float64, false),
+			Field::new("magnitude", DataType::Float64, true),
+			Field::new("magnitude_type", DataType::Utf8, true),
+			Field::new("place", DataType::Utf8, true),
+			Field::new("alert", DataType::Utf8, true),
+			Field::new("status", DataType::Utf8, true),
+			Field::new("tsunami", DataType::UInt8, true),
+			Field::new("sig", DataType::UInt32, true),
+			Field::new("felt", DataType::UInt32, true),
+			Field::new("net", DataType::Utf8, true),
+			Field::new("event_type", DataType::Utf8, true),
+			Field::new("url", DataType::Utf8, true),
+		]));
+
+		let columns: Vec<ArrayRef> = vec![
+			strings(events, |event| Some(event.id.as_str())),
+			Arc::new(events.iter().map(|event| event.properties.time.map(|ms| ms as i64)).collect::<TimestampMillisecondArray>().with_timezone("UTC")),
+			Arc::new(events.iter().map(|event| event.properties.updated_time.map(|ms| ms as i64)).collect::<TimestampMillisecondArray>().with_timezone("UTC")),
+			Arc::new(events.iter().map(|event| event.geometry.latitude()).collect::<Float64Array>()),
+			Arc::new(events.iter().map(|event| event.geometry.longitude()).collect::<Float64Array>()),
+			Arc::new(events.iter().map(|event| event.geometry.depth_km()).collect::<Float64Array>()),
+			Arc::new(events.iter().map(|event| event.properties.magnitude).collect::<Float64Array>()),
+			strings(events, |event| event.properties.magnitude_type.as_deref()),
+			strings(events, |event| event.properties.place.as_deref()),
+			strings(events, |event| event.properties.alert_level.as_deref()),
+			strings(events, |event| event.properties.status.as_deref()),
+			Arc::new(events.iter().map(|event| event.properties.tsunami).collect::<UInt8Array>()),
+			Arc::new(events.iter().map(|event| event.properties.sig).collect::<UInt32Array>()),
+			Arc::new(events.iter().map(|event| event.properties.felt).collect::<UInt32Array>()),
+			strings(events, |event| event.properties.net.as_deref()),
+			strings(events, |event| event.properties.event_type.as_deref()),
+			strings(events, |event| event.properties.url.as_deref()),
+		];
+
+		let parquet_error = |err: &dyn std::fmt::Display| UsgsError::Archive(err.to_string());
+		let batch = RecordBatch::try_new(schema.clone(), columns).map_err(|err| parquet_error(&err))?;
+		let mut body = Vec::new();
+		let mut writer = ArrowWriter::try_new(&mut body, schema, None).map_err(|err| parquet_error(&err))?;
+		writer.write(&batch).map_err(|err| parquet_error(&err))?;
+		writer.close().map_err(|err| parquet_error(&err))?;
+		Ok(body)
+	}
+}
\ No newline at end of file
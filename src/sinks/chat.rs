@@ -0,0 +1,256 @@
+use std::collections::HashMap;
+use chrono::{DateTime, SecondsFormat, Utc};
+use log::debug;
+use reqwest::Client;
+use serde_json::{json, Value};
+use crate::error::error::UsgsError;
+use crate::models::models::EarthquakeFeatures;
+use crate::notify::{summary, MessageFormat, MessageTemplate};
+use crate::AlertLevel;
+use super::Sink;
+
+/// Webhook URLs events are posted to, chosen by their PAGER alert level.
+///
+/// Events whose level has no route of its own (including events without an
+/// alert level) go to the default URL, or are skipped if there is none.
+///
+/// ## Example
+/// ```rust
+/// use usgs_earthquake_api::AlertLevel;
+/// use usgs_earthquake_api::sinks::chat::AlertRoutes;
+///
+/// let routes = AlertRoutes::new("https://discord.com/api/webhooks/1/monitoring")
+///     .route(AlertLevel::Orange, "https://discord.com/api/webhooks/2/on-call")
+///     .route(AlertLevel::Red, "https://discord.com/api/webhooks/2/on-call");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct AlertRoutes {
+	default: Option<String>,
+	routes: HashMap<AlertLevel, String>,
+}
+
+impl AlertRoutes {
+	/// Routes every event to `url`.
+	pub fn new(url: impl Into<String>) -> Self {
+		Self {
+			default: Some(url.into()),
+			routes: HashMap::new(),
+		}
+	}
+
+	/// Routes only events with an explicitly routed alert level.
+	pub fn only_routed() -> Self {
+		Self::default()
+	}
+
+	/// Routes events with the given alert level to `url`.
+	///
+	/// [`AlertLevel::All`] replaces the default URL.
+	pub fn route(mut self, level: AlertLevel, url: impl Into<String>) -> Self {
+		match level {
+			AlertLevel::All => self.default = Some(url.into()),
+			level => {
+				self.routes.insert(level, url.into());
+			}
+		}
+		self
+	}
+
+	/// URL an event is posted to, if any.
+	pub fn url_for(&self, event: &EarthquakeFeatures) -> Option<&str> {
+		event.alert()
+			.and_then(|level| self.routes.get(&level))
+			.or(self.default.as_ref())
+			.map(String::as_str)
+	}
+}
+
+/// Embed/attachment color of an event by alert level, grey without one.
+fn alert_color(event: &EarthquakeFeatures) -> u32 {
+	match event.properties.alert_level.as_deref() {
+		Some("green") => 0x2ecc71,
+		Some("yellow") => 0xf1c40f,
+		Some("orange") => 0xe67e22,
+		Some("red") => 0xe74c3c,
+		_ => 0x95a5a6,
+	}
+}
+
+fn iso_time(event: &EarthquakeFeatures) -> Option<String> {
+	event.properties.time
+		.and_then(|ms| DateTime::<Utc>::from_timestamp_millis(ms as i64))
+		.map(|time| time.to_rfc3339_opts(SecondsFormat::Secs, true))
+}
+
+async fn post(client: &Client, url: &str, event: &EarthquakeFeatures, payload: &Value) -> Result<(), UsgsError> {
+	debug!("posting {} to chat webhook", event.id);
+	client.post(url).json(payload).send().await?.error_for_status()?;
+	Ok(())
+}
+
+/// Posts events as embeds to Discord webhooks.
+///
+/// Each event becomes an embed titled with magnitude and place, linking to
+/// the event page and colored by alert level. The description is rendered
+/// from a [`MessageTemplate`] (the one-line summary by default).
+///
+/// ## Example
+/// ```rust,no_run
+/// use usgs_earthquake_api::UsgsClient;
+/// use usgs_earthquake_api::sinks::Sink;
+/// use usgs_earthquake_api::sinks::chat::{AlertRoutes, DiscordSink};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let sink = DiscordSink::new(AlertRoutes::new("https://discord.com/api/webhooks/1/token"));
+///     let response = UsgsClient::new().query().min_magnitude(5.0).fetch().await.unwrap();
+///     sink.send_all(&response.features).await.unwrap();
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct DiscordSink {
+	client: Client,
+	routes: AlertRoutes,
+	template: MessageTemplate,
+	username: Option<String>,
+}
+
+impl DiscordSink {
+	/// Creates a sink posting to the routed webhooks.
+	pub fn new(routes: AlertRoutes) -> Self {
+		Self {
+			client: Client::new(),
+			routes,
+			template: MessageTemplate::default().format(MessageFormat::Markdown),
+			username: None,
+		}
+	}
+
+	/// Sets the template of the embed description, rendered as Markdown.
+	pub fn template(mut self, template: MessageTemplate) -> Self {
+		self.template = template.format(MessageFormat::Markdown);
+		self
+	}
+
+	/// Overrides the name the webhook posts as.
+	pub fn username(mut self, username: impl Into<String>) -> Self {
+		self.username = Some(username.into());
+		self
+	}
+
+	/// Uses a preconfigured HTTP client (timeouts, proxies, TLS, ...).
+	pub fn http_client(mut self, client: Client) -> Self {
+		self.client = client;
+		self
+	}
+
+	/// Webhook payload of an event.
+	pub fn payload(&self, event: &EarthquakeFeatures) -> Value {
+		let properties = &event.properties;
+		let title = match (properties.magnitude, &properties.place) {
+			(Some(magnitude), Some(place)) => format!("M {:.1} — {}", magnitude, place),
+			(Some(magnitude), None) => format!("M {:.1}", magnitude),
+			(None, place) => place.clone().unwrap_or_else(|| event.id.to_string()),
+		};
+		let mut embed = json!({
+			"title": title,
+			"description": self.template.render(event),
+			"color": alert_color(event),
+			"footer": { "text": event.id },
+		});
+		if let Some(url) = &properties.url {
+			embed["url"] = json!(url);
+		}
+		if let Some(time) = iso_time(event) {
+			embed["timestamp"] = json!(time);
+		}
+
+		let mut payload = json!({ "embeds": [embed] });
+		if let Some(username) = &self.username {
+			payload["username"] = json!(username);
+		}
+		payload
+	}
+}
+
+impl Sink for DiscordSink {
+	async fn send(&self, event: &EarthquakeFeatures) -> Result<(), UsgsError> {
+		match self.routes.url_for(event) {
+			Some(url) => post(&self.client, url, event, &self.payload(event)).await,
+			None => Ok(()),
+		}
+	}
+}
+
+/// Posts events to Slack incoming webhooks.
+///
+/// Each event becomes a message with a colored attachment holding the text
+/// rendered from a [`MessageTemplate`] in Slack `mrkdwn` (the one-line
+/// summary by default).
+///
+/// ## Example
+/// ```rust,no_run
+/// use usgs_earthquake_api::{AlertLevel, UsgsClient};
+/// use usgs_earthquake_api::sinks::Sink;
+/// use usgs_earthquake_api::sinks::chat::{AlertRoutes, SlackSink};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let routes = AlertRoutes::only_routed().route(AlertLevel::Red, "https://hooks.slack.com/services/T0/B0/x");
+///     let sink = SlackSink::new(routes);
+///     let response = UsgsClient::new().query().min_magnitude(5.0).fetch().await.unwrap();
+///     sink.send_all(&response.features).await.unwrap();
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SlackSink {
+	client: Client,
+	routes: AlertRoutes,
+	template: MessageTemplate,
+}
+
+impl SlackSink {
+	/// Creates a sink posting to the routed webhooks.
+	pub fn new(routes: AlertRoutes) -> Self {
+		Self {
+			client: Client::new(),
+			routes,
+			template: MessageTemplate::default().format(MessageFormat::Slack),
+		}
+	}
+
+	/// Sets the template of the message text, rendered as Slack `mrkdwn`.
+	pub fn template(mut self, template: MessageTemplate) -> Self {
+		self.template = template.format(MessageFormat::Slack);
+		self
+	}
+
+	/// Uses a preconfigured HTTP client (timeouts, proxies, TLS, ...).
+	pub fn http_client(mut self, client: Client) -> Self {
+		self.client = client;
+		self
+	}
+
+	/// Webhook payload of an event.
+	pub fn payload(&self, event: &EarthquakeFeatures) -> Value {
+		let mut attachment = json!({
+			"color": format!("#{:06x}", alert_color(event)),
+			"text": self.template.render(event),
+			"fallback": summary(event, MessageFormat::Text),
+			"footer": event.id,
+		});
+		if let Some(time) = event.properties.time {
+			attachment["ts"] = json!(time / 1000);
+		}
+		json!({ "attachments": [attachment] })
+	}
+}
+
+impl Sink for SlackSink {
+	async fn send(&self, event: &EarthquakeFeatures) -> Result<(), UsgsError> {
+		match self.routes.url_for(event) {
+			Some(url) => post(&self.client, url, event, &self.payload(event)).await,
+			None => Ok(()),
+		}
+	}
+}
\ No newline at end of file
@@ -0,0 +1,11 @@
+mod sinks;
+#[cfg(feature = "archive")]
+pub mod archive;
+#[cfg(feature = "chat")]
+pub mod chat;
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
+#[cfg(feature = "webhook")]
+pub mod webhook;
+
+pub use sinks::*;
\ No newline at end of file
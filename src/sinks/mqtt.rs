@@ -0,0 +1,128 @@
+use log::debug;
+#[cfg(feature = "country-filter")]
+use country_boundaries::CountryBoundaries;
+#[cfg(feature = "country-filter")]
+use crate::country::country::{country_at, embedded_boundaries};
+use rumqttc::AsyncClient;
+use crate::error::error::UsgsError;
+use crate::models::models::EarthquakeFeatures;
+use super::Sink;
+
+pub use rumqttc::{MqttOptions, QoS};
+
+/// Default topic template of [`MqttSink`].
+pub const DEFAULT_TOPIC: &str = "quakes/{country}/{mag_bucket}";
+
+/// Publishes each event as a GeoJSON `Feature` to an MQTT broker.
+///
+/// The topic is rendered from a template with these placeholders:
+/// - `{country}`: ISO 3166-1 alpha-2 code of the epicenter (`country-filter`
+///   feature), `unknown` offshore or without the feature
+/// - `{mag_bucket}`: integer part of the magnitude (`5` for M 5.7), or `unknown`
+/// - `{net}`: contributing network
+/// - `{id}`: event id
+///
+/// The crate enables no TLS backend for `rumqttc`; enable one of its
+/// `use-rustls`/`use-native-tls` features in your own manifest for `mqtts`.
+///
+/// ## Example
+/// ```rust,no_run
+/// use usgs_earthquake_api::UsgsClient;
+/// use usgs_earthquake_api::sinks::Sink;
+/// use usgs_earthquake_api::sinks::mqtt::{MqttOptions, MqttSink};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let sink = MqttSink::connect(MqttOptions::new("quake-alarm", "broker.local", 1883));
+///     let response = UsgsClient::new().query().min_magnitude(4.0).fetch().await.unwrap();
+///     sink.send_all(&response.features).await.unwrap();
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct MqttSink {
+	client: AsyncClient,
+	topic: String,
+	qos: QoS,
+	retain: bool,
+	#[cfg(feature = "country-filter")]
+	boundaries: std::sync::Arc<CountryBoundaries>,
+}
+
+impl MqttSink {
+	/// Connects to the broker and drives the connection on a background
+	/// tokio task, reconnecting on errors.
+	///
+	/// Must be called from within a tokio runtime.
+	pub fn connect(options: MqttOptions) -> Self {
+		let (client, mut event_loop) = AsyncClient::new(options, 64);
+		tokio::spawn(async move {
+			loop {
+				if let Err(err) = event_loop.poll().await {
+					debug!("MQTT connection error: {}", err);
+					tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+				}
+			}
+		});
+		Self::from_client(client)
+	}
+
+	/// Uses an existing client; the caller is responsible for polling its
+	/// event loop.
+	pub fn from_client(client: AsyncClient) -> Self {
+		Self {
+			client,
+			topic: DEFAULT_TOPIC.to_string(),
+			qos: QoS::AtLeastOnce,
+			retain: false,
+			#[cfg(feature = "country-filter")]
+			boundaries: std::sync::Arc::new(embedded_boundaries()),
+		}
+	}
+
+	/// Sets the topic template (default [`DEFAULT_TOPIC`]).
+	pub fn topic(mut self, template: impl Into<String>) -> Self {
+		self.topic = template.into();
+		self
+	}
+
+	/// Sets the quality of service (default at least once).
+	pub fn qos(mut self, qos: QoS) -> Self {
+		self.qos = qos;
+		self
+	}
+
+	/// Publishes retained messages, so new subscribers get the latest event.
+	pub fn retain(mut self, retain: bool) -> Self {
+		self.retain = retain;
+		self
+	}
+
+	#[cfg(feature = "country-filter")]
+	fn country(&self, event: &EarthquakeFeatures) -> Option<String> {
+		event.country.clone()
+			.or_else(|| country_at(&self.boundaries, event.geometry.latitude(), event.geometry.longitude()))
+	}
+
+	#[cfg(not(feature = "country-filter"))]
+	fn country(&self, _event: &EarthquakeFeatures) -> Option<String> {
+		None
+	}
+
+	/// Topic an event is published to.
+	pub fn topic_for(&self, event: &EarthquakeFeatures) -> String {
+		let mag_bucket = event.properties.magnitude.map(|m| (m.floor() as i32).to_string());
+		self.topic
+			.replace("{country}", self.country(event).as_deref().unwrap_or("unknown"))
+			.replace("{mag_bucket}", mag_bucket.as_deref().unwrap_or("unknown"))
+			.replace("{net}", event.properties.net.as_deref().unwrap_or("unknown"))
+			.replace("{id}", &event.id)
+	}
+}
+
+impl Sink for MqttSink {
+	async fn send(&self, event: &EarthquakeFeatures) -> Result<(), UsgsError> {
+		let payload = serde_json::to_vec(event)?;
+		self.client.publish(self.topic_for(event), self.qos, self.retain, payload).await
+			.map_err(|err| UsgsError::Mqtt(err.to_string()))
+	}
+}
\ No newline at end of file
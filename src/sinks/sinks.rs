@@ -0,0 +1,26 @@
+use std::future::Future;
+use crate::error::error::UsgsError;
+use crate::models::models::EarthquakeFeatures;
+
+/// Destination that new events are forwarded to, such as a webhook or a
+/// message broker.
+///
+/// Sinks are fed by the caller, typically with the events a polling loop has
+/// not seen before.
+pub trait Sink {
+	/// Delivers a single event.
+	fn send(&self, event: &EarthquakeFeatures) -> impl Future<Output = Result<(), UsgsError>> + Send;
+
+	/// Delivers the events one after another, stopping at the first failure.
+	fn send_all(&self, events: &[EarthquakeFeatures]) -> impl Future<Output = Result<(), UsgsError>> + Send
+	where
+		Self: Sync,
+	{
+		async move {
+			for event in events {
+				self.send(event).await?;
+			}
+			Ok(())
+		}
+	}
+}
\ No newline at end of file
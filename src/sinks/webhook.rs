@@ -0,0 +1,127 @@
+use std::fmt::Write;
+use std::sync::Arc;
+use hmac::{Hmac, KeyInit, Mac};
+use log::debug;
+use reqwest::Client;
+use reqwest::header::CONTENT_TYPE;
+use sha2::Sha256;
+use crate::error::error::UsgsError;
+use crate::models::models::EarthquakeFeatures;
+use crate::retry::retry::{ExponentialBackoff, RetryCause, RetryPolicy};
+use super::Sink;
+
+/// Header carrying the `sha256=<hex>` HMAC of the request body.
+pub const SIGNATURE_HEADER: &str = "X-Signature-256";
+
+/// Header carrying the event id.
+pub const EVENT_ID_HEADER: &str = "X-Event-Id";
+
+/// POSTs each event as a GeoJSON `Feature` to a configurable URL.
+///
+/// Failed deliveries are retried according to the [`RetryPolicy`] (by default
+/// 3 retries with exponential backoff). When a secret is configured, every
+/// request is signed with HMAC-SHA256 over the body, sent as
+/// `X-Signature-256: sha256=<hex>` so receivers can authenticate it.
+///
+/// ## Example
+/// ```rust,no_run
+/// use usgs_earthquake_api::UsgsClient;
+/// use usgs_earthquake_api::sinks::Sink;
+/// use usgs_earthquake_api::sinks::webhook::WebhookSink;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let sink = WebhookSink::new("https://alerts.example.com/quakes").secret("s3cret");
+///     let response = UsgsClient::new().query().min_magnitude(5.0).fetch().await.unwrap();
+///     sink.send_all(&response.features).await.unwrap();
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct WebhookSink {
+	client: Client,
+	url: String,
+	secret: Option<Vec<u8>>,
+	retry_policy: Arc<dyn RetryPolicy>,
+}
+
+impl WebhookSink {
+	/// Creates a sink POSTing to `url`.
+	pub fn new(url: impl Into<String>) -> Self {
+		Self {
+			client: Client::new(),
+			url: url.into(),
+			secret: None,
+			retry_policy: Arc::new(ExponentialBackoff::new(3, std::time::Duration::from_millis(500))),
+		}
+	}
+
+	/// Signs requests with HMAC-SHA256 using `secret`.
+	pub fn secret(mut self, secret: impl AsRef<[u8]>) -> Self {
+		self.secret = Some(secret.as_ref().to_vec());
+		self
+	}
+
+	/// Sets the delivery retry policy.
+	pub fn retry_policy(mut self, policy: impl RetryPolicy + 'static) -> Self {
+		self.retry_policy = Arc::new(policy);
+		self
+	}
+
+	/// Uses a preconfigured HTTP client (timeouts, proxies, TLS, ...).
+	pub fn http_client(mut self, client: Client) -> Self {
+		self.client = client;
+		self
+	}
+
+	/// Target URL.
+	pub fn url(&self) -> &str {
+		&self.url
+	}
+
+	/// `sha256=<hex>` signature of `body`, if a secret is configured.
+	pub fn signature(&self, body: &[u8]) -> Option<String> {
+		let secret = self.secret.as_ref()?;
+		let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts keys of any length");
+		mac.update(body);
+		let mut signature = String::from("sha256=");
+		for byte in mac.finalize().into_bytes() {
+			let _ = write!(signature, "{:02x}", byte);
+		}
+		Some(signature)
+	}
+}
+
+impl Sink for WebhookSink {
+	async fn send(&self, event: &EarthquakeFeatures) -> Result<(), UsgsError> {
+		let body = serde_json::to_vec(event)?;
+		let signature = self.signature(&body);
+
+		let mut attempt = 0;
+		loop {
+			let mut request = self.client.post(&self.url)
+				.header(CONTENT_TYPE, "application/json")
+				.header(EVENT_ID_HEADER, event.id.as_str())
+				.body(body.clone());
+			if let Some(signature) = &signature {
+				request = request.header(SIGNATURE_HEADER, signature);
+			}
+
+			let result = request.send().await;
+			let retryable = match &result {
+				Ok(response) if response.status().is_success() => false,
+				Ok(response) => self.retry_policy.is_retryable(&RetryCause::Status(response.status())),
+				Err(err) => self.retry_policy.is_retryable(&RetryCause::Error(err)),
+			};
+
+			if !retryable || attempt >= self.retry_policy.max_retries() {
+				result?.error_for_status()?;
+				return Ok(());
+			}
+
+			let delay = self.retry_policy.backoff(attempt);
+			debug!("webhook delivery of {} failed, retrying in {:?}", event.id, delay);
+			tokio::time::sleep(delay).await;
+			attempt += 1;
+		}
+	}
+}
\ No newline at end of file
@@ -0,0 +1 @@
+pub mod spatial;
\ No newline at end of file
@@ -0,0 +1,70 @@
+use rstar::primitives::GeomWithData;
+use rstar::RTree;
+use crate::models::models::{EarthquakeFeatures, EarthquakeResponse, EARTH_RADIUS_KM};
+
+type IndexedPoint = GeomWithData<[f64; 3], usize>;
+
+/// Projects a latitude/longitude pair onto a sphere of Earth's radius.
+///
+/// Straight-line (chord) distance between projected points grows with the
+/// great-circle distance, so nearest-neighbour order is preserved.
+fn to_cartesian(latitude: f64, longitude: f64) -> [f64; 3] {
+	let (lat, lon) = (latitude.to_radians(), longitude.to_radians());
+	[
+		EARTH_RADIUS_KM * lat.cos() * lon.cos(),
+		EARTH_RADIUS_KM * lat.cos() * lon.sin(),
+		EARTH_RADIUS_KM * lat.sin(),
+	]
+}
+
+/// R-tree over the epicenters of a response, for repeated proximity lookups.
+///
+/// Created with [`EarthquakeResponse::build_spatial_index`].
+pub struct SpatialIndex<'a> {
+	tree: RTree<IndexedPoint>,
+	features: &'a [EarthquakeFeatures],
+}
+
+impl<'a> SpatialIndex<'a> {
+	/// Returns the `k` events closest to a point, nearest first.
+	pub fn nearest(&self, latitude: f64, longitude: f64, k: usize) -> Vec<&'a EarthquakeFeatures> {
+		self.tree.nearest_neighbor_iter(to_cartesian(latitude, longitude))
+			.take(k)
+			.map(|point| &self.features[point.data])
+			.collect()
+	}
+
+	/// Returns every event whose epicenter lies within `radius_km` of a point.
+	pub fn within_radius(&self, latitude: f64, longitude: f64, radius_km: f64) -> Vec<&'a EarthquakeFeatures> {
+		let angle = (radius_km / EARTH_RADIUS_KM).min(std::f64::consts::PI);
+		let chord = 2.0 * EARTH_RADIUS_KM * (angle / 2.0).sin();
+		self.tree.locate_within_distance(to_cartesian(latitude, longitude), chord * chord)
+			.map(|point| &self.features[point.data])
+			.collect()
+	}
+
+	/// Number of indexed events.
+	pub fn len(&self) -> usize {
+		self.tree.size()
+	}
+
+	/// Returns `true` if no events are indexed.
+	pub fn is_empty(&self) -> bool {
+		self.tree.size() == 0
+	}
+}
+
+impl EarthquakeResponse {
+	/// Builds an R-tree over the epicenters supporting k-nearest and
+	/// within-radius queries.
+	pub fn build_spatial_index(&self) -> SpatialIndex<'_> {
+		let points = self.features.iter().enumerate()
+			.map(|(index, feature)| IndexedPoint::new(to_cartesian(feature.geometry.latitude(), feature.geometry.longitude()), index))
+			.collect();
+
+		SpatialIndex {
+			tree: RTree::bulk_load(points),
+			features: &self.features,
+		}
+	}
+}
\ No newline at end of file
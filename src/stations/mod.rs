@@ -0,0 +1,3 @@
+mod stations;
+
+pub use stations::*;
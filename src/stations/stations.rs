@@ -0,0 +1,198 @@
+use chrono::NaiveDateTime;
+use log::debug;
+use reqwest::{Client, StatusCode, Url};
+use crate::error::error::UsgsError;
+use crate::models::ids::NetworkCode;
+use crate::models::models::{haversine_km, initial_bearing, EarthquakeFeatures, EARTH_RADIUS_KM};
+use crate::parse_url_time;
+
+/// Station service of the EarthScope (IRIS) data center.
+pub const IRIS_STATION_URL: &str = "https://service.iris.edu/fdsnws/station/1/query";
+
+/// Seismic station from an FDSN station service.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Station {
+	/// Network the station belongs to (e.g. `IU`).
+	pub network: NetworkCode,
+
+	/// Station code within the network (e.g. `ANMO`).
+	pub code: String,
+
+	/// Latitude in degrees.
+	pub latitude: f64,
+
+	/// Longitude in degrees.
+	pub longitude: f64,
+
+	/// Elevation above sea level in meters.
+	pub elevation_m: f64,
+
+	/// Description of the site.
+	pub site_name: String,
+
+	/// Start of operation.
+	pub start_time: Option<NaiveDateTime>,
+
+	/// End of operation; operating stations have none or one far in the
+	/// future (e.g. `2599-12-31`).
+	pub end_time: Option<NaiveDateTime>,
+}
+
+impl Station {
+	/// `NET.STA` identifier, as used by picks and arrivals.
+	pub fn id(&self) -> String {
+		format!("{}.{}", self.network, self.code)
+	}
+}
+
+/// Source-receiver geometry between an event and a station, see
+/// [`EarthquakeFeatures::path_to_station`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StationPath {
+	/// Epicentral distance in kilometers.
+	pub distance_km: f64,
+
+	/// Epicentral distance in degrees of arc.
+	pub distance_degrees: f64,
+
+	/// Direction from the epicenter to the station, degrees clockwise from north.
+	pub azimuth: f64,
+
+	/// Direction from the station to the epicenter, degrees clockwise from north.
+	pub back_azimuth: f64,
+}
+
+impl EarthquakeFeatures {
+	/// Distance and azimuths between the epicenter and a station, e.g. to
+	/// recompute the geometry of picks or to plot recording stations.
+	pub fn path_to_station(&self, station: &Station) -> StationPath {
+		let epicenter = (self.geometry.latitude(), self.geometry.longitude());
+		let receiver = (station.latitude, station.longitude);
+		let distance_km = haversine_km(epicenter, receiver);
+		let bearing = |from, to| initial_bearing(from, to).to_degrees().rem_euclid(360.0);
+		StationPath {
+			distance_km,
+			distance_degrees: (distance_km / EARTH_RADIUS_KM).to_degrees(),
+			azimuth: bearing(epicenter, receiver),
+			back_azimuth: bearing(receiver, epicenter),
+		}
+	}
+}
+
+/// Client of an FDSN station web service (`fdsnws/station/1`), to join
+/// picks and arrivals of events with station coordinates.
+///
+/// ## Example
+/// ```rust,no_run
+/// use usgs_earthquake_api::UsgsClient;
+/// use usgs_earthquake_api::stations::StationService;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let client = UsgsClient::new();
+///     let event = client.event("us6000jllz").await.unwrap();
+///     let stations = StationService::new().stations_near(event.geometry.latitude(), event.geometry.longitude(), 500.0).await.unwrap();
+///     for station in &stations {
+///         let path = event.path_to_station(station);
+///         println!("{} {:.0} km, azimuth {:.0}°", station.id(), path.distance_km, path.azimuth);
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct StationService {
+	client: Client,
+	base_url: String,
+}
+
+impl Default for StationService {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl StationService {
+	/// Creates a client of the EarthScope (IRIS) station service.
+	pub fn new() -> Self {
+		Self::with_base_url(IRIS_STATION_URL)
+	}
+
+	/// Creates a client of the station service `query` endpoint at `base_url`.
+	pub fn with_base_url(base_url: &str) -> Self {
+		Self {
+			client: Client::new(),
+			base_url: base_url.to_string(),
+		}
+	}
+
+	/// Uses a preconfigured HTTP client (timeouts, proxies, TLS, ...).
+	pub fn http_client(mut self, client: Client) -> Self {
+		self.client = client;
+		self
+	}
+
+	/// Stations matching network and station codes, which may contain the
+	/// FDSN wildcards `*` and `?` and comma-separated lists.
+	pub async fn stations(&self, network: &str, station: &str) -> Result<Vec<Station>, UsgsError> {
+		self.query(&[("net", network.to_string()), ("sta", station.to_string())]).await
+	}
+
+	/// Stations within `radius_km` of a point.
+	pub async fn stations_near(&self, latitude: f64, longitude: f64, radius_km: f64) -> Result<Vec<Station>, UsgsError> {
+		if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+			return Err(UsgsError::InvalidCoordinates);
+		}
+		if radius_km < 0.0 {
+			return Err(UsgsError::InvalidRadius);
+		}
+		self.query(&[
+			("latitude", latitude.to_string()),
+			("longitude", longitude.to_string()),
+			("maxradius", (radius_km / EARTH_RADIUS_KM).to_degrees().to_string()),
+		]).await
+	}
+
+	async fn query(&self, parameters: &[(&str, String)]) -> Result<Vec<Station>, UsgsError> {
+		let mut url = Url::parse(&self.base_url).map_err(|e| UsgsError::InvalidUrl(e.to_string()))?;
+		url.query_pairs_mut()
+			.extend_pairs(parameters)
+			.append_pair("level", "station")
+			.append_pair("format", "text");
+		debug!("querying stations {}", url);
+
+		let response = self.client.get(url).send().await.map_err(UsgsError::request)?;
+		let status = response.status();
+		let body = response.text().await?;
+		match status {
+			StatusCode::NO_CONTENT | StatusCode::NOT_FOUND => Ok(Vec::new()),
+			status if status.is_success() => parse_station_text(&body),
+			status => Err(UsgsError::api(status, &body)),
+		}
+	}
+}
+
+/// Parses the `level=station` text format,
+/// `Network|Station|Latitude|Longitude|Elevation|SiteName|StartTime|EndTime`.
+fn parse_station_text(body: &str) -> Result<Vec<Station>, UsgsError> {
+	body.lines()
+		.map(str::trim)
+		.filter(|line| !line.is_empty() && !line.starts_with('#'))
+		.map(|line| {
+			let columns: Vec<&str> = line.split('|').map(str::trim).collect();
+			if columns.len() < 6 {
+				return Err(UsgsError::TextFormat(format!("expected at least 6 station columns, found {}", columns.len())));
+			}
+			let number = |index: usize, name: &str| columns[index].parse::<f64>()
+				.map_err(|_| UsgsError::TextFormat(format!("invalid station {} `{}`", name, columns[index])));
+			Ok(Station {
+				network: NetworkCode::from(columns[0]),
+				code: columns[1].to_string(),
+				latitude: number(2, "latitude")?,
+				longitude: number(3, "longitude")?,
+				elevation_m: number(4, "elevation")?,
+				site_name: columns[5].to_string(),
+				start_time: columns.get(6).and_then(|time| parse_url_time(time)),
+				end_time: columns.get(7).and_then(|time| parse_url_time(time)),
+			})
+		})
+		.collect()
+}
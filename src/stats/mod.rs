@@ -0,0 +1,3 @@
+mod stats;
+
+pub use stats::*;
\ No newline at end of file
@@ -0,0 +1,1093 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeDelta, Utc};
+use crate::{parse_url_time, AlertLevel};
+use crate::models::models::{haversine_km, initial_bearing, EarthquakeFeatures, EarthquakeResponse, Unit, EARTH_RADIUS_KM};
+
+/// A single bin of a depth histogram.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DepthBin {
+	/// Lower bound of the bin in kilometers (inclusive).
+	pub min_km: f64,
+
+	/// Upper bound of the bin in kilometers (exclusive, except for the last bin).
+	pub max_km: f64,
+
+	/// Number of events in the bin.
+	pub count: usize,
+}
+
+/// Builds a histogram of hypocenter depths with `bins` equal-width bins
+/// spanning the shallowest to the deepest event.
+///
+/// Returns an empty vector if there are no events or `bins` is 0.
+pub fn depth_histogram(response: &EarthquakeResponse, bins: usize) -> Vec<DepthBin> {
+	let depths: Vec<f64> = response.features.iter().map(|f| f.geometry.depth_km()).collect();
+	if depths.is_empty() || bins == 0 {
+		return Vec::new();
+	}
+
+	let min = depths.iter().copied().fold(f64::INFINITY, f64::min);
+	let max = depths.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+	let width = if max > min { (max - min) / bins as f64 } else { 1.0 };
+
+	let mut histogram: Vec<DepthBin> = (0..bins)
+		.map(|i| DepthBin {
+			min_km: min + width * i as f64,
+			max_km: min + width * (i + 1) as f64,
+			count: 0,
+		})
+		.collect();
+
+	for depth in depths {
+		let index = (((depth - min) / width) as usize).min(bins - 1);
+		histogram[index].count += 1;
+	}
+	histogram
+}
+
+/// A hypocenter projected onto a vertical cross-section plane.
+#[derive(Debug, Clone)]
+pub struct ProfilePoint<'a> {
+	/// The projected event.
+	pub feature: &'a EarthquakeFeatures,
+
+	/// Distance along the profile line from its start, in kilometers.
+	pub along_km: f64,
+
+	/// Signed perpendicular distance from the profile line in kilometers
+	/// (positive to the right when looking from start to end).
+	pub offset_km: f64,
+
+	/// Depth of the hypocenter in kilometers.
+	pub depth_km: f64,
+}
+
+/// Projects hypocenters onto the vertical plane through the great circle
+/// from `start` to `end` (`(latitude, longitude)` pairs).
+///
+/// Only events within `half_width_km` of the line and between its end points
+/// are kept, ordered by distance along the profile. Useful for visualizing
+/// subduction-zone geometry.
+pub fn cross_section(response: &EarthquakeResponse, start: (f64, f64), end: (f64, f64), half_width_km: f64) -> Vec<ProfilePoint<'_>> {
+	let length = haversine_km(start, end);
+	let line_bearing = initial_bearing(start, end);
+
+	let mut points: Vec<ProfilePoint<'_>> = response.features.iter()
+		.filter_map(|feature| {
+			let point = (feature.geometry.latitude(), feature.geometry.longitude());
+			let distance = haversine_km(start, point) / EARTH_RADIUS_KM;
+			let bearing = initial_bearing(start, point);
+
+			let cross_track = (distance.sin() * (bearing - line_bearing).sin()).asin();
+			let along_track = (distance.cos() / cross_track.cos()).clamp(-1.0, 1.0).acos()
+				* (bearing - line_bearing).cos().signum();
+
+			let along_km = along_track * EARTH_RADIUS_KM;
+			let offset_km = cross_track * EARTH_RADIUS_KM;
+			if offset_km.abs() > half_width_km || along_km < 0.0 || along_km > length {
+				return None;
+			}
+
+			Some(ProfilePoint {
+				feature,
+				along_km,
+				offset_km,
+				depth_km: feature.geometry.depth(Unit::Kilometers),
+			})
+		})
+		.collect();
+
+	points.sort_by(|a, b| a.along_km.total_cmp(&b.along_km));
+	points
+}
+
+const MILLIS_PER_DAY: f64 = 86_400_000.0;
+
+/// Parameters of the modified Omori law `n(t) = K / (t + c)^p`, with `t`
+/// in days since the mainshock.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OmoriFit {
+	/// Productivity (events per day at `t + c = 1`).
+	pub k: f64,
+
+	/// Time offset in days.
+	pub c: f64,
+
+	/// Decay exponent.
+	pub p: f64,
+
+	/// Number of aftershocks used for the fit.
+	pub count: usize,
+
+	/// Length of the fitted period in days.
+	pub duration_days: f64,
+}
+
+impl OmoriFit {
+	/// Aftershock rate (events per day) `t_days` after the mainshock.
+	pub fn rate(&self, t_days: f64) -> f64 {
+		self.k / (t_days + self.c).powf(self.p)
+	}
+
+	/// Expected number of aftershocks between `from_days` and `to_days`
+	/// after the mainshock.
+	pub fn expected_count(&self, from_days: f64, to_days: f64) -> f64 {
+		self.k * omori_integral(self.c, self.p, from_days, to_days)
+	}
+}
+
+fn omori_integral(c: f64, p: f64, from: f64, to: f64) -> f64 {
+	if (p - 1.0).abs() < 1e-9 {
+		((to + c) / (from + c)).ln()
+	} else {
+		((to + c).powf(1.0 - p) - (from + c).powf(1.0 - p)) / (1.0 - p)
+	}
+}
+
+/// Profile log-likelihood of `(c, p)` with `K` at its maximum-likelihood value.
+fn omori_log_likelihood(times: &[f64], duration: f64, c: f64, p: f64) -> f64 {
+	let n = times.len() as f64;
+	let integral = omori_integral(c, p, 0.0, duration);
+	if integral <= 0.0 || !integral.is_finite() {
+		return f64::NEG_INFINITY;
+	}
+	let k = n / integral;
+	n * k.ln() - p * times.iter().map(|t| (t + c).ln()).sum::<f64>() - n
+}
+
+/// The event with `mainshock_id`, or the largest event if `None`.
+fn find_mainshock<'a>(response: &'a EarthquakeResponse, mainshock_id: Option<&str>) -> Option<&'a EarthquakeFeatures> {
+	match mainshock_id {
+		Some(id) => response.features.iter().find(|f| f.id == id),
+		None => response.strongest(),
+	}
+}
+
+/// Fits the modified Omori law to the aftershocks of a mainshock by maximum
+/// likelihood (Ogata, 1983).
+///
+/// The mainshock is the event with `mainshock_id`, or the largest event if
+/// `None`. Aftershocks are all later events in the response up to
+/// `observation_end`, which defaults to the `endtime` of the request and,
+/// failing that, to the last aftershock. Since the likelihood depends on how
+/// long the sequence was observed, pass the end of the queried window when it
+/// is known. Returns `None` if the mainshock cannot be found, the observation
+/// ends before it or fewer than 5 aftershocks follow it.
+pub fn fit_omori(response: &EarthquakeResponse, mainshock_id: Option<&str>, observation_end: Option<DateTime<Utc>>) -> Option<OmoriFit> {
+	let mainshock = find_mainshock(response, mainshock_id)?;
+	let origin = mainshock.properties.time? as f64;
+	let end = observation_end
+		.or_else(|| requested_range(response).map(|(_, end)| end.and_utc()))
+		.map(|end| (end.timestamp_millis() as f64 - origin) / MILLIS_PER_DAY);
+
+	let times: Vec<f64> = response.features.iter()
+		.filter(|f| f.id != mainshock.id)
+		.filter_map(|f| f.properties.time)
+		.map(|time| (time as f64 - origin) / MILLIS_PER_DAY)
+		.filter(|t| *t > 0.0 && end.is_none_or(|end| *t <= end))
+		.collect();
+	if times.len() < 5 {
+		return None;
+	}
+	let duration = end.unwrap_or_else(|| times.iter().copied().fold(0.0, f64::max));
+
+	// Coarse grid search over log(c) and p, then a shrinking pattern search.
+	let mut best = (f64::NEG_INFINITY, -3.0, 1.0);
+	for i in 0..=40 {
+		let log_c = -4.0 + i as f64 * 0.125;
+		for j in 0..=30 {
+			let p = 0.3 + j as f64 * 0.05;
+			let likelihood = omori_log_likelihood(&times, duration, 10f64.powf(log_c), p);
+			if likelihood > best.0 {
+				best = (likelihood, log_c, p);
+			}
+		}
+	}
+
+	let mut step = (0.0625, 0.025);
+	while step.0 > 1e-6 {
+		let mut improved = false;
+		for (d_c, d_p) in [(step.0, 0.0), (-step.0, 0.0), (0.0, step.1), (0.0, -step.1)] {
+			let (log_c, p) = (best.1 + d_c, (best.2 + d_p).max(0.01));
+			let likelihood = omori_log_likelihood(&times, duration, 10f64.powf(log_c), p);
+			if likelihood > best.0 {
+				best = (likelihood, log_c, p);
+				improved = true;
+			}
+		}
+		if !improved {
+			step = (step.0 / 2.0, step.1 / 2.0);
+		}
+	}
+
+	let (c, p) = (10f64.powf(best.1), best.2);
+	Some(OmoriFit {
+		k: times.len() as f64 / omori_integral(c, p, 0.0, duration),
+		c,
+		p,
+		count: times.len(),
+		duration_days: duration,
+	})
+}
+
+/// Length of the period covered by a response, in days.
+///
+/// Uses the `starttime`/`endtime` of the request URL in the metadata when
+/// present, otherwise the span between the first and last event.
+fn catalog_span_days(response: &EarthquakeResponse) -> Option<f64> {
+	let requested = requested_range(response).map(|(start, end)| (end - start).num_milliseconds() as f64 / MILLIS_PER_DAY);
+
+	requested.filter(|days| *days > 0.0).or_else(|| {
+		let times = response.features.iter().filter_map(|f| f.properties.time);
+		let (min, max) = times.fold((u64::MAX, u64::MIN), |(min, max), t| (min.min(t), max.max(t)));
+		(max > min).then(|| (max - min) as f64 / MILLIS_PER_DAY)
+	})
+}
+
+/// `starttime` and `endtime` of the request URL in the metadata, if present.
+fn requested_range(response: &EarthquakeResponse) -> Option<(NaiveDateTime, NaiveDateTime)> {
+	let mut start = None;
+	let mut end = None;
+	for (key, value) in response.metadata.url.query_pairs() {
+		match key.as_ref() {
+			"starttime" => start = parse_url_time(&value),
+			"endtime" => end = parse_url_time(&value),
+			_ => {}
+		}
+	}
+	start.zip(end).filter(|(start, end)| end > start)
+}
+
+/// Probability of at least one event of magnitude `magnitude` or larger in
+/// the next `days` days, assuming a Poisson process with the rate observed in
+/// the response.
+///
+/// Returns `None` if the period covered by the response cannot be determined.
+pub fn probability_of_exceedance(response: &EarthquakeResponse, magnitude: f64, days: f64) -> Option<f64> {
+	let span = catalog_span_days(response)?;
+	let count = response.features.iter()
+		.filter(|f| f.properties.magnitude.is_some_and(|m| m >= magnitude))
+		.count();
+	let rate = count as f64 / span;
+	Some(1.0 - (-rate * days).exp())
+}
+
+/// Inter-event time statistics for events above a magnitude threshold.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecurrenceStats {
+	/// Number of events above the threshold.
+	pub event_count: usize,
+
+	/// Mean recurrence interval in days.
+	pub mean_days: f64,
+
+	/// Median recurrence interval in days.
+	pub median_days: f64,
+
+	/// Standard deviation of the intervals in days.
+	pub std_dev_days: f64,
+
+	/// Coefficient of variation (`std_dev / mean`); about 1 for a Poisson
+	/// process, below 1 for quasi-periodic and above 1 for clustered events.
+	pub coefficient_of_variation: f64,
+}
+
+/// Computes recurrence interval statistics for events of magnitude
+/// `min_magnitude` or larger.
+///
+/// Returns `None` if fewer than two such events have a known time.
+pub fn recurrence_intervals(response: &EarthquakeResponse, min_magnitude: f64) -> Option<RecurrenceStats> {
+	let mut times: Vec<u64> = response.features.iter()
+		.filter(|f| f.properties.magnitude.is_some_and(|m| m >= min_magnitude))
+		.filter_map(|f| f.properties.time)
+		.collect();
+	if times.len() < 2 {
+		return None;
+	}
+	times.sort_unstable();
+
+	let mut intervals: Vec<f64> = times.windows(2)
+		.map(|pair| (pair[1] - pair[0]) as f64 / MILLIS_PER_DAY)
+		.collect();
+	intervals.sort_by(f64::total_cmp);
+
+	let n = intervals.len() as f64;
+	let mean = intervals.iter().sum::<f64>() / n;
+	let variance = intervals.iter().map(|i| (i - mean).powi(2)).sum::<f64>() / n;
+	let middle = intervals.len() / 2;
+	let median = if intervals.len().is_multiple_of(2) {
+		(intervals[middle - 1] + intervals[middle]) / 2.0
+	} else {
+		intervals[middle]
+	};
+
+	Some(RecurrenceStats {
+		event_count: times.len(),
+		mean_days: mean,
+		median_days: median,
+		std_dev_days: variance.sqrt(),
+		coefficient_of_variation: if mean > 0.0 { variance.sqrt() / mean } else { 0.0 },
+	})
+}
+/// Role of an event within an earthquake sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SequenceRole {
+	/// Occurred before the mainshock.
+	Foreshock,
+
+	/// The mainshock itself.
+	Mainshock,
+
+	/// Occurred at or after the mainshock.
+	Aftershock,
+}
+
+/// An event labeled relative to the mainshock of its sequence.
+#[derive(Debug, Clone)]
+pub struct SequenceEvent<'a> {
+	/// The labeled event.
+	pub feature: &'a EarthquakeFeatures,
+
+	/// Role of the event in the sequence.
+	pub role: SequenceRole,
+
+	/// Time from the mainshock origin (negative for foreshocks).
+	pub time_from_mainshock: TimeDelta,
+
+	/// Epicentral distance from the mainshock in kilometers.
+	pub distance_km: f64,
+}
+
+/// Labels every event as foreshock, mainshock or aftershock, with its time
+/// and distance from the mainshock, for sequence reports.
+///
+/// The mainshock is the event with `mainshock_id`, or the largest event if
+/// `None`. Events are returned in chronological order; events without an
+/// origin time are skipped. Returns `None` if the mainshock cannot be found
+/// or has no origin time.
+///
+/// ## Example
+/// ```rust,no_run
+/// use usgs_earthquake_api::UsgsClient;
+/// use usgs_earthquake_api::stats::label_sequence;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let response = UsgsClient::new().query().circle(37.2, 37.0, 300.0).start_time(2023, 1, 30, 0, 0).end_time(2023, 3, 6, 0, 0).fetch().await.unwrap();
+///     for event in label_sequence(&response, None).unwrap_or_default() {
+///         println!("{:?} {} ({:.0} km, {} h)", event.role, event.feature, event.distance_km, event.time_from_mainshock.num_hours());
+///     }
+/// }
+/// ```
+pub fn label_sequence<'a>(response: &'a EarthquakeResponse, mainshock_id: Option<&str>) -> Option<Vec<SequenceEvent<'a>>> {
+	let mainshock = find_mainshock(response, mainshock_id)?;
+	let origin = mainshock.properties.time? as i64;
+	let epicenter = (mainshock.geometry.latitude(), mainshock.geometry.longitude());
+
+	let mut events: Vec<SequenceEvent<'a>> = response.features.iter()
+		.filter_map(|feature| {
+			let offset = feature.properties.time? as i64 - origin;
+			let role = if feature.id == mainshock.id {
+				SequenceRole::Mainshock
+			} else if offset < 0 {
+				SequenceRole::Foreshock
+			} else {
+				SequenceRole::Aftershock
+			};
+			Some(SequenceEvent {
+				feature,
+				role,
+				time_from_mainshock: TimeDelta::milliseconds(offset),
+				distance_km: haversine_km(epicenter, (feature.geometry.latitude(), feature.geometry.longitude())),
+			})
+		})
+		.collect();
+	events.sort_by_key(|event| event.time_from_mainshock);
+	Some(events)
+}
+
+/// Probability below which a run of empty days is reported as a gap.
+const GAP_PROBABILITY: f64 = 0.001;
+
+/// Standard score from which a rate change between windows is reported.
+const RATE_CHANGE_SCORE: f64 = 3.0;
+
+/// Suspicious stretch of a catalog found by [`completeness_report`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum CompletenessIssue {
+	/// Consecutive days without events although the average rate predicts
+	/// `expected_events` of them; a run this quiet has a chance below 0.1%.
+	Gap {
+		/// First empty day.
+		from: NaiveDate,
+
+		/// Last empty day.
+		to: NaiveDate,
+
+		/// Events expected over the run at the average daily rate.
+		expected_events: f64,
+	},
+
+	/// Daily rate differing by a factor of two or more (and by three
+	/// standard deviations) between two consecutive windows.
+	RateChange {
+		/// First day of the later window.
+		at: NaiveDate,
+
+		/// Events per day in the earlier window.
+		rate_before: f64,
+
+		/// Events per day in the later window.
+		rate_after: f64,
+	},
+}
+
+/// Completeness analysis of a catalog, see [`completeness_report`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompletenessReport {
+	/// First analysed day.
+	pub start: NaiveDate,
+
+	/// Number of analysed days.
+	pub days: usize,
+
+	/// Average number of events per day.
+	pub mean_daily_rate: f64,
+
+	/// Number of days without events.
+	pub empty_days: usize,
+
+	/// Gaps and rate changes, in chronological order of their start.
+	pub issues: Vec<CompletenessIssue>,
+}
+
+/// Scans a response for signs of missing data, e.g. after a lossy backfill:
+/// runs of empty days that are improbable at the average rate, and abrupt
+/// rate changes between consecutive windows of `window_days` days.
+///
+/// Days are counted from the requested `starttime` (or the first event).
+/// The rate is assumed stationary, so run it on a region and magnitude
+/// range that is active throughout; real aftershock sequences also show up
+/// as rate changes. Returns `None` without events with a time.
+///
+/// ## Example
+/// ```rust,no_run
+/// use usgs_earthquake_api::UsgsClient;
+/// use usgs_earthquake_api::stats::{completeness_report, CompletenessIssue};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let response = UsgsClient::new().query().start_time(2024, 1, 1, 0, 0).min_magnitude(2.5).fetch().await.unwrap();
+///     let report = completeness_report(&response, 30).unwrap();
+///     for issue in report.issues {
+///         if let CompletenessIssue::Gap { from, to, .. } = issue {
+///             println!("no events from {} to {}", from, to);
+///         }
+///     }
+/// }
+/// ```
+pub fn completeness_report(response: &EarthquakeResponse, window_days: u32) -> Option<CompletenessReport> {
+	let times: Vec<u64> = response.features.iter().filter_map(|f| f.properties.time).collect();
+	let (first, last) = (*times.iter().min()?, *times.iter().max()?);
+	let (start, end) = requested_range(response)
+		.map(|(start, end)| (start.and_utc().timestamp_millis().max(0) as u64, end.and_utc().timestamp_millis().max(0) as u64))
+		.filter(|(start, end)| *start <= first && last <= *end)
+		.unwrap_or((first, last + 1));
+	let start_day = chrono::DateTime::from_timestamp_millis(start as i64)?.date_naive();
+	let day_of = |index: usize| start_day + chrono::Days::new(index as u64);
+
+	let days = ((end - start) as f64 / MILLIS_PER_DAY).ceil().max(1.0) as usize;
+	let mut counts = vec![0usize; days];
+	for time in times {
+		counts[(((time - start) as f64 / MILLIS_PER_DAY) as usize).min(days - 1)] += 1;
+	}
+	let mean_daily_rate = counts.iter().sum::<usize>() as f64 / days as f64;
+
+	let mut issues = Vec::new();
+	let mut index = 0;
+	while index < days {
+		let run = counts[index..].iter().take_while(|count| **count == 0).count();
+		let expected_events = mean_daily_rate * run as f64;
+		if run > 0 && (-expected_events).exp() < GAP_PROBABILITY {
+			issues.push(CompletenessIssue::Gap { from: day_of(index), to: day_of(index + run - 1), expected_events });
+		}
+		index += run.max(1);
+	}
+
+	let window = (window_days.max(1) as usize).min(days);
+	let rates: Vec<(usize, f64, f64)> = counts.chunks(window).enumerate()
+		.map(|(i, chunk)| (i * window, chunk.iter().sum::<usize>() as f64 / chunk.len() as f64, chunk.len() as f64))
+		.collect();
+	for pair in rates.windows(2) {
+		let ((_, before, before_days), (at, after, after_days)) = (pair[0], pair[1]);
+		let deviation = (before / before_days + after / after_days).sqrt();
+		let ratio = before.max(after) / before.min(after);
+		if deviation > 0.0 && (before - after).abs() / deviation >= RATE_CHANGE_SCORE && ratio >= 2.0 {
+			issues.push(CompletenessIssue::RateChange { at: day_of(at), rate_before: before, rate_after: after });
+		}
+	}
+	issues.sort_by_key(|issue| match issue {
+		CompletenessIssue::Gap { from, .. } => *from,
+		CompletenessIssue::RateChange { at, .. } => *at,
+	});
+
+	Some(CompletenessReport {
+		start: start_day,
+		days,
+		mean_daily_rate,
+		empty_days: counts.iter().filter(|count| **count == 0).count(),
+		issues,
+	})
+}
+
+/// Thresholds of [`detect_swarms`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SwarmCriteria {
+	/// Length of the sliding window the rate is measured over.
+	pub window: TimeDelta,
+
+	/// Minimum number of events within one window.
+	pub min_events: usize,
+
+	/// Minimum ratio of the rate within the window to the average rate of
+	/// the response.
+	pub rate_factor: f64,
+
+	/// Smallest magnitude counted.
+	pub min_magnitude: f64,
+
+	/// Largest difference between the two strongest events of an episode
+	/// for it to count as a swarm. Mainshock-aftershock sequences typically
+	/// differ by about 1.2 units (Båth's law).
+	pub max_magnitude_gap: f64,
+}
+
+impl Default for SwarmCriteria {
+	fn default() -> Self {
+		Self {
+			window: TimeDelta::days(2),
+			min_events: 10,
+			rate_factor: 5.0,
+			min_magnitude: f64::NEG_INFINITY,
+			max_magnitude_gap: 0.8,
+		}
+	}
+}
+
+/// Episode of elevated seismicity without a dominant mainshock.
+#[derive(Debug, Clone)]
+pub struct Swarm<'a> {
+	/// Events of the episode in chronological order.
+	pub events: Vec<&'a EarthquakeFeatures>,
+
+	/// Origin time of the first event.
+	pub start: DateTime<Utc>,
+
+	/// Origin time of the last event.
+	pub end: DateTime<Utc>,
+
+	/// Events per day during the episode.
+	pub rate_per_day: f64,
+
+	/// Largest magnitude of the episode.
+	pub max_magnitude: f64,
+
+	/// Difference between the two largest magnitudes.
+	pub magnitude_gap: f64,
+}
+
+/// Flags swarms in a response for a region: episodes where at least
+/// `min_events` events occur within `window` at `rate_factor` times the
+/// average rate, and the largest event does not stand out by more than
+/// `max_magnitude_gap` from the next largest.
+///
+/// Episodes are joined while the sliding windows overlap. Events without a
+/// magnitude or origin time are ignored; the average rate is taken over the
+/// requested time range (or the span of the events).
+///
+/// ## Example
+/// ```rust,no_run
+/// use usgs_earthquake_api::UsgsClient;
+/// use usgs_earthquake_api::stats::{detect_swarms, SwarmCriteria};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let response = UsgsClient::new().query().circle(19.4, -155.3, 30.0).start_time(2024, 1, 1, 0, 0).fetch().await.unwrap();
+///     let criteria = SwarmCriteria { min_magnitude: 1.0, ..Default::default() };
+///     for swarm in detect_swarms(&response, &criteria) {
+///         println!("{} events from {} to {}, up to M {:.1}", swarm.events.len(), swarm.start, swarm.end, swarm.max_magnitude);
+///     }
+/// }
+/// ```
+pub fn detect_swarms<'a>(response: &'a EarthquakeResponse, criteria: &SwarmCriteria) -> Vec<Swarm<'a>> {
+	let mut events: Vec<(i64, f64, &'a EarthquakeFeatures)> = response.features.iter()
+		.filter_map(|feature| Some((feature.properties.time? as i64, feature.properties.magnitude?, feature)))
+		.filter(|(_, magnitude, _)| *magnitude >= criteria.min_magnitude)
+		.collect();
+	events.sort_by_key(|(time, _, _)| *time);
+	let Some(span_days) = catalog_span_days(response) else {
+		return Vec::new();
+	};
+	let window_ms = criteria.window.num_milliseconds().max(1);
+	let window_days = window_ms as f64 / MILLIS_PER_DAY;
+	let average_per_window = events.len() as f64 / span_days * window_days;
+	let min_events = criteria.min_events.max(2);
+
+	// Mark the events of every window that is busy enough, then join
+	// overlapping windows into episodes.
+	let mut episodes: Vec<(usize, usize)> = Vec::new();
+	let mut end = 0;
+	for start in 0..events.len() {
+		end = end.max(start);
+		while end + 1 < events.len() && events[end + 1].0 - events[start].0 <= window_ms {
+			end += 1;
+		}
+		let count = end - start + 1;
+		if count < min_events || (count as f64) < criteria.rate_factor * average_per_window {
+			continue;
+		}
+		match episodes.last_mut() {
+			Some((_, last)) if start <= *last => *last = (*last).max(end),
+			_ => episodes.push((start, end)),
+		}
+	}
+
+	episodes.into_iter()
+		.filter_map(|(first, last)| {
+			let episode = &events[first..=last];
+			let mut magnitudes: Vec<f64> = episode.iter().map(|(_, magnitude, _)| *magnitude).collect();
+			magnitudes.sort_by(|a, b| b.total_cmp(a));
+			let magnitude_gap = magnitudes[0] - magnitudes[1];
+			if magnitude_gap > criteria.max_magnitude_gap {
+				return None;
+			}
+			let (start, end) = (episode[0].0, episode[episode.len() - 1].0);
+			let duration_days = ((end - start) as f64 / MILLIS_PER_DAY).max(window_days);
+			Some(Swarm {
+				events: episode.iter().map(|(_, _, feature)| *feature).collect(),
+				start: DateTime::from_timestamp_millis(start)?,
+				end: DateTime::from_timestamp_millis(end)?,
+				rate_per_day: episode.len() as f64 / duration_days,
+				max_magnitude: magnitudes[0],
+				magnitude_gap,
+			})
+		})
+		.collect()
+}
+
+/// Weights of the components of [`RankingWeights::score`].
+///
+/// Each component is scaled to 0..=1 before weighting: magnitude over 10,
+/// `sig` over 1000, felt reports logarithmically up to 10,000, alert level
+/// from green (0.25) to red (1.0), and recency halving every
+/// `recency_half_life`. Missing values count as 0.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RankingWeights {
+	/// Weight of the magnitude.
+	pub magnitude: f64,
+
+	/// Weight of the USGS significance (`sig`).
+	pub significance: f64,
+
+	/// Weight of the number of "Did You Feel It?" reports.
+	pub felt: f64,
+
+	/// Weight of the PAGER alert level.
+	pub alert: f64,
+
+	/// Weight of the recency relative to the time the response was generated.
+	pub recency: f64,
+
+	/// Age at which the recency component drops to one half.
+	pub recency_half_life: TimeDelta,
+}
+
+impl Default for RankingWeights {
+	fn default() -> Self {
+		Self {
+			magnitude: 0.3,
+			significance: 0.25,
+			felt: 0.15,
+			alert: 0.2,
+			recency: 0.1,
+			recency_half_life: TimeDelta::days(1),
+		}
+	}
+}
+
+impl RankingWeights {
+	/// Newsworthiness of `event` at time `now` as the weighted mean of the
+	/// components, between 0 and 1.
+	pub fn score(&self, event: &EarthquakeFeatures, now: DateTime<Utc>) -> f64 {
+		let properties = &event.properties;
+		let magnitude = properties.magnitude.map_or(0.0, |magnitude| (magnitude / 10.0).clamp(0.0, 1.0));
+		let significance = properties.sig.map_or(0.0, |sig| (f64::from(sig) / 1000.0).min(1.0));
+		let felt = properties.felt.map_or(0.0, |felt| (f64::from(felt).ln_1p() / 10_000f64.ln_1p()).min(1.0));
+		let alert = match event.alert() {
+			Some(AlertLevel::Green) => 0.25,
+			Some(AlertLevel::Yellow) => 0.5,
+			Some(AlertLevel::Orange) => 0.75,
+			Some(AlertLevel::Red) => 1.0,
+			Some(AlertLevel::All) | None => 0.0,
+		};
+		let half_life_ms = self.recency_half_life.num_milliseconds().max(1) as f64;
+		let recency = event.origin_time().map_or(0.0, |origin| {
+			let age_ms = (now - origin).num_milliseconds().max(0) as f64;
+			0.5f64.powf(age_ms / half_life_ms)
+		});
+
+		let total = self.magnitude + self.significance + self.felt + self.alert + self.recency;
+		if total <= 0.0 {
+			return 0.0;
+		}
+		(self.magnitude * magnitude + self.significance * significance + self.felt * felt + self.alert * alert + self.recency * recency) / total
+	}
+}
+
+/// Event with its score from [`rank_events`].
+#[derive(Debug, Clone, Copy)]
+pub struct RankedEvent<'a> {
+	/// The ranked event.
+	pub event: &'a EarthquakeFeatures,
+
+	/// Score between 0 and 1, see [`RankingWeights::score`].
+	pub score: f64,
+}
+
+/// Orders the events of `response` from most to least newsworthy by
+/// [`RankingWeights::score`], with recency measured from the time the
+/// response was generated.
+///
+/// ## Example
+/// ```rust,no_run
+/// use usgs_earthquake_api::UsgsClient;
+/// use usgs_earthquake_api::stats::{rank_events, RankingWeights};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let response = UsgsClient::new().query().min_magnitude(2.5).fetch().await.unwrap();
+///     for ranked in rank_events(&response, &RankingWeights::default()).iter().take(10) {
+///         println!("{:.2} {}", ranked.score, ranked.event);
+///     }
+/// }
+/// ```
+pub fn rank_events<'a>(response: &'a EarthquakeResponse, weights: &RankingWeights) -> Vec<RankedEvent<'a>> {
+	let now = response.metadata.generated_timestamp;
+	let mut ranked: Vec<RankedEvent<'a>> = response.features.iter()
+		.map(|event| RankedEvent { event, score: weights.score(event, now) })
+		.collect();
+	ranked.sort_by(|a, b| b.score.total_cmp(&a.score));
+	ranked
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json::json;
+	use super::*;
+	use crate::parse_response;
+	use crate::tests::tests::{collection, feature, response, QUERY_URL};
+
+	const ORIGIN: u64 = 1_700_000_000_000;
+
+	/// Catalog of `(id, latitude, longitude, magnitude, time)` events with
+	/// the given request URL in the metadata.
+	fn catalog(url: &str, events: &[(&str, f64, f64, f64, u64)]) -> EarthquakeResponse {
+		let features = events.iter()
+			.map(|&(id, latitude, longitude, magnitude, time)| feature(id, [longitude, latitude, 10.0], json!({ "mag": magnitude, "time": time, "type": "earthquake" })))
+			.collect();
+		let mut body = collection(url, features);
+		body["metadata"]["generated"] = json!(ORIGIN);
+		parse_response(&body.to_string()).unwrap()
+	}
+
+	/// Aftershock times in days at evenly spaced quantiles of the Omori law
+	/// with parameters `c` and `p` observed for `duration` days.
+	fn omori_sequence(count: usize, c: f64, p: f64, duration: f64) -> Vec<f64> {
+		let total = omori_integral(c, p, 0.0, duration);
+		(0..count)
+			.map(|i| (i as f64 + 0.5) / count as f64 * total)
+			.map(|integral| (c.powf(1.0 - p) + integral * (1.0 - p)).powf(1.0 / (1.0 - p)) - c)
+			.collect()
+	}
+
+	fn aftershock_catalog(days: &[f64]) -> EarthquakeResponse {
+		let ids: Vec<String> = (0..days.len()).map(|i| format!("as{}", i)).collect();
+		let mut events = vec![("main", 38.0, 142.0, 7.0, ORIGIN)];
+		events.extend(days.iter().zip(&ids).map(|(day, id)| (id.as_str(), 38.0, 142.0, 3.0, ORIGIN + (day * MILLIS_PER_DAY) as u64)));
+		catalog(QUERY_URL, &events)
+	}
+
+	fn at_day(day: f64) -> DateTime<Utc> {
+		DateTime::from_timestamp_millis(ORIGIN as i64 + (day * MILLIS_PER_DAY) as i64).unwrap()
+	}
+
+	#[test]
+	fn fit_omori_recovers_synthetic_parameters() {
+		let (c, p, duration) = (0.05, 1.1, 100.0);
+		let days = omori_sequence(2000, c, p, duration);
+		let fit = fit_omori(&aftershock_catalog(&days), Some("main"), Some(at_day(duration))).unwrap();
+
+		let k = 2000.0 / omori_integral(c, p, 0.0, duration);
+		assert_eq!(fit.count, 2000);
+		assert!((fit.duration_days - duration).abs() < 1e-6);
+		assert!((fit.p - p).abs() < 0.03, "p = {}", fit.p);
+		assert!((fit.c / c).ln().abs() < 0.2, "c = {}", fit.c);
+		assert!((fit.k / k - 1.0).abs() < 0.1, "k = {} instead of {}", fit.k, k);
+		assert!((fit.expected_count(0.0, duration) - 2000.0).abs() < 1e-6);
+	}
+
+	#[test]
+	fn fit_omori_uses_the_observation_end() {
+		let days = omori_sequence(2000, 0.05, 1.1, 100.0);
+
+		// Events after the observation end are ignored and the first 10 days
+		// alone still follow the same law.
+		let early = fit_omori(&aftershock_catalog(&days), None, Some(at_day(10.0))).unwrap();
+		assert!(early.count < days.len());
+		assert!((early.duration_days - 10.0).abs() < 1e-6);
+		assert!((early.p - 1.1).abs() < 0.05, "p = {}", early.p);
+
+		// A quiet tail until the observation end means a faster decay.
+		let quiet: Vec<f64> = days.iter().copied().filter(|day| *day <= 10.0).collect();
+		let observed = fit_omori(&aftershock_catalog(&quiet), None, Some(at_day(100.0))).unwrap();
+		assert!((observed.duration_days - 100.0).abs() < 1e-6);
+		assert!(observed.p > early.p + 0.2, "p = {}", observed.p);
+
+		assert!(fit_omori(&aftershock_catalog(&days), None, Some(at_day(-1.0))).is_none());
+		assert!(fit_omori(&aftershock_catalog(&days[..4]), None, None).is_none());
+	}
+
+	#[test]
+	fn probability_of_exceedance_follows_poisson_rate() {
+		let day = MILLIS_PER_DAY as u64;
+		let events: Vec<_> = [5.0, 5.5, 6.1, 5.0, 5.2, 4.9, 3.0].iter().enumerate()
+			.map(|(i, magnitude)| ("ev", 0.0, 0.0, *magnitude, ORIGIN + i as u64 * day))
+			.collect();
+
+		// 5 events of M5+ in the requested 10 days: 0.5 per day.
+		let requested = catalog("https://earthquake.usgs.gov/fdsnws/event/1/query?starttime=2023-11-14&endtime=2023-11-24", &events);
+		let probability = probability_of_exceedance(&requested, 5.0, 2.0).unwrap();
+		assert!((probability - (1.0 - (-1.0f64).exp())).abs() < 1e-12);
+		assert_eq!(probability_of_exceedance(&requested, 7.0, 2.0), Some(0.0));
+
+		// Without a requested range the span of the events (6 days) is used.
+		let unbounded = catalog("https://earthquake.usgs.gov/earthquakes/feed/v1.0/summary/all_week.geojson", &events);
+		let probability = probability_of_exceedance(&unbounded, 6.0, 6.0).unwrap();
+		assert!((probability - (1.0 - (-1.0f64).exp())).abs() < 1e-12);
+
+		let single = catalog("https://earthquake.usgs.gov/earthquakes/feed/v1.0/summary/all_hour.geojson", &events[..1]);
+		assert_eq!(probability_of_exceedance(&single, 5.0, 1.0), None);
+	}
+
+	#[test]
+	fn cross_section_projects_onto_the_profile() {
+		let response = catalog(QUERY_URL, &[
+			("north", 0.1, 5.0, 4.0, ORIGIN),
+			("south", -0.2, 2.0, 4.0, ORIGIN),
+			("far", 1.0, 5.0, 4.0, ORIGIN),
+			("before", 0.0, -1.0, 4.0, ORIGIN),
+			("beyond", 0.0, 11.0, 4.0, ORIGIN),
+		]);
+		let profile = cross_section(&response, (0.0, 0.0), (0.0, 10.0), 50.0);
+
+		let ids: Vec<&str> = profile.iter().map(|point| point.feature.id.as_str()).collect();
+		assert_eq!(ids, ["south", "north"]);
+
+		let km_per_degree = EARTH_RADIUS_KM.to_radians();
+		assert!((profile[0].along_km - 2.0 * km_per_degree).abs() < 0.5);
+		assert!((profile[0].offset_km - 0.2 * km_per_degree).abs() < 0.5);
+		assert!((profile[1].along_km - 5.0 * km_per_degree).abs() < 0.5);
+		assert!((profile[1].offset_km + 0.1 * km_per_degree).abs() < 0.5);
+		assert!((profile[1].depth_km - 10.0).abs() < 1e-6);
+	}
+
+	#[test]
+	fn recurrence_intervals_summarize_the_intervals() {
+		let day = MILLIS_PER_DAY as u64;
+		let response = catalog(QUERY_URL, &[
+			("a", 0.0, 0.0, 5.0, ORIGIN + 6 * day),
+			("b", 0.0, 0.0, 5.5, ORIGIN),
+			("small", 0.0, 0.0, 3.0, ORIGIN + 2 * day),
+			("c", 0.0, 0.0, 6.0, ORIGIN + day),
+			("d", 0.0, 0.0, 5.0, ORIGIN + 3 * day),
+		]);
+
+		// Intervals of 1, 2 and 3 days between the M5+ events.
+		let stats = recurrence_intervals(&response, 5.0).unwrap();
+		assert_eq!(stats.event_count, 4);
+		assert!((stats.mean_days - 2.0).abs() < 1e-9);
+		assert!((stats.median_days - 2.0).abs() < 1e-9);
+		assert!((stats.std_dev_days - (2.0f64 / 3.0).sqrt()).abs() < 1e-9);
+		assert!((stats.coefficient_of_variation - stats.std_dev_days / 2.0).abs() < 1e-9);
+
+		let stats = recurrence_intervals(&response, 5.5).unwrap();
+		assert_eq!((stats.event_count, stats.median_days), (2, 1.0));
+		assert_eq!(recurrence_intervals(&response, 6.0), None);
+	}
+
+	#[test]
+	fn label_sequence_orders_events_around_the_mainshock() {
+		let hour = 3_600_000;
+		let mut response = catalog(QUERY_URL, &[
+			("after", 38.0, 142.0, 5.0, ORIGIN + 12 * hour),
+			("main", 38.0, 142.0, 7.0, ORIGIN),
+			("fore", 38.09, 142.0, 5.5, ORIGIN - 24 * hour),
+			("untimed", 38.0, 142.0, 4.0, ORIGIN),
+		]);
+		response.features[3].properties.time = None;
+
+		let sequence = label_sequence(&response, None).unwrap();
+		let labels: Vec<(&str, SequenceRole, i64)> = sequence.iter()
+			.map(|event| (event.feature.id.as_str(), event.role, event.time_from_mainshock.num_hours()))
+			.collect();
+		assert_eq!(labels, [("fore", SequenceRole::Foreshock, -24), ("main", SequenceRole::Mainshock, 0), ("after", SequenceRole::Aftershock, 12)]);
+		assert!((sequence[0].distance_km - 0.09 * EARTH_RADIUS_KM.to_radians()).abs() < 0.01);
+		assert_eq!(sequence[1].distance_km, 0.0);
+
+		let sequence = label_sequence(&response, Some("fore")).unwrap();
+		let roles: Vec<SequenceRole> = sequence.iter().map(|event| event.role).collect();
+		assert_eq!(roles, [SequenceRole::Mainshock, SequenceRole::Aftershock, SequenceRole::Aftershock]);
+
+		assert!(label_sequence(&response, Some("missing")).is_none());
+		assert!(label_sequence(&response, Some("untimed")).is_none());
+	}
+
+	/// Midnight UTC of 2023-11-15, the `starttime` of [`daily_catalog`].
+	const DAY0: u64 = 1_700_006_400_000;
+
+	/// Catalog of 2023-11-15 to 2023-12-15 with `per_day(day)` events of M3
+	/// on each of the 30 days.
+	fn daily_catalog(per_day: impl Fn(u64) -> u64) -> EarthquakeResponse {
+		let events: Vec<(String, u64)> = (0..30)
+			.flat_map(|day| (0..per_day(day)).map(move |i| (format!("d{}e{}", day, i), DAY0 + day * MILLIS_PER_DAY as u64 + i * 60_000)))
+			.collect();
+		let events: Vec<_> = events.iter().map(|(id, time)| (id.as_str(), 0.0, 0.0, 3.0, *time)).collect();
+		catalog("https://earthquake.usgs.gov/fdsnws/event/1/query?starttime=2023-11-15&endtime=2023-12-15", &events)
+	}
+
+	#[test]
+	fn completeness_report_finds_improbable_gaps() {
+		let response = daily_catalog(|day| if (10..15).contains(&day) { 0 } else { 4 });
+		let report = completeness_report(&response, 30).unwrap();
+		assert_eq!(report.start, NaiveDate::from_ymd_opt(2023, 11, 15).unwrap());
+		assert_eq!((report.days, report.empty_days), (30, 5));
+		assert!((report.mean_daily_rate - 100.0 / 30.0).abs() < 1e-9);
+		assert_eq!(report.issues, [CompletenessIssue::Gap {
+			from: NaiveDate::from_ymd_opt(2023, 11, 25).unwrap(),
+			to: NaiveDate::from_ymd_opt(2023, 11, 29).unwrap(),
+			expected_events: 5.0 * report.mean_daily_rate,
+		}]);
+
+		// A single quiet day is likely enough at this rate.
+		let response = daily_catalog(|day| if day == 10 { 0 } else { 4 });
+		assert!(completeness_report(&response, 30).unwrap().issues.is_empty());
+	}
+
+	#[test]
+	fn completeness_report_finds_rate_changes() {
+		let response = daily_catalog(|day| if day < 15 { 2 } else { 8 });
+		let report = completeness_report(&response, 15).unwrap();
+		assert_eq!(report.empty_days, 0);
+		assert_eq!(report.issues, [CompletenessIssue::RateChange {
+			at: NaiveDate::from_ymd_opt(2023, 11, 30).unwrap(),
+			rate_before: 2.0,
+			rate_after: 8.0,
+		}]);
+
+		let steady = daily_catalog(|day| 4 + day % 2);
+		assert!(completeness_report(&steady, 15).unwrap().issues.is_empty());
+		assert!(completeness_report(&catalog(QUERY_URL, &[]), 15).is_none());
+	}
+
+	/// Background of one M2 event every other day over 30 days, plus 20
+	/// events on day 20 with the given magnitudes.
+	fn episode_catalog(magnitude: impl Fn(usize) -> f64) -> EarthquakeResponse {
+		let ids: Vec<String> = (0..20).map(|i| format!("sw{}", i)).collect();
+		let mut events: Vec<_> = (0..15).map(|i| ("bg", 19.4, -155.3, 2.0, DAY0 + 2 * i * MILLIS_PER_DAY as u64)).collect();
+		events.extend(ids.iter().enumerate().map(|(i, id)| (id.as_str(), 19.4, -155.3, magnitude(i), DAY0 + 20 * MILLIS_PER_DAY as u64 + 3_600_000 + i as u64 * 600_000)));
+		catalog("https://earthquake.usgs.gov/fdsnws/event/1/query?starttime=2023-11-15&endtime=2023-12-15", &events)
+	}
+
+	#[test]
+	fn detect_swarms_flags_busy_episodes_without_a_mainshock() {
+		let response = episode_catalog(|i| 3.0 + i as f64 * 0.02);
+		let swarms = detect_swarms(&response, &SwarmCriteria::default());
+		assert_eq!(swarms.len(), 1);
+		let swarm = &swarms[0];
+		assert_eq!(swarm.events.iter().filter(|event| event.id.starts_with("sw")).count(), 20);
+		assert!((swarm.max_magnitude - 3.38).abs() < 1e-9);
+		assert!((swarm.magnitude_gap - 0.02).abs() < 1e-9);
+
+		// Background events within a window of the swarm join the episode.
+		let at_day = |day: u64| DateTime::from_timestamp_millis((DAY0 + day * MILLIS_PER_DAY as u64) as i64).unwrap();
+		assert_eq!(swarm.events.len(), 22);
+		assert_eq!((swarm.start, swarm.end), (at_day(20), at_day(22)));
+
+		// Only the swarm counts above M3.
+		let criteria = SwarmCriteria { min_magnitude: 3.0, ..Default::default() };
+		let swarms = detect_swarms(&response, &criteria);
+		assert_eq!(swarms[0].events.len(), 20);
+		assert!((swarms[0].rate_per_day - 10.0).abs() < 1e-9);
+	}
+
+	#[test]
+	fn detect_swarms_skips_mainshock_sequences_and_quiet_catalogs() {
+		let sequence = episode_catalog(|i| if i == 0 { 6.0 } else { 3.0 + i as f64 * 0.02 });
+		assert!(detect_swarms(&sequence, &SwarmCriteria::default()).is_empty());
+
+		let quiet = episode_catalog(|i| 3.0 + i as f64 * 0.02);
+		let criteria = SwarmCriteria { min_events: 25, ..Default::default() };
+		assert!(detect_swarms(&quiet, &criteria).is_empty());
+	}
+
+	/// Generation time of [`response`] bodies.
+	const GENERATED: u64 = 1_718_028_000_000;
+
+	fn scores(ranked: &[RankedEvent<'_>]) -> Vec<(String, f64)> {
+		ranked.iter().map(|ranked| (ranked.event.id.to_string(), (ranked.score * 1e9).round() / 1e9)).collect()
+	}
+
+	fn score(id: &str, score: f64) -> (String, f64) {
+		(id.to_string(), score)
+	}
+
+	#[test]
+	fn rank_events_orders_by_weighted_score() {
+		let response = response(QUERY_URL, vec![
+			feature("minor", [0.0, 0.0, 10.0], json!({ "mag": 2.5, "time": GENERATED })),
+			feature("major", [0.0, 0.0, 10.0], json!({ "mag": 10.0, "sig": 1500, "felt": 10_000, "alert": "red", "time": GENERATED })),
+			feature("felt", [0.0, 0.0, 10.0], json!({ "mag": 4.0, "sig": 500, "felt": 0, "alert": "yellow", "time": GENERATED - 86_400_000 })),
+			feature("bare", [0.0, 0.0, 10.0], json!({})),
+		]);
+
+		// Felt: 0.3 * 0.4 + 0.25 * 0.5 + 0.2 * 0.5 (yellow) + 0.1 * 0.5 (a day
+		// old); minor: 0.3 * 0.25 + 0.1; missing values count as 0.
+		let ranked = rank_events(&response, &RankingWeights::default());
+		assert_eq!(scores(&ranked), [
+			score("major", 1.0),
+			score("felt", 0.395),
+			score("minor", 0.175),
+			score("bare", 0.0),
+		]);
+	}
+
+	#[test]
+	fn rank_events_uses_only_weighted_components() {
+		let response = response(QUERY_URL, vec![
+			feature("old", [0.0, 0.0, 10.0], json!({ "mag": 6.0, "time": GENERATED - 2 * 3_600_000 })),
+			feature("untimed", [0.0, 0.0, 10.0], json!({ "mag": 7.0 })),
+			feature("new", [0.0, 0.0, 10.0], json!({ "mag": 5.0, "time": GENERATED - 3_600_000 })),
+			feature("future", [0.0, 0.0, 10.0], json!({ "time": GENERATED + 3_600_000 })),
+		]);
+
+		let magnitude = RankingWeights { magnitude: 2.0, significance: 0.0, felt: 0.0, alert: 0.0, recency: 0.0, ..Default::default() };
+		assert_eq!(scores(&rank_events(&response, &magnitude)), [score("untimed", 0.7), score("old", 0.6), score("new", 0.5), score("future", 0.0)]);
+
+		let recency = RankingWeights { magnitude: 0.0, significance: 0.0, felt: 0.0, alert: 0.0, recency: 1.0, recency_half_life: TimeDelta::hours(1) };
+		assert_eq!(scores(&rank_events(&response, &recency)), [score("future", 1.0), score("new", 0.5), score("old", 0.25), score("untimed", 0.0)]);
+
+		// Without weights every score is 0 and the response order is kept.
+		let none = RankingWeights { magnitude: 0.0, significance: 0.0, felt: 0.0, alert: 0.0, recency: 0.0, ..Default::default() };
+		let ids: Vec<String> = rank_events(&response, &none).iter().map(|ranked| ranked.event.id.to_string()).collect();
+		assert_eq!(ids, ["old", "untimed", "new", "future"]);
+	}
+}
@@ -0,0 +1 @@
+pub mod tests;
\ No newline at end of file
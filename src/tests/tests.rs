@@ -1 +1,167 @@
-// TODO: Test could be added
\ No newline at end of file
+//! Fixtures shared by the unit tests, and tests of the client and queries.
+
+use serde_json::{json, Value};
+use crate::*;
+
+/// `metadata.url` of test responses to FDSN queries.
+pub(crate) const QUERY_URL: &str = "https://earthquake.usgs.gov/fdsnws/event/1/query";
+
+/// Contents of the response stored as `tests/fixtures/<name>`.
+pub(crate) fn fixture(name: &str) -> String {
+	let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name);
+	std::fs::read_to_string(&path).unwrap_or_else(|err| panic!("Failed to read {}: {}", path.display(), err))
+}
+
+/// GeoJSON event `id` at `[longitude, latitude, depth]`.
+pub(crate) fn feature(id: &str, coordinates: [f64; 3], properties: Value) -> Value {
+	json!({ "type": "Feature", "properties": properties, "geometry": { "type": "Point", "coordinates": coordinates }, "id": id })
+}
+
+/// FeatureCollection of `features` as served for `url`.
+pub(crate) fn collection(url: &str, features: Vec<Value>) -> Value {
+	json!({
+		"type": "FeatureCollection",
+		"metadata": { "generated": 1718028000000u64, "url": url, "title": "test", "status": 200, "api": "1.14.1", "count": features.len() },
+		"features": features,
+	})
+}
+
+/// Parses a [`collection`] of `features`.
+pub(crate) fn response(url: &str, features: Vec<Value>) -> EarthquakeResponse {
+	parse_response(&collection(url, features).to_string()).unwrap()
+}
+
+#[test]
+fn from_url_round_trips_build_url() {
+	let client = UsgsClient::new();
+	let url = client.query()
+		.start_time(2024, 1, 1, 0, 0)
+		.end_time(2024, 6, 30, 12, 30)
+		.min_magnitude(4.5)
+		.max_magnitude(8.0)
+		.alert_level(AlertLevel::Orange)
+		.order_by(OrderBy::MagnitudeAsc)
+		.catalog("us")
+		.circle(38.5, 142.25, 300.0)
+		.min_felt(10)
+		.min_significance(600)
+		.include_deleted()
+		.build_url()
+		.unwrap();
+	assert_eq!(UsgsQuery::from_url(&client, &url).unwrap().build_url().unwrap(), url);
+
+	let rectangle = "https://earthquake.usgs.gov/fdsnws/event/1/query?format=geojson&jsonerror=true&starttime=2024-01-01T00%3A00%3A00&endtime=2024-02-01T00%3A00%3A00&minmagnitude=2.5&maxmagnitude=10&orderby=time&minlatitude=35.5&maxlatitude=42.1&minlongitude=25.7&maxlongitude=44.8";
+	assert_eq!(UsgsQuery::from_url(&client, rectangle).unwrap().build_url().unwrap(), rectangle);
+}
+
+#[test]
+fn from_url_rejects_unknown_and_invalid_parameters() {
+	let client = UsgsClient::new();
+	let base = "https://earthquake.usgs.gov/fdsnws/event/1/query?starttime=2024-01-01";
+
+	let unknown = UsgsQuery::from_url(&client, &format!("{}&mindepth=10", base));
+	assert!(matches!(unknown, Err(UsgsError::UnsupportedParameter(parameter)) if parameter == "mindepth"));
+
+	for invalid in ["minmagnitude=strong", "starttime=yesterday", "includedeleted=yes", "latitude=38&longitude=142"] {
+		let result = UsgsQuery::from_url(&client, &format!("{}&{}", base, invalid));
+		assert!(matches!(result, Err(UsgsError::InvalidUrl(_))), "{}", invalid);
+	}
+	assert!(matches!(UsgsQuery::from_url(&client, "not a url"), Err(UsgsError::InvalidUrl(_))));
+}
+
+#[tokio::test]
+async fn split_queries_are_cached_and_not_fetched_raw() {
+	let client = UsgsClient::new();
+	let query = || client.query()
+		.start_time(2024, 1, 1, 0, 0)
+		.min_alert_level(AlertLevel::Yellow)
+		.split_on_timeout(Duration::days(7));
+	assert!(matches!(query().fetch_raw().await, Err(UsgsError::Config(_))));
+
+	let merged = collection(&query().build_url().unwrap(), vec![
+		feature("nc1", [-122.8, 38.8, 5.0], json!({ "mag": 6.1, "alert": "orange" })),
+		feature("ci1", [-118.2, 34.0, 8.0], json!({ "mag": 3.2 })),
+	]);
+	let cache = MemoryCache::new();
+	let key = format!("{}:split", query().cache_key().unwrap());
+	cache.put(&key, merged.to_string().into_bytes(), std::time::Duration::from_secs(60)).await.unwrap();
+
+	let response = query().fetch_cached(&cache, std::time::Duration::from_secs(60)).await.unwrap();
+	let ids: Vec<&str> = response.features.iter().map(|feature| feature.id.as_str()).collect();
+	assert_eq!(ids, ["nc1"]);
+}
+
+#[test]
+fn from_url_accepts_includesuperseded() {
+	let client = UsgsClient::new();
+	let url = "https://earthquake.usgs.gov/fdsnws/event/1/query?starttime=2024-01-01&includesuperseded=true";
+	let query = UsgsQuery::from_url(&client, url).unwrap();
+	assert!(query.include_superseded);
+	assert!(query.build_url().unwrap().contains("includesuperseded=true"));
+
+	let emsc = UsgsClient::with_profile(FdsnProfile::emsc());
+	let query = UsgsQuery::from_url(&emsc, url).unwrap();
+	assert!(matches!(query.build_url(), Err(UsgsError::UnsupportedParameter(parameter)) if parameter == "includesuperseded"));
+}
+
+#[test]
+fn min_alert_level_resets_the_server_side_level() {
+	let client = UsgsClient::new();
+	let url = client.query()
+		.start_time(2024, 1, 1, 0, 0)
+		.min_alert_level(AlertLevel::Red)
+		.min_alert_level(AlertLevel::Orange)
+		.build_url()
+		.unwrap();
+	assert!(!url.contains("alertlevel"), "{}", url);
+
+	let url = client.query()
+		.start_time(2024, 1, 1, 0, 0)
+		.min_alert_level(AlertLevel::Red)
+		.build_url()
+		.unwrap();
+	assert!(url.contains("alertlevel=red"), "{}", url);
+}
+
+#[tokio::test]
+async fn split_cache_hits_use_the_client_parse_mode() {
+	let client = UsgsClient::builder().parse_mode(ParseMode::Strict).build().unwrap();
+	let query = || client.query()
+		.start_time(2024, 1, 1, 0, 0)
+		.split_on_timeout(Duration::days(7));
+	let merged = collection(&query().build_url().unwrap(), vec![
+		feature("nc1", [-122.8, 38.8, 5.0], json!({ "mag": 6.1, "shaking": "strong" })),
+	]);
+	let cache = MemoryCache::new();
+	let key = format!("{}:split", query().cache_key().unwrap());
+	cache.put(&key, merged.to_string().into_bytes(), std::time::Duration::from_secs(60)).await.unwrap();
+
+	let result = query().fetch_cached(&cache, std::time::Duration::from_secs(60)).await;
+	assert!(matches!(result, Err(UsgsError::Schema(message)) if message.contains("shaking")));
+}
+
+#[test]
+fn parse_unfiltered_treats_an_empty_body_as_no_events() {
+	let client = UsgsClient::new();
+	let query = client.query().start_time(2024, 1, 1, 0, 0);
+	let url = query.build_url().unwrap();
+	for body in ["", " \n"] {
+		let response = query.parse_unfiltered(body, &url).unwrap();
+		assert!(response.features.is_empty());
+		assert_eq!(response.metadata.count, 0);
+		assert_eq!(response.metadata.url.as_str(), url);
+	}
+}
+
+#[test]
+fn parse_unfiltered_rejects_non_200_statuses() {
+	let client = UsgsClient::new();
+	let query = client.query().start_time(2024, 1, 1, 0, 0);
+	let url = query.build_url().unwrap();
+	let mut body = collection(&url, vec![feature("us1", [142.4, 38.1, 20.0], json!({ "mag": 5.0 }))]);
+	assert!(query.parse_unfiltered(&body.to_string(), &url).is_ok());
+
+	body["metadata"]["status"] = json!(503);
+	let result = query.parse_unfiltered(&body.to_string(), &url);
+	assert!(matches!(result, Err(UsgsError::ApiStatus(status)) if status == reqwest::StatusCode::SERVICE_UNAVAILABLE), "{:?}", result);
+}
@@ -0,0 +1 @@
+pub mod time_support;
\ No newline at end of file
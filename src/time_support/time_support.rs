@@ -0,0 +1,65 @@
+use chrono::{DateTime, NaiveDateTime};
+use time::OffsetDateTime;
+use crate::models::models::{EarthquakeFeatures, EarthquakeResponse};
+use crate::UsgsQuery;
+
+/// UTC wall-clock time of an `OffsetDateTime`, whatever its offset.
+fn to_naive_utc(time: OffsetDateTime) -> NaiveDateTime {
+	DateTime::from_timestamp(time.unix_timestamp(), time.nanosecond())
+		.expect("OffsetDateTime is within chrono's range")
+		.naive_utc()
+}
+
+fn from_millis(millis: i64) -> Option<OffsetDateTime> {
+	OffsetDateTime::from_unix_timestamp_nanos(i128::from(millis) * 1_000_000).ok()
+}
+
+impl UsgsQuery<'_> {
+	/// Sets the start time from a `time` crate timestamp in any offset.
+	///
+	/// ## Example
+	/// ```rust
+	/// use time::{OffsetDateTime, UtcOffset};
+	/// use usgs_earthquake_api::UsgsClient;
+	///
+	/// // 2024-01-01 03:00 in Istanbul
+	/// let start = OffsetDateTime::from_unix_timestamp(1_704_067_200).unwrap()
+	///     .to_offset(UtcOffset::from_hms(3, 0, 0).unwrap());
+	/// let client = UsgsClient::new();
+	/// let url = client.query()
+	///     .start_offset_datetime(start)
+	///     .end_offset_datetime(OffsetDateTime::from_unix_timestamp(1_704_153_600).unwrap())
+	///     .build_url()
+	///     .unwrap();
+	/// assert!(url.contains("starttime=2024-01-01T00%3A00%3A00"));
+	/// ```
+	pub fn start_offset_datetime(mut self, start: OffsetDateTime) -> Self {
+		self.start_time = Some(to_naive_utc(start));
+		self
+	}
+
+	/// Sets the end time from a `time` crate timestamp in any offset.
+	pub fn end_offset_datetime(mut self, end: OffsetDateTime) -> Self {
+		self.end_time = to_naive_utc(end);
+		self
+	}
+}
+
+impl EarthquakeFeatures {
+	/// Origin time of the event as a `time` crate timestamp in UTC.
+	pub fn origin_offset_datetime(&self) -> Option<OffsetDateTime> {
+		from_millis(self.properties.time? as i64)
+	}
+
+	/// Time the event was last updated as a `time` crate timestamp in UTC.
+	pub fn updated_offset_datetime(&self) -> Option<OffsetDateTime> {
+		from_millis(self.properties.updated_time? as i64)
+	}
+}
+
+impl EarthquakeResponse {
+	/// Time the server generated this response as a `time` crate timestamp.
+	pub fn generated_offset_datetime(&self) -> OffsetDateTime {
+		from_millis(self.generated_at().timestamp_millis()).unwrap_or(OffsetDateTime::UNIX_EPOCH)
+	}
+}
@@ -0,0 +1 @@
+pub mod timezone;
\ No newline at end of file
@@ -0,0 +1,18 @@
+use std::sync::OnceLock;
+use chrono_tz::Tz;
+use tzf_rs::DefaultFinder;
+use crate::models::models::EarthquakeFeatures;
+
+/// Time zone polygons, parsed on first use.
+fn finder() -> &'static DefaultFinder {
+	static FINDER: OnceLock<DefaultFinder> = OnceLock::new();
+	FINDER.get_or_init(DefaultFinder::new)
+}
+
+impl EarthquakeFeatures {
+	/// IANA time zone at the epicenter, looked up in the embedded time zone
+	/// boundaries. Offshore events get the nautical `Etc/GMT±n` zones.
+	pub fn timezone(&self) -> Option<Tz> {
+		finder().get_tz_name(self.geometry.longitude(), self.geometry.latitude()).parse().ok()
+	}
+}
@@ -0,0 +1,2 @@
+mod tsunami;
+pub use tsunami::*;
@@ -0,0 +1,223 @@
+use chrono::{DateTime, Utc};
+use log::debug;
+use reqwest::{Client, Url};
+use serde::Deserialize;
+use crate::error::error::UsgsError;
+use crate::models::ids::EventId;
+use crate::models::models::EarthquakeFeatures;
+
+/// Active alerts endpoint of the NWS API, which carries the messages of the
+/// U.S. Tsunami Warning Centers.
+pub const NWS_ALERTS_URL: &str = "https://api.weather.gov/alerts/active";
+
+/// The NWS API rejects requests without a `User-Agent`.
+const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+/// Threat level of a tsunami alert, from lowest to highest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TsunamiLevel {
+	/// A tsunami is possible; the danger is still being evaluated.
+	Watch,
+
+	/// Strong currents and waves dangerous near the water are expected.
+	Advisory,
+
+	/// Dangerous coastal flooding and powerful currents are expected.
+	Warning,
+}
+
+impl TsunamiLevel {
+	const ALL: [TsunamiLevel; 3] = [TsunamiLevel::Watch, TsunamiLevel::Advisory, TsunamiLevel::Warning];
+
+	/// NWS event name of the level (e.g. `Tsunami Warning`).
+	pub fn event_name(&self) -> &'static str {
+		match self {
+			TsunamiLevel::Watch => "Tsunami Watch",
+			TsunamiLevel::Advisory => "Tsunami Advisory",
+			TsunamiLevel::Warning => "Tsunami Warning",
+		}
+	}
+
+	fn from_event_name(name: &str) -> Option<Self> {
+		Self::ALL.into_iter().find(|level| level.event_name().eq_ignore_ascii_case(name))
+	}
+}
+
+/// Active tsunami alert issued by the NWS.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TsunamiAlert {
+	/// Alert identifier.
+	pub id: String,
+
+	/// Threat level.
+	pub level: TsunamiLevel,
+
+	/// Time the alert (or its latest update) was sent.
+	pub sent: DateTime<Utc>,
+
+	/// Time the alert expires, if announced.
+	pub expires: Option<DateTime<Utc>>,
+
+	/// Affected coastal zones.
+	pub area: String,
+
+	/// One-line summary.
+	pub headline: Option<String>,
+
+	/// Full text of the message.
+	pub description: Option<String>,
+}
+
+/// Tsunami threat for an event, see [`TsunamiService::status_for`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TsunamiStatus {
+	/// Event the status was determined for.
+	pub event_id: EventId,
+
+	/// Whether USGS flagged the event for tsunami potential (`tsunami == 1`).
+	pub flagged: bool,
+
+	/// Active alerts sent since the origin time of the event.
+	pub alerts: Vec<TsunamiAlert>,
+}
+
+impl TsunamiStatus {
+	/// Whether an alert is currently active for the event.
+	pub fn is_active_threat(&self) -> bool {
+		!self.alerts.is_empty()
+	}
+
+	/// Highest level among the active alerts.
+	pub fn highest_level(&self) -> Option<TsunamiLevel> {
+		self.alerts.iter().map(|alert| alert.level).max()
+	}
+}
+
+#[derive(Deserialize)]
+struct AlertCollection {
+	features: Vec<AlertFeature>,
+}
+
+#[derive(Deserialize)]
+struct AlertFeature {
+	properties: AlertProperties,
+}
+
+#[derive(Deserialize)]
+struct AlertProperties {
+	id: String,
+	event: String,
+	sent: DateTime<Utc>,
+	expires: Option<DateTime<Utc>>,
+	#[serde(rename = "areaDesc", default)]
+	area: String,
+	headline: Option<String>,
+	description: Option<String>,
+}
+
+/// Client of the NWS alerts API for cross-checking events against active
+/// tsunami alerts.
+///
+/// The NWS publishes the messages of the U.S. Tsunami Warning Centers,
+/// covering U.S. and territorial coasts; threats elsewhere are not listed.
+/// Alerts do not name the earthquake they were issued for, so those sent
+/// since the origin time of an event are attributed to it.
+///
+/// ## Example
+/// ```rust,no_run
+/// use usgs_earthquake_api::UsgsClient;
+/// use usgs_earthquake_api::tsunami::TsunamiService;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let response = UsgsClient::new().query().min_magnitude(7.0).fetch().await.unwrap();
+///     let tsunami = TsunamiService::new();
+///     for event in &response.features {
+///         let status = tsunami.status_for(event).await.unwrap();
+///         if let Some(level) = status.highest_level() {
+///             println!("{}: {:?}", event, level);
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct TsunamiService {
+	client: Client,
+	base_url: String,
+}
+
+impl Default for TsunamiService {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl TsunamiService {
+	/// Creates a client of the NWS alerts API.
+	pub fn new() -> Self {
+		Self::with_base_url(NWS_ALERTS_URL)
+	}
+
+	/// Creates a client of an alerts endpoint compatible with the NWS API.
+	pub fn with_base_url(base_url: &str) -> Self {
+		Self {
+			client: Client::builder().user_agent(USER_AGENT).build().unwrap_or_default(),
+			base_url: base_url.to_string(),
+		}
+	}
+
+	/// Uses `client` for the requests, e.g. one with a contact address in
+	/// its `User-Agent` as the NWS asks for.
+	pub fn http_client(mut self, client: Client) -> Self {
+		self.client = client;
+		self
+	}
+
+	/// Fetches all active tsunami watches, advisories and warnings.
+	pub async fn active_alerts(&self) -> Result<Vec<TsunamiAlert>, UsgsError> {
+		let mut url = Url::parse(&self.base_url).map_err(|e| UsgsError::InvalidUrl(e.to_string()))?;
+		let events = TsunamiLevel::ALL.map(|level| level.event_name()).join(",");
+		url.query_pairs_mut().append_pair("event", &events);
+		debug!("querying tsunami alerts {}", url);
+
+		let response = self.client.get(url)
+			.header(reqwest::header::ACCEPT, "application/geo+json")
+			.send().await.map_err(UsgsError::request)?;
+		let status = response.status();
+		let body = response.text().await?;
+		if !status.is_success() {
+			return Err(UsgsError::api(status, &body));
+		}
+		let collection: AlertCollection = serde_json::from_str(&body)?;
+		Ok(collection.features.into_iter()
+			.filter_map(|feature| {
+				let alert = feature.properties;
+				Some(TsunamiAlert {
+					level: TsunamiLevel::from_event_name(&alert.event)?,
+					id: alert.id,
+					sent: alert.sent,
+					expires: alert.expires,
+					area: alert.area,
+					headline: alert.headline,
+					description: alert.description,
+				})
+			})
+			.collect())
+	}
+
+	/// Cross-checks `event` against the active alerts.
+	///
+	/// Only events flagged with `tsunami == 1` are checked; for others no
+	/// request is made and the status lists no alerts.
+	pub async fn status_for(&self, event: &EarthquakeFeatures) -> Result<TsunamiStatus, UsgsError> {
+		let flagged = event.properties.tsunami == Some(1);
+		let alerts = match (flagged, event.origin_time()) {
+			(true, Some(origin)) => self.active_alerts().await?
+				.into_iter()
+				.filter(|alert| alert.sent >= origin)
+				.collect(),
+			_ => Vec::new(),
+		};
+		Ok(TsunamiStatus { event_id: event.id.clone(), flagged, alerts })
+	}
+}
@@ -0,0 +1 @@
+pub mod utm;
\ No newline at end of file
@@ -0,0 +1,217 @@
+use std::fmt::Display;
+use crate::models::models::EarthquakeGeometry;
+
+const WGS84_A: f64 = 6378137.0;
+const WGS84_F: f64 = 1.0 / 298.257223563;
+const SCALE_FACTOR: f64 = 0.9996;
+const FALSE_EASTING: f64 = 500000.0;
+const FALSE_NORTHING: f64 = 10000000.0;
+
+const LATITUDE_BANDS: &[u8] = b"CDEFGHJKLMNPQRSTUVWX";
+const MGRS_COLUMN_SETS: [&[u8]; 3] = [b"STUVWXYZ", b"ABCDEFGH", b"JKLMNPQR"];
+const MGRS_ROW_LETTERS: &[u8] = b"ABCDEFGHJKLMNPQRSTUV";
+
+/// Universal Transverse Mercator coordinate (WGS84).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UtmCoordinate {
+	/// UTM zone number (1-60).
+	pub zone: u8,
+
+	/// Latitude band letter (`C`-`X`).
+	pub band: char,
+
+	/// Easting in meters.
+	pub easting: f64,
+
+	/// Northing in meters (false northing applied in the southern hemisphere).
+	pub northing: f64,
+}
+
+impl UtmCoordinate {
+	/// Converts a latitude/longitude pair into UTM.
+	///
+	/// Returns `None` outside the UTM latitude range (80°S to 84°N).
+	pub fn from_lat_lon(latitude: f64, longitude: f64) -> Option<Self> {
+		if !(-80.0..=84.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+			return None;
+		}
+
+		let zone = utm_zone(latitude, longitude);
+		let central_meridian = ((zone as f64 - 1.0) * 6.0 - 180.0 + 3.0).to_radians();
+
+		let e2 = WGS84_F * (2.0 - WGS84_F);
+		let e4 = e2 * e2;
+		let e6 = e4 * e2;
+		let ep2 = e2 / (1.0 - e2);
+
+		let phi = latitude.to_radians();
+		let n = WGS84_A / (1.0 - e2 * phi.sin().powi(2)).sqrt();
+		let t = phi.tan().powi(2);
+		let c = ep2 * phi.cos().powi(2);
+		let a = phi.cos() * (longitude.to_radians() - central_meridian);
+
+		let m = WGS84_A * ((1.0 - e2 / 4.0 - 3.0 * e4 / 64.0 - 5.0 * e6 / 256.0) * phi
+			- (3.0 * e2 / 8.0 + 3.0 * e4 / 32.0 + 45.0 * e6 / 1024.0) * (2.0 * phi).sin()
+			+ (15.0 * e4 / 256.0 + 45.0 * e6 / 1024.0) * (4.0 * phi).sin()
+			- (35.0 * e6 / 3072.0) * (6.0 * phi).sin());
+
+		let easting = SCALE_FACTOR * n * (a
+			+ (1.0 - t + c) * a.powi(3) / 6.0
+			+ (5.0 - 18.0 * t + t * t + 72.0 * c - 58.0 * ep2) * a.powi(5) / 120.0)
+			+ FALSE_EASTING;
+
+		let mut northing = SCALE_FACTOR * (m + n * phi.tan() * (a * a / 2.0
+			+ (5.0 - t + 9.0 * c + 4.0 * c * c) * a.powi(4) / 24.0
+			+ (61.0 - 58.0 * t + t * t + 600.0 * c - 330.0 * ep2) * a.powi(6) / 720.0));
+		if latitude < 0.0 {
+			northing += FALSE_NORTHING;
+		}
+
+		let band_index = (((latitude + 80.0) / 8.0).floor() as usize).min(LATITUDE_BANDS.len() - 1);
+
+		Some(Self {
+			zone,
+			band: LATITUDE_BANDS[band_index] as char,
+			easting,
+			northing,
+		})
+	}
+
+	/// Returns `true` for coordinates in the northern hemisphere.
+	pub fn is_north(&self) -> bool {
+		self.band >= 'N'
+	}
+
+	/// Formats the coordinate as an MGRS string.
+	///
+	/// `precision` is the number of digits per axis (1-5), i.e. 5 gives
+	/// 1 m resolution and 1 gives 10 km resolution.
+	pub fn to_mgrs(&self, precision: u8) -> String {
+		let precision = precision.clamp(1, 5) as u32;
+
+		let column = (self.easting / 100000.0).floor() as usize;
+		let column_letters = MGRS_COLUMN_SETS[self.zone as usize % 3];
+		let column_letter = column_letters[(column.max(1) - 1) % column_letters.len()] as char;
+
+		let row_offset = if self.zone.is_multiple_of(2) { 5 } else { 0 };
+		let row = (self.northing / 100000.0).floor() as usize;
+		let row_letter = MGRS_ROW_LETTERS[(row + row_offset) % MGRS_ROW_LETTERS.len()] as char;
+
+		let divisor = 10f64.powi(5 - precision as i32);
+		let easting = ((self.easting % 100000.0) / divisor).floor() as u64;
+		let northing = ((self.northing % 100000.0) / divisor).floor() as u64;
+
+		format!("{}{}{}{}{:0width$}{:0width$}", self.zone, self.band, column_letter, row_letter,
+			easting, northing, width = precision as usize)
+	}
+}
+
+impl Display for UtmCoordinate {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}{} {:.0}E {:.0}N", self.zone, self.band, self.easting, self.northing)
+	}
+}
+
+fn utm_zone(latitude: f64, longitude: f64) -> u8 {
+	// Norway and Svalbard exceptions.
+	if (56.0..64.0).contains(&latitude) && (3.0..12.0).contains(&longitude) {
+		return 32;
+	}
+	if (72.0..=84.0).contains(&latitude) && (0.0..42.0).contains(&longitude) {
+		return match longitude {
+			l if l < 9.0 => 31,
+			l if l < 21.0 => 33,
+			l if l < 33.0 => 35,
+			_ => 37,
+		};
+	}
+	((((longitude + 180.0) / 6.0).floor() as i32).clamp(0, 59) + 1) as u8
+}
+
+impl EarthquakeGeometry {
+	/// Epicenter as a UTM coordinate, or `None` outside 80°S to 84°N.
+	pub fn to_utm(&self) -> Option<UtmCoordinate> {
+		UtmCoordinate::from_lat_lon(self.latitude(), self.longitude())
+	}
+
+	/// Epicenter as an MGRS string with `precision` digits per axis (1-5).
+	pub fn to_mgrs(&self, precision: u8) -> Option<String> {
+		self.to_utm().map(|utm| utm.to_mgrs(precision))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Asserts the conversion of `(latitude, longitude)` against reference
+	/// values from the Krüger series, which are accurate to well below a
+	/// millimeter.
+	fn assert_utm(latitude: f64, longitude: f64, zone: u8, band: char, easting: f64, northing: f64) -> UtmCoordinate {
+		let utm = UtmCoordinate::from_lat_lon(latitude, longitude).unwrap();
+		assert_eq!((utm.zone, utm.band), (zone, band), "{} {}", latitude, longitude);
+		assert!((utm.easting - easting).abs() < 0.05, "easting {} instead of {}", utm.easting, easting);
+		assert!((utm.northing - northing).abs() < 0.05, "northing {} instead of {}", utm.northing, northing);
+		utm
+	}
+
+	#[test]
+	fn converts_reference_points() {
+		let origin = assert_utm(0.0, 0.0, 31, 'N', 166021.443, 0.0);
+		assert_eq!(origin.to_mgrs(5), "31NAA6602100000");
+
+		let washington = assert_utm(38.8895, -77.0353, 18, 'S', 323478.063, 4306483.242);
+		assert_eq!(washington.to_mgrs(5), "18SUJ2347806483");
+		assert_eq!(washington.to_mgrs(1), "18SUJ20");
+
+		let tokyo = assert_utm(35.6586, 139.7454, 54, 'S', 386437.603, 3946808.155);
+		assert_eq!(tokyo.to_mgrs(3), "54SUE864468");
+	}
+
+	#[test]
+	fn applies_norway_and_svalbard_zones() {
+		// Bergen lies in zone 31 by longitude but uses the widened zone 32V.
+		let bergen = assert_utm(60.39, 5.32, 32, 'V', 297230.220, 6700510.175);
+		assert_eq!(bergen.to_mgrs(5), "32VKN9723000510");
+		assert_eq!(UtmCoordinate::from_lat_lon(60.39, 2.9).unwrap().zone, 31);
+		assert_eq!(UtmCoordinate::from_lat_lon(55.9, 5.32).unwrap().zone, 31);
+
+		// Svalbard only has the odd zones 31X to 37X.
+		assert_utm(80.0, 5.0, 31, 'X', 538764.058, 8882252.170);
+		let longyearbyen = assert_utm(78.22, 15.65, 33, 'X', 514813.527, 8683004.153);
+		assert_eq!(longyearbyen.to_mgrs(5), "33XWG1481383004");
+		assert_utm(74.0, 38.0, 37, 'X', 469234.482, 8212296.180);
+		assert_eq!(UtmCoordinate::from_lat_lon(78.0, 30.0).unwrap().zone, 35);
+		assert_eq!(UtmCoordinate::from_lat_lon(71.9, 15.65).unwrap().zone, 33);
+		assert_eq!(UtmCoordinate::from_lat_lon(71.9, 8.0).unwrap().zone, 32);
+	}
+
+	#[test]
+	fn converts_southern_hemisphere() {
+		let sydney = assert_utm(-33.8568, 151.2153, 56, 'H', 334900.570, 6252288.753);
+		assert!(!sydney.is_north());
+		assert_eq!(sydney.to_mgrs(5), "56HLH3490052288");
+
+		let lima = assert_utm(-12.05, -77.04, 18, 'L', 277925.346, 8667091.851);
+		assert_eq!(lima.to_mgrs(4), "18LTM77926709");
+
+		let ushuaia = assert_utm(-54.8, -68.3, 19, 'F', 545000.053, 3927239.381);
+		assert_eq!(ushuaia.to_mgrs(5), "19FEV4500027239");
+	}
+
+	#[test]
+	fn assigns_latitude_bands() {
+		let band = |latitude: f64| UtmCoordinate::from_lat_lon(latitude, 10.0).unwrap().band;
+		assert_eq!(band(-80.0), 'C');
+		assert_eq!(band(-72.1), 'C');
+		assert_eq!(band(-0.1), 'M');
+		assert_eq!(band(0.0), 'N');
+		assert_eq!(band(71.9), 'W');
+		assert_eq!(band(72.0), 'X');
+		assert_eq!(band(84.0), 'X');
+		assert!(band(0.0) >= 'N' && UtmCoordinate::from_lat_lon(0.0, 10.0).unwrap().is_north());
+
+		assert_eq!(UtmCoordinate::from_lat_lon(84.1, 10.0), None);
+		assert_eq!(UtmCoordinate::from_lat_lon(-80.1, 10.0), None);
+	}
+}
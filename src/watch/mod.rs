@@ -0,0 +1 @@
+pub mod watch;
\ No newline at end of file
@@ -0,0 +1,350 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use chrono::{DateTime, Duration, Utc};
+use log::debug;
+use crate::cache::cache::{MemorySeenStore, SeenEventStore};
+use crate::error::error::UsgsError;
+use crate::models::ids::EventId;
+use crate::models::models::{EarthquakeFeatures, EarthquakeResponse};
+use crate::{AlertLevel, ConfigureQuery, UsgsClient, UsgsQuery};
+
+/// Notification of a [`Watcher::poll_changes`].
+#[derive(Debug)]
+pub enum WatchEvent {
+	/// Event reported for the first time.
+	New(EarthquakeFeatures),
+
+	/// Previously reported event whose alert level or magnitude was raised.
+	Updated {
+		event: EarthquakeFeatures,
+		field_changes: Vec<FieldChange>,
+	},
+
+	/// Previously reported event that was withdrawn, see [`Watcher::include_deleted`].
+	Deleted(EarthquakeFeatures),
+}
+
+impl WatchEvent {
+	/// Event the notification is about, in its current revision.
+	pub fn event(&self) -> &EarthquakeFeatures {
+		match self {
+			WatchEvent::New(event) | WatchEvent::Updated { event, .. } | WatchEvent::Deleted(event) => event,
+		}
+	}
+}
+
+/// Upward revision of an event reported by [`WatchEvent::Updated`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FieldChange {
+	/// PAGER alert level raised or assigned for the first time.
+	AlertLevel { from: Option<AlertLevel>, to: AlertLevel },
+
+	/// Magnitude revised upward.
+	Magnitude { from: f64, to: f64 },
+}
+
+/// Fields compared between polls to detect upgrades.
+#[derive(Debug, Clone, Copy)]
+struct Revision {
+	alert: Option<AlertLevel>,
+	magnitude: Option<f64>,
+}
+
+impl Revision {
+	fn of(event: &EarthquakeFeatures) -> Self {
+		Self { alert: event.alert(), magnitude: event.properties.magnitude }
+	}
+
+	fn upgrades_to(&self, current: &Revision) -> Vec<FieldChange> {
+		let mut changes = Vec::new();
+		if let Some(to) = current.alert.filter(|to| self.alert.is_none_or(|from| from < *to)) {
+			changes.push(FieldChange::AlertLevel { from: self.alert, to });
+		}
+		if let (Some(from), Some(to)) = (self.magnitude, current.magnitude) && to > from {
+			changes.push(FieldChange::Magnitude { from, to });
+		}
+		changes
+	}
+}
+
+/// Polling subscription reporting events that were not seen before.
+///
+/// Every [`Watcher::poll`] fetches the events of a trailing time window
+/// (narrowed by [`Watcher::configure`]) and returns those whose ids the
+/// [`SeenEventStore`] did not know yet. Polls answered with a response that
+/// was not regenerated since the previous poll (judged by
+/// `metadata.generated`) are skipped without touching the store.
+///
+/// With [`Watcher::include_deleted`] events withdrawn after being reported
+/// are reported once more with `status` `deleted`, so mirrored stores can
+/// remove them.
+///
+/// [`Watcher::poll_changes`] additionally reports events whose PAGER alert
+/// level or magnitude was raised since an earlier poll, as long as they stay
+/// within the window.
+///
+/// Seen ids are kept in memory by default; use a shared store such as
+/// [`RedisStore`](crate::RedisStore) so replicas do not alert twice.
+///
+/// ## Example
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use usgs_earthquake_api::{UsgsClient, Watcher};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let mut watcher = Watcher::new(UsgsClient::new()).configure(|query| query.min_magnitude(4.5));
+///     loop {
+///         for event in watcher.poll().await.unwrap() {
+///             println!("new event {}", event.id);
+///         }
+///         tokio::time::sleep(Duration::from_secs(60)).await;
+///     }
+/// }
+/// ```
+pub struct Watcher<S: SeenEventStore = MemorySeenStore> {
+	client: UsgsClient,
+	configure: Arc<ConfigureQuery>,
+	window: Duration,
+	seen: S,
+	include_deleted: bool,
+	last_generated: Option<DateTime<Utc>>,
+	revisions: HashMap<EventId, Revision>,
+}
+
+impl Watcher {
+	/// Creates a watcher of worldwide events of the past hour.
+	pub fn new(client: UsgsClient) -> Self {
+		Self {
+			client,
+			configure: Arc::new(|query| query),
+			window: Duration::hours(1),
+			seen: MemorySeenStore::new(),
+			include_deleted: false,
+			last_generated: None,
+			revisions: HashMap::new(),
+		}
+	}
+}
+
+impl<S: SeenEventStore> Watcher<S> {
+	/// Narrows the polled query, e.g. by magnitude, region or catalog.
+	///
+	/// The time range is always the trailing window.
+	pub fn configure(mut self, configure: impl for<'a> Fn(UsgsQuery<'a>) -> UsgsQuery<'a> + Send + Sync + 'static) -> Self {
+		self.configure = Arc::new(configure);
+		self
+	}
+
+	/// Sets how far back each poll looks (default one hour).
+	///
+	/// Should comfortably exceed the poll interval, since events are often
+	/// published minutes after their origin time.
+	pub fn window(mut self, window: Duration) -> Self {
+		self.window = window;
+		self
+	}
+
+	/// Also reports deletions of events, see [`EarthquakeFeatures::is_deleted`].
+	pub fn include_deleted(mut self) -> Self {
+		self.include_deleted = true;
+		self
+	}
+
+	/// Remembers seen events in another store, e.g. one shared between replicas.
+	pub fn seen_store<T: SeenEventStore>(self, seen: T) -> Watcher<T> {
+		Watcher {
+			client: self.client,
+			configure: self.configure,
+			window: self.window,
+			seen,
+			include_deleted: self.include_deleted,
+			last_generated: self.last_generated,
+			revisions: self.revisions,
+		}
+	}
+
+	/// Seen-event store of this watcher.
+	pub fn seen(&self) -> &S {
+		&self.seen
+	}
+
+	/// Fetches the window and returns the events not seen before.
+	///
+	/// The first poll reports every event of the window.
+	pub async fn poll(&mut self) -> Result<Vec<EarthquakeFeatures>, UsgsError> {
+		Ok(self.poll_changes().await?.into_iter()
+			.filter_map(|change| match change {
+				WatchEvent::New(event) | WatchEvent::Deleted(event) => Some(event),
+				WatchEvent::Updated { .. } => None,
+			})
+			.collect())
+	}
+
+	/// Fetches the window and returns new events along with upgrades of
+	/// events reported before.
+	///
+	/// Upgrades are judged against the previous poll of this watcher, so
+	/// after a restart the first poll only records the current revisions.
+	/// Each upgrade is marked in the seen-event store as well, so replicas
+	/// sharing a store report it once.
+	///
+	/// ## Example
+	/// ```rust,no_run
+	/// use usgs_earthquake_api::{UsgsClient, WatchEvent, Watcher};
+	///
+	/// #[tokio::main]
+	/// async fn main() {
+	///     let mut watcher = Watcher::new(UsgsClient::new()).configure(|query| query.min_magnitude(5.0));
+	///     for change in watcher.poll_changes().await.unwrap() {
+	///         if let WatchEvent::Updated { event, field_changes } = change {
+	///             println!("{} revised: {:?}", event.id, field_changes);
+	///         }
+	///     }
+	/// }
+	/// ```
+	pub async fn poll_changes(&mut self) -> Result<Vec<WatchEvent>, UsgsError> {
+		let mut query = (self.configure)(self.client.query().worldwide().since(self.window));
+		if self.include_deleted {
+			query = query.include_deleted();
+		}
+		let response = query.fetch().await?;
+		self.changes_in(response).await
+	}
+
+	/// Compares a fetched window with the previous one, see [`Watcher::poll_changes`].
+	async fn changes_in(&mut self, response: EarthquakeResponse) -> Result<Vec<WatchEvent>, UsgsError> {
+		let generated = response.metadata.generated_timestamp;
+		if self.last_generated.is_some_and(|last| generated <= last) {
+			debug!("response generated at {} was already processed, skipping", generated);
+			return Ok(Vec::new());
+		}
+		self.last_generated = Some(generated);
+
+		let mut changes = Vec::new();
+		let mut revisions = HashMap::with_capacity(response.features.len());
+		for event in response.features {
+			// Deletions are tracked separately so an already reported event is reported again once deleted.
+			if event.is_deleted() {
+				if self.seen.mark_seen(&format!("{}:deleted", event.id)).await? {
+					changes.push(WatchEvent::Deleted(event));
+				}
+				continue;
+			}
+
+			let revision = Revision::of(&event);
+			let previous = self.revisions.get(&event.id).copied();
+			revisions.insert(event.id.clone(), revision);
+			if self.seen.mark_seen(&event.id).await? {
+				changes.push(WatchEvent::New(event));
+				continue;
+			}
+			let Some(previous) = previous else {
+				continue;
+			};
+			let mut field_changes = Vec::new();
+			for change in previous.upgrades_to(&revision) {
+				let seen_id = match change {
+					FieldChange::AlertLevel { to, .. } => format!("{}:alert:{}", event.id, to),
+					FieldChange::Magnitude { to, .. } => format!("{}:magnitude:{}", event.id, to),
+				};
+				if self.seen.mark_seen(&seen_id).await? {
+					field_changes.push(change);
+				}
+			}
+			if !field_changes.is_empty() {
+				changes.push(WatchEvent::Updated { event, field_changes });
+			}
+		}
+		// Events that left the window are no longer tracked.
+		self.revisions = revisions;
+		Ok(changes)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json::{json, Value};
+	use super::*;
+	use crate::parse_response;
+	use crate::tests::tests::{collection, feature, QUERY_URL};
+
+	/// Response generated `minute` minutes into the test with `features`.
+	fn poll(minute: u64, features: Vec<Value>) -> EarthquakeResponse {
+		let mut body = collection(QUERY_URL, features);
+		body["metadata"]["generated"] = json!(1_718_028_000_000u64 + minute * 60_000);
+		parse_response(&body.to_string()).unwrap()
+	}
+
+	fn summary(changes: &[WatchEvent]) -> Vec<(&'static str, &str)> {
+		changes.iter()
+			.map(|change| match change {
+				WatchEvent::New(event) => ("new", event.id.as_str()),
+				WatchEvent::Updated { event, .. } => ("updated", event.id.as_str()),
+				WatchEvent::Deleted(event) => ("deleted", event.id.as_str()),
+			})
+			.collect()
+	}
+
+	#[tokio::test]
+	async fn successive_polls_report_updates_and_deletions() {
+		let mut watcher = Watcher::new(UsgsClient::new()).include_deleted();
+		let first = watcher.changes_in(poll(0, vec![
+			feature("us1", [142.4, 38.1, 20.0], json!({ "mag": 6.1, "alert": "yellow" })),
+			feature("us2", [-122.8, 38.8, 5.0], json!({ "mag": 4.0 })),
+		])).await.unwrap();
+		assert_eq!(summary(&first), [("new", "us1"), ("new", "us2")]);
+
+		let second = watcher.changes_in(poll(1, vec![
+			feature("us1", [142.4, 38.1, 20.0], json!({ "mag": 6.1, "alert": "red" })),
+			feature("us2", [-122.8, 38.8, 5.0], json!({ "mag": 4.0, "status": "deleted" })),
+			feature("us3", [139.7, 35.7, 30.0], json!({ "mag": 4.5 })),
+		])).await.unwrap();
+		assert_eq!(summary(&second), [("updated", "us1"), ("deleted", "us2"), ("new", "us3")]);
+		let WatchEvent::Updated { field_changes, .. } = &second[0] else { unreachable!() };
+		assert_eq!(field_changes, &[FieldChange::AlertLevel { from: Some(AlertLevel::Yellow), to: AlertLevel::Red }]);
+
+		// The same revisions are reported once, and stale responses skipped.
+		let third = poll(2, vec![
+			feature("us1", [142.4, 38.1, 20.0], json!({ "mag": 6.1, "alert": "red" })),
+			feature("us2", [-122.8, 38.8, 5.0], json!({ "mag": 4.0, "status": "deleted" })),
+		]);
+		assert!(watcher.changes_in(third).await.unwrap().is_empty());
+		let stale = poll(1, vec![feature("us4", [0.0, 0.0, 10.0], json!({ "mag": 5.0 }))]);
+		assert!(watcher.changes_in(stale).await.unwrap().is_empty());
+	}
+
+	fn revision(alert: Option<AlertLevel>, magnitude: Option<f64>) -> Revision {
+		Revision { alert, magnitude }
+	}
+
+	#[test]
+	fn upgrades_are_raised_alert_levels_and_magnitudes() {
+		use AlertLevel::{Green, Orange, Red, Yellow};
+
+		let changes = revision(Some(Yellow), Some(6.1)).upgrades_to(&revision(Some(Red), Some(6.4)));
+		assert_eq!(changes, [FieldChange::AlertLevel { from: Some(Yellow), to: Red }, FieldChange::Magnitude { from: 6.1, to: 6.4 }]);
+		assert_eq!(revision(None, None).upgrades_to(&revision(Some(Green), None)), [FieldChange::AlertLevel { from: None, to: Green }]);
+
+		// Downgrades, unchanged and unknown values are not upgrades.
+		assert!(revision(Some(Red), Some(6.4)).upgrades_to(&revision(Some(Orange), Some(6.1))).is_empty());
+		assert!(revision(Some(Orange), Some(6.1)).upgrades_to(&revision(Some(Orange), Some(6.1))).is_empty());
+		assert!(revision(Some(Orange), None).upgrades_to(&revision(None, Some(6.1))).is_empty());
+	}
+
+	#[tokio::test]
+	async fn each_upgrade_is_reported_once() {
+		let event = |alert: &str, magnitude: f64| feature("us1", [142.4, 38.1, 20.0], json!({ "mag": magnitude, "alert": alert }));
+		let mut watcher = Watcher::new(UsgsClient::new());
+		watcher.changes_in(poll(0, vec![event("yellow", 6.1)])).await.unwrap();
+
+		let changes = watcher.changes_in(poll(1, vec![event("red", 6.1)])).await.unwrap();
+		assert_eq!(summary(&changes), [("updated", "us1")]);
+		assert!(watcher.changes_in(poll(2, vec![event("orange", 6.1)])).await.unwrap().is_empty());
+
+		// Back to red was already reported; the magnitude is new.
+		let changes = watcher.changes_in(poll(3, vec![event("red", 6.3)])).await.unwrap();
+		let WatchEvent::Updated { field_changes, .. } = &changes[0] else { unreachable!() };
+		assert_eq!(field_changes, &[FieldChange::Magnitude { from: 6.1, to: 6.3 }]);
+	}
+}
@@ -0,0 +1 @@
+pub mod windows;
\ No newline at end of file
@@ -0,0 +1,289 @@
+use std::collections::{BTreeMap, HashSet};
+use chrono::{DateTime, Datelike, Days, Duration, Months, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Utc};
+use log::debug;
+use reqwest::{StatusCode, Url};
+use crate::error::error::{TimeoutPhase, UsgsError};
+use crate::models::models::{EarthquakeFeatures, EarthquakeResponse};
+use crate::{format_url_time, OrderBy, UsgsQuery};
+
+/// Size of the sub-ranges produced by [`time_windows`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChunkBy {
+	/// Fixed-length windows.
+	Duration(Duration),
+
+	/// Windows of `n` calendar days.
+	Days(u32),
+
+	/// Windows of `n` calendar months.
+	Months(u32),
+
+	/// Windows of `n` calendar years.
+	Years(u32),
+}
+
+impl ChunkBy {
+	/// End of the `n`-th window starting at `start`.
+	///
+	/// Computed from `start` rather than from the previous boundary, so
+	/// month windows starting on the 31st do not drift to the 28th after
+	/// February.
+	fn boundary(self, start: NaiveDateTime, n: u32) -> Option<NaiveDateTime> {
+		match self {
+			ChunkBy::Duration(duration) if duration > Duration::zero() => start.checked_add_signed(duration.checked_mul(n as i32)?),
+			ChunkBy::Days(days) if days > 0 => start.checked_add_days(Days::new(days as u64 * n as u64)),
+			ChunkBy::Months(months) if months > 0 => start.checked_add_months(Months::new(months.checked_mul(n)?)),
+			ChunkBy::Years(years) if years > 0 => start.checked_add_months(Months::new(years.checked_mul(12)?.checked_mul(n)?)),
+			_ => None,
+		}
+	}
+}
+
+/// Iterator over consecutive `(start, end)` sub-ranges of a time range.
+///
+/// Created with [`time_windows`].
+#[derive(Debug, Clone)]
+pub struct TimeWindows {
+	start: NaiveDateTime,
+	next_start: NaiveDateTime,
+	count: u32,
+	end: NaiveDateTime,
+	chunk_by: ChunkBy,
+}
+
+impl Iterator for TimeWindows {
+	type Item = (NaiveDateTime, NaiveDateTime);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.next_start >= self.end {
+			return None;
+		}
+
+		let start = self.next_start;
+		self.count = self.count.saturating_add(1);
+		let window_end = self.chunk_by.boundary(self.start, self.count).unwrap_or(self.end).min(self.end);
+		self.next_start = window_end;
+		Some((start, window_end))
+	}
+}
+
+/// Splits `start..end` into consecutive, non-overlapping windows.
+///
+/// The last window is truncated at `end`. A non-positive chunk size yields
+/// the whole range as a single window. Useful for orchestrating backfills
+/// and resumable jobs that stay below the service's result limits.
+///
+/// ## Example
+/// ```rust
+/// use chrono::NaiveDate;
+/// use usgs_earthquake_api::{time_windows, ChunkBy};
+///
+/// let start = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+/// let end = NaiveDate::from_ymd_opt(2024, 4, 15).unwrap().and_hms_opt(0, 0, 0).unwrap();
+/// assert_eq!(time_windows(start, end, ChunkBy::Months(1)).count(), 4);
+/// ```
+pub fn time_windows(start: NaiveDateTime, end: NaiveDateTime, chunk_by: ChunkBy) -> TimeWindows {
+	TimeWindows {
+		start,
+		next_start: start,
+		count: 0,
+		end,
+		chunk_by,
+	}
+}
+/// Calendar bucket used by [`EarthquakeResponse::group_by`] (in UTC).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Interval {
+	/// Hours, starting at minute zero.
+	Hour,
+
+	/// Days, starting at midnight.
+	Day,
+
+	/// ISO weeks, starting on Monday.
+	Week,
+
+	/// Calendar months.
+	Month,
+
+	/// Calendar years.
+	Year,
+}
+
+impl Interval {
+	/// Start of the bucket containing `time`.
+	pub fn bucket_start(self, time: DateTime<Utc>) -> DateTime<Utc> {
+		let date = time.date_naive();
+		let start = match self {
+			Interval::Hour => date.and_time(NaiveTime::from_hms_opt(time.hour(), 0, 0).unwrap_or(NaiveTime::MIN)),
+			Interval::Day => date.and_time(NaiveTime::MIN),
+			Interval::Week => (date - Days::new(date.weekday().num_days_from_monday() as u64)).and_time(NaiveTime::MIN),
+			Interval::Month => date.with_day(1).unwrap_or(date).and_time(NaiveTime::MIN),
+			Interval::Year => NaiveDate::from_ymd_opt(date.year(), 1, 1).unwrap_or(date).and_time(NaiveTime::MIN),
+		};
+		start.and_utc()
+	}
+}
+
+impl EarthquakeResponse {
+	/// Groups events by the start of the [`Interval`] containing their
+	/// origin time, in chronological order.
+	///
+	/// Only buckets holding events are present. Events without an origin
+	/// time are skipped.
+	///
+	/// ## Example
+	/// ```rust,no_run
+	/// use usgs_earthquake_api::{Interval, UsgsClient};
+	///
+	/// #[tokio::main]
+	/// async fn main() {
+	///     let response = UsgsClient::new().query().min_magnitude(4.5).fetch().await.unwrap();
+	///     for (week, events) in response.group_by(Interval::Week) {
+	///         println!("{}: {} events", week.date_naive(), events.len());
+	///     }
+	/// }
+	/// ```
+	pub fn group_by(&self, interval: Interval) -> BTreeMap<DateTime<Utc>, Vec<&EarthquakeFeatures>> {
+		let mut groups: BTreeMap<DateTime<Utc>, Vec<&EarthquakeFeatures>> = BTreeMap::new();
+		for feature in &self.features {
+			if let Some(time) = feature.origin_time() {
+				groups.entry(interval.bucket_start(time)).or_default().push(feature);
+			}
+		}
+		groups
+	}
+
+	/// Groups events by the UTC date of their origin time, in chronological order.
+	pub fn group_by_day(&self) -> BTreeMap<NaiveDate, Vec<&EarthquakeFeatures>> {
+		self.group_by(Interval::Day).into_iter()
+			.map(|(day, events)| (day.date_naive(), events))
+			.collect()
+	}
+}
+
+/// Whether the service gave up on a request, as it does for long time ranges.
+fn is_server_timeout(err: &UsgsError) -> bool {
+	match err {
+		UsgsError::Timeout(phase) => *phase == TimeoutPhase::Read,
+		UsgsError::Api { status, .. } | UsgsError::ApiStatus(status) => matches!(*status, StatusCode::SERVICE_UNAVAILABLE | StatusCode::GATEWAY_TIMEOUT),
+		_ => false,
+	}
+}
+
+/// `url` with its time range replaced by `start..end`.
+fn with_window(url: &str, start: NaiveDateTime, end: NaiveDateTime) -> Result<String, UsgsError> {
+	let mut url = Url::parse(url).map_err(|e| UsgsError::InvalidUrl(e.to_string()))?;
+	let pairs: Vec<(String, String)> = url.query_pairs()
+		.map(|(key, value)| match key.as_ref() {
+			"starttime" => (key.to_string(), format_url_time(start)),
+			"endtime" => (key.to_string(), format_url_time(end)),
+			_ => (key.to_string(), value.to_string()),
+		})
+		.collect();
+	url.query_pairs_mut().clear().extend_pairs(pairs);
+	Ok(url.to_string())
+}
+
+impl UsgsQuery<'_> {
+	/// Fetches the query, halving the time windows that time out server-side
+	/// down to `min_window`, see [`UsgsQuery::split_on_timeout`].
+	pub(crate) async fn fetch_splitting(self, min_window: Duration) -> Result<EarthquakeResponse, UsgsError> {
+		let merged = self.fetch_windows(min_window).await?;
+		Ok(self.filter(merged))
+	}
+
+	/// Merged events of the windows of [`UsgsQuery::fetch_splitting`],
+	/// before the client-side filters.
+	pub(crate) async fn fetch_windows(&self, min_window: Duration) -> Result<EarthquakeResponse, UsgsError> {
+		let url = self.build_url()?;
+		let start = self.start_time.ok_or(UsgsError::EmptyStartTime)?;
+		let mut pending = vec![(start, self.end_time)];
+		let mut merged: Option<EarthquakeResponse> = None;
+		let mut seen = HashSet::new();
+
+		while let Some((start, end)) = pending.pop() {
+			let window_url = with_window(&url, start, end)?;
+			let response = match self.fetch_body(&window_url).await.and_then(|body| self.parse_unfiltered(&body.text, &window_url)) {
+				Ok(response) => response,
+				Err(err) if is_server_timeout(&err) && end - start >= min_window * 2 => {
+					let middle = start + (end - start) / 2;
+					debug!("{} to {} timed out, retrying as two windows", start, end);
+					pending.push((middle, end));
+					pending.push((start, middle));
+					continue;
+				}
+				Err(err) => return Err(err),
+			};
+			match &mut merged {
+				None => {
+					seen.extend(response.features.iter().map(|feature| feature.id.clone()));
+					merged = Some(response);
+				}
+				Some(merged) => {
+					let features = response.features.into_iter().filter(|feature| seen.insert(feature.id.clone()));
+					merged.features.extend(features);
+				}
+			}
+		}
+
+		let mut merged = merged.expect("at least one window is fetched");
+		if let Ok(url) = Url::parse(&url) {
+			merged.metadata.url = url;
+		}
+		merged.metadata.count = merged.features.len() as u32;
+		merged.recompute_bbox();
+		self.sort_merged(&mut merged.features);
+		Ok(merged)
+	}
+
+	/// Restores the requested order of events merged from several windows.
+	fn sort_merged(&self, features: &mut [EarthquakeFeatures]) {
+		let magnitude = |feature: &EarthquakeFeatures| feature.properties.magnitude.unwrap_or(f64::NEG_INFINITY);
+		match self.order_by {
+			OrderBy::Time => features.sort_by_key(|feature| std::cmp::Reverse(feature.properties.time)),
+			OrderBy::TimeAsc => features.sort_by_key(|feature| feature.properties.time),
+			OrderBy::Magnitude => features.sort_by(|a, b| magnitude(b).total_cmp(&magnitude(a))),
+			OrderBy::MagnitudeAsc => features.sort_by(|a, b| magnitude(a).total_cmp(&magnitude(b))),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn at(year: i32, month: u32, day: u32) -> NaiveDateTime {
+		NaiveDate::from_ymd_opt(year, month, day).unwrap().and_time(NaiveTime::MIN)
+	}
+
+	#[test]
+	fn month_windows_do_not_drift_from_month_end() {
+		let windows: Vec<_> = time_windows(at(2024, 1, 31), at(2024, 6, 15), ChunkBy::Months(1)).collect();
+		assert_eq!(windows, [
+			(at(2024, 1, 31), at(2024, 2, 29)),
+			(at(2024, 2, 29), at(2024, 3, 31)),
+			(at(2024, 3, 31), at(2024, 4, 30)),
+			(at(2024, 4, 30), at(2024, 5, 31)),
+			(at(2024, 5, 31), at(2024, 6, 15)),
+		]);
+
+		let quarters: Vec<_> = time_windows(at(2023, 11, 30), at(2024, 9, 1), ChunkBy::Months(3)).map(|(_, end)| end).collect();
+		assert_eq!(quarters, [at(2024, 2, 29), at(2024, 5, 30), at(2024, 8, 30), at(2024, 9, 1)]);
+
+		let years: Vec<_> = time_windows(at(2020, 2, 29), at(2025, 1, 1), ChunkBy::Years(2)).map(|(_, end)| end).collect();
+		assert_eq!(years, [at(2022, 2, 28), at(2024, 2, 29), at(2025, 1, 1)]);
+	}
+
+	#[test]
+	fn fixed_windows_cover_the_range() {
+		let windows: Vec<_> = time_windows(at(2024, 1, 1), at(2024, 1, 4), ChunkBy::Duration(Duration::hours(36))).collect();
+		assert_eq!(windows, [
+			(at(2024, 1, 1), at(2024, 1, 2) + Duration::hours(12)),
+			(at(2024, 1, 2) + Duration::hours(12), at(2024, 1, 4)),
+		]);
+		assert_eq!(time_windows(at(2024, 1, 1), at(2024, 1, 10), ChunkBy::Days(4)).count(), 3);
+		assert_eq!(time_windows(at(2024, 1, 1), at(2024, 1, 10), ChunkBy::Days(0)).collect::<Vec<_>>(), [(at(2024, 1, 1), at(2024, 1, 10))]);
+		assert_eq!(time_windows(at(2024, 1, 10), at(2024, 1, 1), ChunkBy::Days(1)).count(), 0);
+	}
+}